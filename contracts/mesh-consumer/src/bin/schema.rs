@@ -1,5 +1,5 @@
 use cosmwasm_schema::write_api;
-use mesh_apis::ConsumerExecuteMsg;
+use mesh_apis::{ConsumerExecuteMsg, ConsumerSudoMsg};
 
 use mesh_consumer::msg::{InstantiateMsg, QueryMsg};
 
@@ -8,5 +8,6 @@ fn main() {
         instantiate: InstantiateMsg,
         execute: ConsumerExecuteMsg,
         query: QueryMsg,
+        sudo: ConsumerSudoMsg,
     }
 }
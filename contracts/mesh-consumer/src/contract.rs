@@ -1,17 +1,35 @@
 #[cfg(not(feature = "library"))]
 use cosmwasm_std::entry_point;
+
+use std::collections::BTreeMap;
+
 use cosmwasm_std::{
-    to_binary, Binary, Deps, DepsMut, Env, IbcMsg, MessageInfo, Response, StdResult,
+    coin, to_binary, Binary, Decimal, Deps, DepsMut, Env, IbcMsg, MessageInfo, Order, Reply,
+    Response, StdError, StdResult, SubMsg, SubMsgResult, Uint128, WasmMsg,
 };
 use cw2::set_contract_version;
+use cw_storage_plus::Bound;
 
-use mesh_apis::ConsumerExecuteMsg;
-use mesh_ibc::ConsumerMsg;
+use mesh_apis::{ConsumerExecuteMsg, ConsumerSudoMsg, IbcLifecycleComplete, StakingExecuteMsg};
+use mesh_ibc::{ConsumerMsg, RewardsEntry, StdAck};
 
 use crate::error::ContractError;
-use crate::ibc::build_timeout;
-use crate::msg::{InstantiateMsg, QueryMsg};
-use crate::state::{Config, CHANNEL, CONFIG, PACKET_LIFETIME};
+use crate::ibc::{
+    build_rewards_transfer, build_timeout, build_valset_updates, exchange_rate,
+    parse_transfer_sequence, provider_stake, queue_slash, update_valset, validator_stakes,
+    ValsetUpdate, STAKE_REPLY_ID, TRANSFER_REPLY_ID, UNSTAKE_REPLY_ID,
+};
+use crate::msg::{
+    ChannelResponse, InstantiateMsg, ListVirtualStakeResponse, PendingRewardsResponse,
+    ProviderResponse, ProvidersResponse, QueryMsg, UnsentReward, UnsentRewardsResponse,
+    VirtualStakeResponse,
+};
+use crate::state::{
+    Config, Position, PriceFeed, Provider, RewardsTransfer, BATCH_REWARDS, CHANNELS, CONFIG,
+    LAST_BATCH, LAST_REBALANCE, PACKET_LIFETIME, PENDING_CHANGE, PENDING_REWARDS,
+    PENDING_TRANSFERS, POSITIONS, PROVIDERS, TRANSFERS, UNSENT_REWARDS, UNSENT_SLASHES,
+    UNSENT_TRANSFERS,
+};
 
 // version info for migration info
 const CONTRACT_NAME: &str = "crates.io:mesh-consumer";
@@ -30,11 +48,42 @@ pub fn instantiate(
     let meta_staking_contract_address =
         deps.api.addr_validate(&msg.meta_staking_contract_address)?;
 
+    if msg.providers.is_empty() {
+        return Err(ContractError::NoProviders {});
+    }
+    for info in msg.providers {
+        if PROVIDERS.has(deps.storage, &info.connection_id) {
+            return Err(ContractError::DuplicateProvider(info.connection_id));
+        }
+        let price_feed = match info.price_feed {
+            Some(feed) => {
+                if feed.discount >= Decimal::one() {
+                    return Err(ContractError::InvalidDiscount {});
+                }
+                Some(PriceFeed {
+                    oracle: deps.api.addr_validate(&feed.oracle)?,
+                    discount: feed.discount,
+                    max_staleness: feed.max_staleness,
+                })
+            }
+            None => None,
+        };
+        let provider = Provider {
+            port_id: info.port_id,
+            address: info.address,
+            address_from_port: info.address_from_port,
+            ics20_channel: info.ics20_channel,
+            price_feed,
+            max_cap: info.max_cap,
+        };
+        PROVIDERS.save(deps.storage, &info.connection_id, &provider)?;
+    }
+
     let config = Config {
         meta_staking_contract_address,
-        provider: msg.provider,
         remote_to_local_exchange_rate: msg.remote_to_local_exchange_rate,
-        ics20_channel: msg.ics20_channel,
+        rebalance: msg.rebalance,
+        rewards_batch_interval: msg.rewards_batch_interval,
     };
 
     // Set packet lifetime from msg or set default
@@ -60,48 +109,632 @@ pub fn execute(
         ConsumerExecuteMsg::MeshConsumerRecieveRewardsMsg { validator } => {
             execute_receive_rewards(deps, env, info, validator)
         }
+        ConsumerExecuteMsg::Rebalance {} => execute_rebalance(deps, env),
+        ConsumerExecuteMsg::SyncValidators {} => execute_sync_validators(deps, env),
+        ConsumerExecuteMsg::RetryRewards {} => execute_retry_rewards(deps, env),
+        ConsumerExecuteMsg::RetrySlashes {} => execute_retry_slashes(deps, env),
+        ConsumerExecuteMsg::SendBatchRewards {} => execute_send_batch_rewards(deps, env),
     }
 }
 
-// We receive the rewards as funds from meta-stacking, and send it over IBC to mesh-provider
+// We receive the rewards as funds from meta-stacking, and send each provider its share over IBC
+// to mesh-provider
 pub fn execute_receive_rewards(
     deps: DepsMut,
     env: Env,
     info: MessageInfo,
     validator: String,
 ) -> Result<Response, ContractError> {
-    let channel_id = CHANNEL.load(deps.storage)?;
+    let funds = info.funds[0].clone();
+    let batch = CONFIG.load(deps.storage)?.rewards_batch_interval.is_some();
+    let timeout = build_timeout(deps.as_ref(), &env)?;
+
+    // split by the local stake each provider has on the validator
+    let stakes = validator_stakes(deps.storage, &validator)?;
+    let total: Uint128 = stakes.iter().map(|(_, local)| *local).sum();
+    if total.is_zero() {
+        return Err(ContractError::NoVirtualStake(validator));
+    }
+
+    let mut left = funds.amount;
+    let mut msgs = vec![];
+    for (i, (provider, local)) in stakes.iter().enumerate() {
+        // the last provider gets the rounding remainder
+        let amount = if i + 1 == stakes.len() {
+            left
+        } else {
+            funds.amount.multiply_ratio(*local, total)
+        };
+        left -= amount;
+        if amount.is_zero() {
+            continue;
+        }
+        let denom = funds.denom.as_str();
+        PENDING_REWARDS.update::<_, StdError>(deps.storage, (provider, denom), |pending| {
+            Ok(pending.unwrap_or_default() + amount)
+        })?;
 
-    let coin = info.funds[0].clone();
+        if batch {
+            let key = (provider.as_str(), validator.as_str(), denom);
+            BATCH_REWARDS.update::<_, StdError>(deps.storage, key, |batch| {
+                Ok(batch.unwrap_or_default() + amount)
+            })?;
+            continue;
+        }
+        match CHANNELS.may_load(deps.storage, provider)? {
+            Some(channel) => msgs.push(IbcMsg::SendPacket {
+                channel_id: channel.channel_id,
+                data: to_binary(&ConsumerMsg::Rewards {
+                    validator: validator.clone(),
+                    total_funds: coin(amount.u128(), denom),
+                })?,
+                timeout: timeout.clone(),
+            }),
+            // sent with RetryRewards once the provider reconnects
+            None => {
+                let key = (provider.as_str(), validator.as_str(), denom);
+                UNSENT_REWARDS.update::<_, StdError>(deps.storage, key, |unsent| {
+                    Ok(unsent.unwrap_or_default() + amount)
+                })?;
+            }
+        }
+    }
+
+    let res = Response::new().add_messages(msgs);
+    if batch {
+        return Ok(res.add_attribute("action", "batch_rewards"));
+    }
+    Ok(res)
+}
+
+pub fn execute_send_batch_rewards(deps: DepsMut, env: Env) -> Result<Response, ContractError> {
+    let interval = CONFIG
+        .load(deps.storage)?
+        .rewards_batch_interval
+        .ok_or(ContractError::BatchRewardsDisabled {})?;
+    if let Some(last) = LAST_BATCH.may_load(deps.storage)? {
+        let next = last.plus_seconds(interval);
+        if env.block.time < next {
+            return Err(ContractError::BatchRewardsTooSoon(next));
+        }
+    }
 
-    let msg = IbcMsg::SendPacket {
-        channel_id,
-        data: to_binary(&ConsumerMsg::Rewards {
+    let rewards = BATCH_REWARDS
+        .range(deps.storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<_>>>()?;
+    let mut batches: BTreeMap<String, Vec<RewardsEntry>> = BTreeMap::new();
+    for ((provider, validator, denom), amount) in rewards {
+        batches.entry(provider).or_default().push(RewardsEntry {
             validator,
-            total_funds: coin,
-        })?,
-        timeout: build_timeout(deps.as_ref(), &env)?,
+            amount: coin(amount.u128(), denom),
+        });
+    }
+
+    // one packet per provider, the entries of disconnected providers wait for a later batch
+    let timeout = build_timeout(deps.as_ref(), &env)?;
+    let mut msgs = vec![];
+    for (provider, entries) in batches {
+        let channel = match CHANNELS.may_load(deps.storage, &provider)? {
+            Some(channel) => channel,
+            None => continue,
+        };
+        for entry in &entries {
+            let key = (
+                provider.as_str(),
+                entry.validator.as_str(),
+                entry.amount.denom.as_str(),
+            );
+            BATCH_REWARDS.remove(deps.storage, key);
+        }
+        msgs.push(IbcMsg::SendPacket {
+            channel_id: channel.channel_id,
+            data: to_binary(&ConsumerMsg::BatchRewards { entries })?,
+            timeout: timeout.clone(),
+        });
+    }
+
+    let res = Response::new().add_attribute("action", "send_batch_rewards");
+    if msgs.is_empty() {
+        return Ok(res);
+    }
+    LAST_BATCH.save(deps.storage, &env.block.time)?;
+    Ok(res.add_messages(msgs))
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn reply(deps: DepsMut, _env: Env, reply: Reply) -> Result<Response, ContractError> {
+    match reply.id {
+        STAKE_REPLY_ID => reply_stake(deps, true, reply.result),
+        UNSTAKE_REPLY_ID => reply_stake(deps, false, reply.result),
+        TRANSFER_REPLY_ID => reply_transfer(deps, reply.result),
+        id => Err(ContractError::InvalidReplyId(id)),
+    }
+}
+
+pub fn reply_stake(
+    deps: DepsMut,
+    staked: bool,
+    result: SubMsgResult,
+) -> Result<Response, ContractError> {
+    let err = match result {
+        SubMsgResult::Err(err) => err,
+        SubMsgResult::Ok(_) => return Ok(Response::new()),
     };
 
-    Ok(Response::default().add_message(msg))
+    // undo the position change of the packet
+    let change = PENDING_CHANGE.load(deps.storage)?;
+    PENDING_CHANGE.remove(deps.storage);
+    let key = (change.provider.as_str(), change.validator.as_str());
+    POSITIONS.update::<_, StdError>(deps.storage, key, |position| {
+        let mut position = position.unwrap_or_default();
+        if staked {
+            position.remote -= change.remote;
+            position.local -= change.local;
+        } else {
+            position.remote += change.remote;
+            position.local += change.local;
+        }
+        Ok(position)
+    })?;
+    let err = if staked {
+        ContractError::DelegateFailed(err)
+    } else {
+        ContractError::UndelegateFailed(err)
+    };
+
+    // the data of the reply becomes the ack of the packet
+    Ok(Response::new()
+        .set_data(StdAck::fail(err.to_string()))
+        .add_attribute("error", err.to_string()))
+}
+
+// Remember the sequence of the reward transfer, to requeue it if it fails
+pub fn reply_transfer(deps: DepsMut, result: SubMsgResult) -> Result<Response, ContractError> {
+    let transfer = PENDING_TRANSFERS
+        .pop_front(deps.storage)?
+        .ok_or_else(|| StdError::not_found("pending transfer"))?;
+    let sequence = result
+        .into_result()
+        .ok()
+        .and_then(|res| res.data)
+        .and_then(|data| parse_transfer_sequence(&data));
+    let res = Response::new().add_attribute("action", "reply_transfer");
+    match sequence {
+        Some(sequence) => {
+            let channel = PROVIDERS
+                .load(deps.storage, &transfer.provider)?
+                .ics20_channel;
+            TRANSFERS.save(deps.storage, (&channel, sequence), &transfer)?;
+            Ok(res.add_attribute("sequence", sequence.to_string()))
+        }
+        // without a sequence we can't match the transfer result, so we can't requeue it
+        None => Ok(res.add_attribute("sequence", "unknown")),
+    }
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
-pub fn query(_deps: Deps, _env: Env, _msg: QueryMsg) -> StdResult<Binary> {
-    unimplemented!();
+pub fn sudo(deps: DepsMut, env: Env, msg: ConsumerSudoMsg) -> Result<Response, ContractError> {
+    match msg {
+        ConsumerSudoMsg::IbcLifecycleComplete(IbcLifecycleComplete::IbcAck {
+            channel,
+            sequence,
+            success,
+            ..
+        }) => sudo_transfer_result(deps, channel, sequence, success),
+        ConsumerSudoMsg::IbcLifecycleComplete(IbcLifecycleComplete::IbcTimeout {
+            channel,
+            sequence,
+        }) => sudo_transfer_result(deps, channel, sequence, false),
+        ConsumerSudoMsg::Slash {
+            validator,
+            percentage,
+            infraction_height,
+            tombstone,
+        } => sudo_slash(
+            deps,
+            env,
+            validator,
+            percentage,
+            infraction_height,
+            tombstone,
+        ),
+    }
+}
+
+// Forward a slash on this chain to the providers, so they slash the cross-stake as well
+pub fn sudo_slash(
+    deps: DepsMut,
+    env: Env,
+    validator: String,
+    percentage: Decimal,
+    infraction_height: u64,
+    tombstone: bool,
+) -> Result<Response, ContractError> {
+    if percentage.is_zero() || percentage > Decimal::one() {
+        return Err(ContractError::InvalidSlashPercentage(percentage));
+    }
+    let data = to_binary(&ConsumerMsg::Slash {
+        validator: validator.clone(),
+        percentage,
+        infraction_height,
+        tombstone,
+    })?;
+    let timeout = build_timeout(deps.as_ref(), &env)?;
+    let providers = PROVIDERS
+        .keys(deps.storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<_>>>()?;
+    let mut msgs = vec![];
+    for provider in providers {
+        // a disconnected provider gets it once it reconnects
+        match CHANNELS.may_load(deps.storage, &provider)? {
+            Some(channel) => msgs.push(IbcMsg::SendPacket {
+                channel_id: channel.channel_id,
+                data: data.clone(),
+                timeout: timeout.clone(),
+            }),
+            None => queue_slash(deps.storage, &provider, data.clone())?,
+        }
+    }
+
+    Ok(Response::new()
+        .add_messages(msgs)
+        .add_attribute("action", "slash")
+        .add_attribute("validator", validator))
+}
+
+// A failed reward transfer was refunded to us, queue it for RetryRewards
+pub fn sudo_transfer_result(
+    deps: DepsMut,
+    channel: String,
+    sequence: u64,
+    success: bool,
+) -> Result<Response, ContractError> {
+    let transfer = match TRANSFERS.may_load(deps.storage, (&channel, sequence))? {
+        Some(transfer) => transfer,
+        // not one of our reward transfers
+        None => return Ok(Response::new()),
+    };
+    TRANSFERS.remove(deps.storage, (&channel, sequence));
+
+    let res = Response::new()
+        .add_attribute("action", "transfer_result")
+        .add_attribute("channel", channel)
+        .add_attribute("sequence", sequence.to_string())
+        .add_attribute("success", success.to_string());
+    if success {
+        return Ok(res);
+    }
+    let RewardsTransfer { provider, amount } = transfer;
+    let key = (provider.as_str(), amount.denom.as_str());
+    UNSENT_TRANSFERS.update::<_, StdError>(deps.storage, key, |unsent| {
+        Ok(unsent.unwrap_or_default() + amount.amount)
+    })?;
+    PENDING_REWARDS.update::<_, StdError>(deps.storage, key, |pending| {
+        Ok(pending.unwrap_or_default() + amount.amount)
+    })?;
+    Ok(res)
+}
+
+// how many rewards packets and transfers we resend in one RetryRewards call
+const RETRY_REWARDS_BATCH: usize = 30;
+
+/// Resends failed rewards packets, and failed transfers of rewards the provider already
+/// accounted for. Rewards of disconnected providers wait until they reconnect.
+pub fn execute_retry_rewards(mut deps: DepsMut, env: Env) -> Result<Response, ContractError> {
+    let channels = CHANNELS
+        .range(deps.storage, None, None, Order::Ascending)
+        .collect::<StdResult<BTreeMap<_, _>>>()?;
+    let timeout = build_timeout(deps.as_ref(), &env)?;
+
+    let rewards = UNSENT_REWARDS
+        .range(deps.storage, None, None, Order::Ascending)
+        .filter(|r| matches!(r, Ok(((provider, _, _), _)) if channels.contains_key(provider)))
+        .take(RETRY_REWARDS_BATCH)
+        .collect::<StdResult<Vec<_>>>()?;
+    let mut msgs = Vec::with_capacity(rewards.len());
+    for ((provider, validator, denom), amount) in rewards {
+        UNSENT_REWARDS.remove(deps.storage, (&provider, &validator, &denom));
+        let msg = IbcMsg::SendPacket {
+            channel_id: channels[&provider].channel_id.clone(),
+            data: to_binary(&ConsumerMsg::Rewards {
+                validator,
+                total_funds: coin(amount.u128(), denom),
+            })?,
+            timeout: timeout.clone(),
+        };
+        msgs.push(SubMsg::new(msg));
+    }
+
+    let transfers = UNSENT_TRANSFERS
+        .range(deps.storage, None, None, Order::Ascending)
+        .filter(|r| matches!(r, Ok(((provider, _), _)) if channels.contains_key(provider)))
+        .take(RETRY_REWARDS_BATCH)
+        .collect::<StdResult<Vec<_>>>()?;
+    for ((provider, denom), amount) in transfers {
+        UNSENT_TRANSFERS.remove(deps.storage, (&provider, &denom));
+        msgs.push(build_rewards_transfer(
+            deps.branch(),
+            &env,
+            &provider,
+            coin(amount.u128(), denom),
+        )?);
+    }
+
+    Ok(Response::new()
+        .add_submessages(msgs)
+        .add_attribute("action", "retry_rewards"))
+}
+
+/// Resends the queued slashes of connected providers, the others get them when they reconnect
+pub fn execute_retry_slashes(deps: DepsMut, env: Env) -> Result<Response, ContractError> {
+    let channels = CHANNELS
+        .range(deps.storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<_>>>()?;
+    let timeout = build_timeout(deps.as_ref(), &env)?;
+
+    let mut msgs = vec![];
+    for (provider, channel) in channels {
+        let slashes = UNSENT_SLASHES
+            .may_load(deps.storage, &provider)?
+            .unwrap_or_default();
+        UNSENT_SLASHES.remove(deps.storage, &provider);
+        msgs.extend(slashes.into_iter().map(|data| IbcMsg::SendPacket {
+            channel_id: channel.channel_id.clone(),
+            data,
+            timeout: timeout.clone(),
+        }));
+    }
+
+    Ok(Response::new()
+        .add_messages(msgs)
+        .add_attribute("action", "retry_slashes"))
+}
+
+/// Diffs the active set against the last one reported and sends the changes to the providers.
+pub fn execute_sync_validators(mut deps: DepsMut, env: Env) -> Result<Response, ContractError> {
+    let validators = deps
+        .querier
+        .query_all_validators()?
+        .into_iter()
+        .map(|v| v.address)
+        .collect();
+    let ValsetUpdate {
+        epoch,
+        added,
+        removed,
+        ..
+    } = update_valset(deps.storage, validators)?;
+
+    // also resends the changes that failed before
+    let msgs = build_valset_updates(deps.branch(), &env, added, removed, epoch, None)?;
+    Ok(Response::new()
+        .add_messages(msgs)
+        .add_attribute("action", "sync_validators")
+        .add_attribute("epoch", epoch.to_string()))
+}
+
+/// Moves the local stake on every validator towards the value of the provider stake at the
+/// current price, by at most `max_adjustment` of it and never above the provider's `max_cap`.
+/// Only providers with a price feed are revalued, the ones with a stale price are skipped.
+pub fn execute_rebalance(deps: DepsMut, env: Env) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    let providers: Vec<_> = PROVIDERS
+        .range(deps.storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<_>>>()?
+        .into_iter()
+        .filter(|(_, provider)| provider.price_feed.is_some())
+        .collect();
+    let rebalance = match (providers.is_empty(), &config.rebalance) {
+        (false, Some(rebalance)) => rebalance,
+        _ => return Err(ContractError::RebalanceDisabled {}),
+    };
+    if let Some(last) = LAST_REBALANCE.may_load(deps.storage)? {
+        let next = last.plus_seconds(rebalance.epoch);
+        if env.block.time < next {
+            return Err(ContractError::RebalanceTooSoon(next));
+        }
+    }
+    LAST_REBALANCE.save(deps.storage, &env.block.time)?;
+
+    let mut msgs = vec![];
+    let mut res = Response::new().add_attribute("action", "rebalance");
+    for (provider_id, provider) in providers {
+        // one bad oracle must not block the others
+        let rate = match exchange_rate(deps.as_ref(), &env, &config, &provider) {
+            Ok(rate) => rate,
+            Err(err) => {
+                res = res
+                    .add_attribute("skipped_provider", &provider_id)
+                    .add_attribute("error", err.to_string());
+                continue;
+            }
+        };
+        res = res
+            .add_attribute("provider", &provider_id)
+            .add_attribute("exchange_rate", rate.to_string());
+
+        let mut total = provider_stake(deps.storage, &provider_id)?;
+
+        let positions = POSITIONS
+            .prefix(&provider_id)
+            .range(deps.storage, None, None, Order::Ascending)
+            .collect::<StdResult<Vec<_>>>()?;
+        for (validator, mut position) in positions {
+            let target = position.remote * rate;
+            let max = position.local * rebalance.max_adjustment;
+            let delta = if target > position.local {
+                target - position.local
+            } else {
+                position.local - target
+            };
+            let mut amount = delta.min(max);
+            if target > position.local {
+                if let Some(cap) = provider.max_cap {
+                    amount = amount.min(cap.saturating_sub(total));
+                }
+            }
+            if amount.is_zero() {
+                continue;
+            }
+
+            let msg = if target > position.local {
+                position.local += amount;
+                total += amount;
+                StakingExecuteMsg::Delegate {
+                    validator: validator.clone(),
+                    amount,
+                }
+            } else {
+                position.local -= amount;
+                total -= amount;
+                StakingExecuteMsg::Undelegate {
+                    validator: validator.clone(),
+                    amount,
+                }
+            };
+            POSITIONS.save(deps.storage, (&provider_id, &validator), &position)?;
+            msgs.push(WasmMsg::Execute {
+                contract_addr: config.meta_staking_contract_address.to_string(),
+                msg: to_binary(&msg)?,
+                funds: vec![],
+            });
+        }
+    }
+
+    Ok(res.add_messages(msgs))
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
+    match msg {
+        QueryMsg::Config {} => to_binary(&CONFIG.load(deps.storage)?),
+        QueryMsg::Providers {} => to_binary(&query_providers(deps)?),
+        QueryMsg::Channel { provider } => to_binary(&query_channel(deps, provider)?),
+        QueryMsg::VirtualStake {
+            provider,
+            validator,
+        } => to_binary(&query_virtual_stake(deps, provider, validator)?),
+        QueryMsg::ListVirtualStake {
+            provider,
+            start_after,
+            limit,
+        } => to_binary(&list_virtual_stake(deps, provider, start_after, limit)?),
+        QueryMsg::PendingRewards { provider } => to_binary(&query_pending_rewards(deps, provider)?),
+        QueryMsg::UnsentRewards { provider } => to_binary(&query_unsent_rewards(deps, provider)?),
+    }
+}
+
+pub fn query_providers(deps: Deps) -> StdResult<ProvidersResponse> {
+    let providers = PROVIDERS
+        .range(deps.storage, None, None, Order::Ascending)
+        .map(|r| {
+            let (connection_id, provider) = r?;
+            Ok(ProviderResponse {
+                channel: CHANNELS.may_load(deps.storage, &connection_id)?,
+                connection_id,
+                provider,
+            })
+        })
+        .collect::<StdResult<_>>()?;
+    Ok(ProvidersResponse { providers })
+}
+
+pub fn query_channel(deps: Deps, provider: String) -> StdResult<ChannelResponse> {
+    Ok(ChannelResponse {
+        channel: CHANNELS
+            .may_load(deps.storage, &provider)?
+            .map(|channel| channel.channel_id),
+        ics20_channel: PROVIDERS.load(deps.storage, &provider)?.ics20_channel,
+        packet_lifetime: PACKET_LIFETIME.load(deps.storage)?,
+    })
+}
+
+fn build_virtual_stake(validator: String, position: Position) -> VirtualStakeResponse {
+    VirtualStakeResponse {
+        validator,
+        remote: position.remote,
+        local: position.local,
+        rate: position.rate(),
+    }
+}
+
+pub fn query_virtual_stake(
+    deps: Deps,
+    provider: String,
+    validator: String,
+) -> StdResult<VirtualStakeResponse> {
+    let position = POSITIONS
+        .may_load(deps.storage, (&provider, &validator))?
+        .unwrap_or_default();
+    Ok(build_virtual_stake(validator, position))
+}
+
+const MAX_LIMIT: u32 = 100;
+const DEFAULT_LIMIT: u32 = 30;
+
+pub fn list_virtual_stake(
+    deps: Deps,
+    provider: String,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<ListVirtualStakeResponse> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.as_ref().map(|x| Bound::exclusive(x.as_str()));
+
+    let stakes = POSITIONS
+        .prefix(&provider)
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|r| r.map(|(validator, position)| build_virtual_stake(validator, position)))
+        .collect::<StdResult<Vec<_>>>()?;
+
+    Ok(ListVirtualStakeResponse { stakes })
+}
+
+pub fn query_pending_rewards(deps: Deps, provider: String) -> StdResult<PendingRewardsResponse> {
+    let rewards = PENDING_REWARDS
+        .prefix(&provider)
+        .range(deps.storage, None, None, Order::Ascending)
+        .map(|r| r.map(|(denom, amount)| coin(amount.u128(), denom)))
+        .collect::<StdResult<Vec<_>>>()?;
+    Ok(PendingRewardsResponse { rewards })
+}
+
+pub fn query_unsent_rewards(deps: Deps, provider: String) -> StdResult<UnsentRewardsResponse> {
+    let rewards = UNSENT_REWARDS
+        .sub_prefix(&provider)
+        .range(deps.storage, None, None, Order::Ascending)
+        .map(|r| {
+            r.map(|((validator, denom), amount)| UnsentReward {
+                validator,
+                amount: coin(amount.u128(), denom),
+            })
+        })
+        .collect::<StdResult<_>>()?;
+    let transfers = UNSENT_TRANSFERS
+        .prefix(&provider)
+        .range(deps.storage, None, None, Order::Ascending)
+        .map(|r| r.map(|(denom, amount)| coin(amount.u128(), denom)))
+        .collect::<StdResult<_>>()?;
+    Ok(UnsentRewardsResponse { rewards, transfers })
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::msg::ProviderInfo;
+    use cosmwasm_std::coins;
     use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info};
-    use cosmwasm_std::{coins, Decimal};
 
     fn provider_info() -> ProviderInfo {
         ProviderInfo {
             port_id: "port-1".to_string(),
             connection_id: "conn-2".to_string(),
+            address: Some("provider".to_string()),
+            address_from_port: false,
+            ics20_channel: "channel-10".to_string(),
+            price_feed: None,
+            max_cap: None,
         }
     }
 
@@ -111,10 +744,11 @@ mod tests {
 
         let msg = InstantiateMsg {
             meta_staking_contract_address: "meta_staking".to_string(),
-            provider: provider_info(),
+            providers: vec![provider_info()],
             remote_to_local_exchange_rate: Decimal::percent(10),
-            ics20_channel: "channel-10".to_string(),
             packet_lifetime: None,
+            rebalance: None,
+            rewards_batch_interval: None,
         };
         let info = mock_info("creator", &coins(1000, "earth"));
 
@@ -1,4 +1,4 @@
-use cosmwasm_std::StdError;
+use cosmwasm_std::{Decimal, StdError, Timestamp, Uint128};
 use thiserror::Error;
 
 use mesh_ibc::MeshSecurityError;
@@ -17,10 +17,22 @@ pub enum ContractError {
     #[error("Couldn't parse provider from port_id")]
     ProviderAddrParsing {},
 
-    #[error("Contract already has a bound channel: {0}")]
+    #[error("No provider address, set it or allow taking it from the port")]
+    NoProviderAddr {},
+
+    #[error("Provider port doesn't belong to provider address '{0}'")]
+    WrongProviderAddr(String),
+
+    #[error("No provider chains given")]
+    NoProviders {},
+
+    #[error("Provider on connection '{0}' is listed twice")]
+    DuplicateProvider(String),
+
+    #[error("Provider already has a bound channel: {0}")]
     ChannelExists(String),
 
-    #[error("Unauthorized counterparty chain, awaiting connection '{0}'")]
+    #[error("Unauthorized counterparty chain, no provider on connection '{0}'")]
     WrongConnection(String),
 
     #[error("Unauthorized counterparty port, awaiting port '{0}'")]
@@ -32,12 +44,42 @@ pub enum ContractError {
     #[error("Custom Error val: {val:?}")]
     CustomError { val: String },
 
-    #[error("Acknowledgement failed")]
-    AckFailed {},
+    #[error("Discount must be below 100%")]
+    InvalidDiscount {},
+
+    #[error("Oracle price is too old, last updated at {0}")]
+    StalePrice(Timestamp),
+
+    #[error("Provider reached its max cap of {0} delegated")]
+    MaxCapReached(Uint128),
+
+    #[error("Slash percentage must be above 0% and at most 100%, got {0}")]
+    InvalidSlashPercentage(Decimal),
+
+    #[error("Provider only has {0} staked on this validator")]
+    InsufficientStake(Uint128),
+
+    #[error("No virtual stake on validator '{0}' to reward")]
+    NoVirtualStake(String),
+
+    #[error("Rebalance needs a price feed and rebalance limits")]
+    RebalanceDisabled {},
+
+    #[error("Already rebalanced this epoch, next rebalance at {0}")]
+    RebalanceTooSoon(Timestamp),
+
+    #[error("Meta-staking failed to delegate: {0}")]
+    DelegateFailed(String),
+
+    #[error("Meta-staking failed to undelegate: {0}")]
+    UndelegateFailed(String),
+
+    #[error("Invalid reply id: {0}")]
+    InvalidReplyId(u64),
 
-    #[error("Rewards acknowledgement failed")]
-    RewardsFailed {},
+    #[error("Rewards are not batched")]
+    BatchRewardsDisabled {},
 
-    #[error("Update validators acknowledgement failed")]
-    UpdateValidatorsFailed {},
+    #[error("Already sent a batch this interval, next batch at {0}")]
+    BatchRewardsTooSoon(Timestamp),
 }
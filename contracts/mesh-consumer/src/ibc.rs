@@ -1,17 +1,34 @@
 #[cfg(not(feature = "library"))]
 use cosmwasm_std::entry_point;
 
+use std::collections::BTreeMap;
+
 use cosmwasm_std::{
-    from_slice, to_binary, Coin, Deps, DepsMut, Env, Ibc3ChannelOpenResponse, IbcBasicResponse,
-    IbcChannelCloseMsg, IbcChannelConnectMsg, IbcChannelOpenMsg, IbcMsg, IbcPacketAckMsg,
-    IbcPacketReceiveMsg, IbcPacketTimeoutMsg, IbcReceiveResponse, IbcTimeout, Uint128, WasmMsg,
+    from_slice, to_binary, Binary, Coin, CosmosMsg, Decimal, Deps, DepsMut, Env,
+    Ibc3ChannelOpenResponse, IbcBasicResponse, IbcChannelCloseMsg, IbcChannelConnectMsg,
+    IbcChannelOpenMsg, IbcMsg, IbcPacketAckMsg, IbcPacketReceiveMsg, IbcPacketTimeoutMsg,
+    IbcReceiveResponse, IbcTimeout, Order, StdError, StdResult, Storage, SubMsg, Uint128,
+    Validator, WasmMsg,
 };
 
-use mesh_apis::StakingExecuteMsg;
-use mesh_ibc::{check_order, check_version, ConsumerMsg, ProviderMsg, StdAck};
+use mesh_apis::{Delegation, PriceFeedQueryMsg, PriceResponse, StakingExecuteMsg, StakingQueryMsg};
+use mesh_ibc::{
+    check_order, check_version, ConsumerMsg, ProviderMsg, RewardsEntry, StdAck, SyncStateResponse,
+    ValidatorMetadata, ValidatorStake,
+};
 
 use crate::error::ContractError;
-use crate::state::{CHANNEL, CONFIG, PACKET_LIFETIME};
+use crate::state::{
+    Config, PendingChange, Provider, ProviderChannel, RewardsTransfer, ValsetDiff, CHANNELS,
+    CHANNEL_PROVIDERS, CONFIG, PACKET_LIFETIME, PENDING_CHANGE, PENDING_REWARDS, PENDING_TRANSFERS,
+    POSITIONS, PROVIDERS, UNSENT_REWARDS, UNSENT_SLASHES, UNSENT_VALSET, VALSET, VALSET_EPOCH,
+};
+
+// replies to failed meta-staking calls from stake packets
+pub const STAKE_REPLY_ID: u64 = 1;
+pub const UNSTAKE_REPLY_ID: u64 = 2;
+// reply to the ICS20 transfer of rewards, with its packet sequence
+pub const TRANSFER_REPLY_ID: u64 = 3;
 
 pub fn build_timeout(deps: Deps, env: &Env) -> Result<IbcTimeout, ContractError> {
     let packet_time = PACKET_LIFETIME.load(deps.storage)?;
@@ -19,6 +36,25 @@ pub fn build_timeout(deps: Deps, env: &Env) -> Result<IbcTimeout, ContractError>
     Ok(IbcTimeout::with_timestamp(time))
 }
 
+/// The provider (connection id) a channel of ours belongs to, also after it closed
+pub fn channel_provider(storage: &dyn Storage, channel_id: &str) -> Result<String, ContractError> {
+    CHANNEL_PROVIDERS
+        .may_load(storage, channel_id)?
+        .ok_or_else(|| ContractError::UnknownChannel(channel_id.to_string()))
+}
+
+/// The provider a channel belongs to, if it is still the open channel of that provider
+pub fn connected_provider(
+    storage: &dyn Storage,
+    channel_id: &str,
+) -> Result<String, ContractError> {
+    let provider = channel_provider(storage, channel_id)?;
+    match CHANNELS.may_load(storage, &provider)? {
+        Some(channel) if channel.channel_id == channel_id => Ok(provider),
+        _ => Err(ContractError::UnknownChannel(channel_id.to_string())),
+    }
+}
+
 #[cfg_attr(not(feature = "library"), entry_point)]
 /// enforces ordering and versioning constraints
 pub fn ibc_channel_open(
@@ -26,13 +62,13 @@ pub fn ibc_channel_open(
     _env: Env,
     msg: IbcChannelOpenMsg,
 ) -> Result<Option<Ibc3ChannelOpenResponse>, ContractError> {
-    // ensure we have no other channels currently
-    if let Some(chan) = CHANNEL.may_load(deps.storage)? {
-        return Err(ContractError::ChannelExists(chan));
+    // ensure the provider has no other channels currently
+    let channel = msg.channel();
+    if let Some(chan) = CHANNELS.may_load(deps.storage, &channel.connection_id)? {
+        return Err(ContractError::ChannelExists(chan.channel_id));
     }
 
     // check the handshake order/version is correct
-    let channel = msg.channel();
     check_order(&channel.order)?;
     check_version(&channel.version)?;
     if let Some(counter_version) = msg.counterparty_version() {
@@ -40,13 +76,12 @@ pub fn ibc_channel_open(
     }
 
     // ensure the remote connection / port is authorized
-    let cfg = CONFIG.load(deps.storage)?;
-    if cfg.provider.connection_id != channel.connection_id {
-        return Err(ContractError::WrongConnection(cfg.provider.connection_id));
-    }
+    let provider = PROVIDERS
+        .may_load(deps.storage, &channel.connection_id)?
+        .ok_or_else(|| ContractError::WrongConnection(channel.connection_id.clone()))?;
 
-    if cfg.provider.port_id != channel.counterparty_endpoint.port_id {
-        return Err(ContractError::WrongPort(cfg.provider.port_id));
+    if provider.port_id != channel.counterparty_endpoint.port_id {
+        return Err(ContractError::WrongPort(provider.port_id));
     }
 
     Ok(None)
@@ -55,19 +90,70 @@ pub fn ibc_channel_open(
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn ibc_channel_connect(
     deps: DepsMut,
-    _env: Env,
+    env: Env,
     msg: IbcChannelConnectMsg,
 ) -> Result<IbcBasicResponse, ContractError> {
     let channel = msg.channel();
     let channel_id = &channel.endpoint.channel_id;
+    let connection_id = &channel.connection_id;
+
+    if let Some(chan) = CHANNELS.may_load(deps.storage, connection_id)? {
+        return Err(ContractError::ChannelExists(chan.channel_id));
+    }
+    let provider = PROVIDERS
+        .may_load(deps.storage, connection_id)?
+        .ok_or_else(|| ContractError::WrongConnection(connection_id.clone()))?;
+    let recipient = provider_address(&provider, &channel.counterparty_endpoint.port_id)?;
 
     // save the channel id for future use
-    match CHANNEL.may_load(deps.storage)? {
-        Some(chan) => return Err(ContractError::ChannelExists(chan)),
-        None => CHANNEL.save(deps.storage, channel_id)?,
+    let provider_channel = ProviderChannel {
+        channel_id: channel_id.clone(),
+        recipient: recipient.clone(),
     };
+    CHANNELS.save(deps.storage, connection_id, &provider_channel)?;
+    CHANNEL_PROVIDERS.save(deps.storage, channel_id, connection_id)?;
+
+    // slashes that couldn't reach the provider while it was disconnected
+    let slashes = UNSENT_SLASHES
+        .may_load(deps.storage, connection_id)?
+        .unwrap_or_default();
+    UNSENT_SLASHES.remove(deps.storage, connection_id);
+    let timeout = build_timeout(deps.as_ref(), &env)?;
+    let msgs: Vec<_> = slashes
+        .into_iter()
+        .map(|data| IbcMsg::SendPacket {
+            channel_id: channel_id.clone(),
+            data,
+            timeout: timeout.clone(),
+        })
+        .collect();
 
-    Ok(IbcBasicResponse::new())
+    Ok(IbcBasicResponse::new()
+        .add_messages(msgs)
+        .add_attribute("provider", recipient)
+        .add_attribute("connection_id", connection_id))
+}
+
+/// The provider contract address, as configured or taken from its port if allowed
+pub fn provider_address(provider: &Provider, port_id: &str) -> Result<String, ContractError> {
+    let from_port = port_id
+        .split_once('.')
+        .map(|(_, addr)| addr)
+        .filter(|addr| !addr.is_empty());
+    match &provider.address {
+        Some(addr) if addr.is_empty() => Err(ContractError::NoProviderAddr {}),
+        Some(addr) => {
+            // wasmd ports are `wasm.<contract address>`
+            if port_id.starts_with("wasm.") && from_port != Some(addr.as_str()) {
+                return Err(ContractError::WrongProviderAddr(addr.clone()));
+            }
+            Ok(addr.clone())
+        }
+        None if provider.address_from_port => from_port
+            .map(String::from)
+            .ok_or(ContractError::ProviderAddrParsing {}),
+        None => Err(ContractError::NoProviderAddr {}),
+    }
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
@@ -79,14 +165,10 @@ pub fn ibc_channel_close(
     let channel = msg.channel();
     let channel_id = &channel.endpoint.channel_id;
 
-    // let's ensure this is really closed by same channel we previously connected (paranoia?)
+    // let's ensure this is really closed by a channel we previously connected (paranoia?)
     // then delete from store
-    let channel = CHANNEL.load(deps.storage)?;
-    if &channel == channel_id {
-        CHANNEL.remove(deps.storage);
-    } else {
-        return Err(ContractError::UnknownChannel(channel_id.clone()));
-    }
+    let provider = connected_provider(deps.storage, channel_id)?;
+    CHANNELS.remove(deps.storage, &provider);
 
     Ok(IbcBasicResponse::new())
 }
@@ -94,52 +176,288 @@ pub fn ibc_channel_close(
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn ibc_packet_receive(
     deps: DepsMut,
-    _env: Env,
+    env: Env,
     msg: IbcPacketReceiveMsg,
 ) -> Result<IbcReceiveResponse, ContractError> {
-    // paranoia: ensure it was sent on proper channel
+    // paranoia: ensure it was sent on a provider channel
     let caller = msg.packet.dest.channel_id;
-    if CHANNEL.load(deps.storage)? != caller {
-        return Err(ContractError::UnknownChannel(caller));
-    }
+    let provider = connected_provider(deps.storage, &caller)?;
 
     let msg: ProviderMsg = from_slice(&msg.packet.data)?;
     match msg {
-        ProviderMsg::ListValidators {} => receive_list_validators(deps),
+        ProviderMsg::ListValidators {} => receive_list_validators(deps, env, provider),
         ProviderMsg::Stake {
             validator,
             amount,
             key: _,
-        } => receive_stake(deps, validator, amount),
+        } => receive_stake(deps, env, provider, validator, amount),
         ProviderMsg::Unstake {
             validator,
             amount,
             key: _,
-        } => receive_unstake(deps, validator, amount),
+        } => receive_unstake(deps, provider, validator, amount),
+        ProviderMsg::SyncState {} => receive_sync_state(deps, env, provider),
     }
 }
 
-pub fn receive_list_validators(deps: DepsMut) -> Result<IbcReceiveResponse, ContractError> {
-    let validators = deps
-        .querier
-        .query_all_validators()?
+pub fn receive_list_validators(
+    mut deps: DepsMut,
+    env: Env,
+    provider: String,
+) -> Result<IbcReceiveResponse, ContractError> {
+    let all_validators = deps.querier.query_all_validators()?;
+    let validators: Vec<String> = all_validators.iter().map(|x| x.address.clone()).collect();
+    let ValsetUpdate {
+        validators,
+        epoch,
+        added,
+        removed,
+    } = update_valset(deps.storage, validators)?;
+
+    // the caller gets the whole set, the other providers learn about the change with an update
+    UNSENT_VALSET.remove(deps.storage, &provider);
+    let msgs = build_valset_updates(deps.branch(), &env, added, removed, epoch, Some(&provider))?;
+
+    let metadata = all_validators.into_iter().map(validator_metadata).collect();
+    let ack = StdAck::success(mesh_ibc::ListValidatorsResponse {
+        validators,
+        epoch,
+        metadata,
+    });
+
+    Ok(IbcReceiveResponse::new().add_messages(msgs).set_ack(ack))
+}
+
+/// The active set after `update_valset`, and how it differs from the previous one
+pub struct ValsetUpdate {
+    /// sorted
+    pub validators: Vec<String>,
+    pub epoch: u64,
+    pub added: Vec<String>,
+    pub removed: Vec<String>,
+}
+
+/// Stores the sorted set as the last one reported, bumping the epoch if it changed.
+pub fn update_valset(
+    storage: &mut dyn Storage,
+    mut validators: Vec<String>,
+) -> Result<ValsetUpdate, ContractError> {
+    validators.sort();
+    let old = VALSET.may_load(storage)?;
+    let mut epoch = VALSET_EPOCH.may_load(storage)?.unwrap_or_default();
+    if old.as_ref() == Some(&validators) {
+        return Ok(ValsetUpdate {
+            validators,
+            epoch,
+            added: vec![],
+            removed: vec![],
+        });
+    }
+
+    let old = old.unwrap_or_default();
+    let added = validators
+        .iter()
+        .filter(|v| old.binary_search(v).is_err())
+        .cloned()
+        .collect();
+    let removed = old
         .into_iter()
-        .map(|x| x.address)
+        .filter(|v| validators.binary_search(v).is_err())
         .collect();
-    let ack = StdAck::success(mesh_ibc::ListValidatorsResponse { validators });
+    epoch += 1;
+    VALSET_EPOCH.save(storage, &epoch)?;
+    VALSET.save(storage, &validators)?;
+    Ok(ValsetUpdate {
+        validators,
+        epoch,
+        added,
+        removed,
+    })
+}
 
-    Ok(IbcReceiveResponse::new().set_ack(ack))
+// The staking queries only return bonded validators, so none of them is jailed
+fn validator_metadata(validator: Validator) -> ValidatorMetadata {
+    ValidatorMetadata {
+        address: validator.address,
+        commission: validator.commission,
+        max_commission: validator.max_commission,
+        max_change_rate: validator.max_change_rate,
+        jailed: false,
+    }
+}
+
+/// Builds the updates for the connected providers, including the changes they failed to take
+/// before, skipping `except`
+pub fn build_valset_updates(
+    deps: DepsMut,
+    env: &Env,
+    added: Vec<String>,
+    removed: Vec<String>,
+    epoch: u64,
+    except: Option<&str>,
+) -> Result<Vec<IbcMsg>, ContractError> {
+    let channels = CHANNELS
+        .range(deps.storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<_>>>()?;
+    let mut msgs = vec![];
+    for (provider, channel) in channels {
+        if except == Some(provider.as_str()) {
+            continue;
+        }
+        let diff = take_valset_diff(deps.storage, &provider, added.clone(), removed.clone())?;
+        if diff.added.is_empty() && diff.removed.is_empty() {
+            continue;
+        }
+        msgs.push(build_update_validators(
+            deps.as_ref(),
+            env,
+            channel.channel_id,
+            diff.added,
+            diff.removed,
+            epoch,
+        )?);
+    }
+    Ok(msgs)
+}
+
+/// Takes the queued changes of the provider merged with these, minus the parts a later sync
+/// already reverted
+fn take_valset_diff(
+    storage: &mut dyn Storage,
+    provider: &str,
+    mut added: Vec<String>,
+    mut removed: Vec<String>,
+) -> StdResult<ValsetDiff> {
+    if let Some(unsent) = UNSENT_VALSET.may_load(storage, provider)? {
+        UNSENT_VALSET.remove(storage, provider);
+        added.extend(unsent.added);
+        removed.extend(unsent.removed);
+    }
+    let valset = VALSET.may_load(storage)?.unwrap_or_default();
+    added.retain(|v| valset.binary_search(v).is_ok());
+    removed.retain(|v| valset.binary_search(v).is_err());
+    added.sort();
+    added.dedup();
+    removed.sort();
+    removed.dedup();
+    Ok(ValsetDiff { added, removed })
+}
+
+/// Builds the packet with the current metadata of the added validators that are still bonded
+pub fn build_update_validators(
+    deps: Deps,
+    env: &Env,
+    channel_id: String,
+    added: Vec<String>,
+    removed: Vec<String>,
+    epoch: u64,
+) -> Result<IbcMsg, ContractError> {
+    let mut metadata = vec![];
+    for address in &added {
+        if let Some(validator) = deps.querier.query_validator(address)? {
+            metadata.push(validator_metadata(validator));
+        }
+    }
+    Ok(IbcMsg::SendPacket {
+        channel_id,
+        data: to_binary(&ConsumerMsg::UpdateValidators {
+            added,
+            removed,
+            epoch,
+            metadata,
+        })?,
+        timeout: build_timeout(deps, env)?,
+    })
+}
+
+/// Local tokens per remote token of the provider, from its price feed if it has one
+pub fn exchange_rate(
+    deps: Deps,
+    env: &Env,
+    config: &Config,
+    provider: &Provider,
+) -> Result<Decimal, ContractError> {
+    let feed = match &provider.price_feed {
+        Some(feed) => feed,
+        None => return Ok(config.remote_to_local_exchange_rate),
+    };
+    let res: PriceResponse = deps
+        .querier
+        .query_wasm_smart(&feed.oracle, &PriceFeedQueryMsg::Price {})?;
+    if env.block.time > res.updated_at.plus_seconds(feed.max_staleness) {
+        return Err(ContractError::StalePrice(res.updated_at));
+    }
+    Ok(res.price * (Decimal::one() - feed.discount))
+}
+
+/// Local tokens delegated for the stake of a provider
+pub fn provider_stake(storage: &dyn Storage, provider: &str) -> StdResult<Uint128> {
+    POSITIONS
+        .prefix(provider)
+        .range(storage, None, None, Order::Ascending)
+        .map(|r| r.map(|(_, position)| position.local))
+        .sum()
+}
+
+/// Local tokens delegated on a validator, per provider that has stake on it
+pub fn validator_stakes(
+    storage: &dyn Storage,
+    validator: &str,
+) -> StdResult<Vec<(String, Uint128)>> {
+    let mut stakes = vec![];
+    for provider in PROVIDERS.keys(storage, None, None, Order::Ascending) {
+        let provider = provider?;
+        let position = POSITIONS.may_load(storage, (&provider, validator))?;
+        if let Some(position) = position.filter(|p| !p.local.is_zero()) {
+            stakes.push((provider, position.local));
+        }
+    }
+    Ok(stakes)
+}
+
+/// Refuses a packet with an error ack, without failing the relayer's transaction
+fn error_ack(err: ContractError) -> IbcReceiveResponse {
+    IbcReceiveResponse::new()
+        .set_ack(StdAck::fail(err.to_string()))
+        .add_attribute("error", err.to_string())
 }
 
 pub fn receive_stake(
     deps: DepsMut,
+    env: Env,
+    provider_id: String,
     validator: String,
-    amount: Uint128,
+    remote: Uint128,
 ) -> Result<IbcReceiveResponse, ContractError> {
     let config = CONFIG.load(deps.storage)?;
+    let provider = PROVIDERS.load(deps.storage, &provider_id)?;
 
-    // Convert remote token to local token
-    let amount = amount * config.remote_to_local_exchange_rate;
+    // Convert remote token to local token.
+    // If we can't take the stake, the provider releases it again on an error ack.
+    let rate = match exchange_rate(deps.as_ref(), &env, &config, &provider) {
+        Ok(rate) => rate,
+        Err(err) => return Ok(error_ack(err)),
+    };
+    let amount = remote * rate;
+    if let Some(cap) = provider.max_cap {
+        if provider_stake(deps.storage, &provider_id)? + amount > cap {
+            return Ok(error_ack(ContractError::MaxCapReached(cap)));
+        }
+    }
+    let key = (provider_id.as_str(), validator.as_str());
+    POSITIONS.update::<_, ContractError>(deps.storage, key, |position| {
+        let mut position = position.unwrap_or_default();
+        position.remote += remote;
+        position.local += amount;
+        Ok(position)
+    })?;
+    let change = PendingChange {
+        provider: provider_id.clone(),
+        validator: validator.clone(),
+        remote,
+        local: amount,
+    };
+    PENDING_CHANGE.save(deps.storage, &change)?;
 
     let msg = WasmMsg::Execute {
         contract_addr: config.meta_staking_contract_address.to_string(),
@@ -147,19 +465,41 @@ pub fn receive_stake(
         funds: vec![],
     };
 
+    // if meta-staking fails, the reply replaces this ack with an error
     let ack = StdAck::success(mesh_ibc::StakeResponse {});
-    Ok(IbcReceiveResponse::new().add_message(msg).set_ack(ack))
+    Ok(IbcReceiveResponse::new()
+        .add_submessage(SubMsg::reply_on_error(msg, STAKE_REPLY_ID))
+        .set_ack(ack)
+        .add_attribute("exchange_rate", rate.to_string()))
 }
 
 pub fn receive_unstake(
     deps: DepsMut,
+    provider_id: String,
     validator: String,
-    amount: Uint128,
+    remote: Uint128,
 ) -> Result<IbcReceiveResponse, ContractError> {
     let config = CONFIG.load(deps.storage)?;
 
-    // Convert remote token to local token
-    let amount = amount * config.remote_to_local_exchange_rate;
+    // meta-staking pools the delegations of all providers, so each may only unstake its own
+    let key = (provider_id.as_str(), validator.as_str());
+    let mut position = POSITIONS.may_load(deps.storage, key)?.unwrap_or_default();
+    if remote > position.remote {
+        return Ok(error_ack(ContractError::InsufficientStake(position.remote)));
+    }
+
+    // Convert remote token to local token, at the rate the stake came in
+    let amount = remote.multiply_ratio(position.local, position.remote);
+    let change = PendingChange {
+        provider: provider_id.clone(),
+        validator: validator.clone(),
+        remote,
+        local: amount,
+    };
+    position.remote -= change.remote;
+    position.local -= change.local;
+    POSITIONS.save(deps.storage, key, &position)?;
+    PENDING_CHANGE.save(deps.storage, &change)?;
 
     let msg = WasmMsg::Execute {
         contract_addr: config.meta_staking_contract_address.to_string(),
@@ -168,7 +508,46 @@ pub fn receive_unstake(
     };
 
     let ack = StdAck::success(mesh_ibc::UnstakeResponse {});
-    Ok(IbcReceiveResponse::new().add_message(msg).set_ack(ack))
+    Ok(IbcReceiveResponse::new()
+        .add_submessage(SubMsg::reply_on_error(msg, UNSTAKE_REPLY_ID))
+        .set_ack(ack))
+}
+
+pub fn receive_sync_state(
+    deps: DepsMut,
+    env: Env,
+    provider: String,
+) -> Result<IbcReceiveResponse, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+
+    let delegations: Vec<Delegation> = deps.querier.query_wasm_smart(
+        &config.meta_staking_contract_address,
+        &StakingQueryMsg::AllDelegations {
+            consumer: env.contract.address.into_string(),
+        },
+    )?;
+
+    // Our share of each delegation goes by our part of the local stake on the validator,
+    // converted back to remote token at the rate the stake came in
+    let mut validators = vec![];
+    for d in delegations {
+        let position = POSITIONS.may_load(deps.storage, (&provider, &d.validator))?;
+        let position = match position {
+            Some(p) if !p.local.is_zero() => p,
+            _ => continue,
+        };
+        let total: Uint128 = validator_stakes(deps.storage, &d.validator)?
+            .into_iter()
+            .map(|(_, local)| local)
+            .sum();
+        validators.push(ValidatorStake {
+            validator: d.validator,
+            stake: d.delegation.multiply_ratio(position.remote, total),
+        });
+    }
+
+    let ack = StdAck::success(SyncStateResponse { validators });
+    Ok(IbcReceiveResponse::new().set_ack(ack))
 }
 
 /// Only handle errors in send
@@ -178,80 +557,283 @@ pub fn ibc_packet_ack(
     env: Env,
     msg: IbcPacketAckMsg,
 ) -> Result<IbcBasicResponse, ContractError> {
-    let res: StdAck = from_slice(&msg.acknowledgement.data)?;
-    if res.is_err() {
-        return Err(ContractError::AckFailed {});
-    }
+    let ack: StdAck = from_slice(&msg.acknowledgement.data)?;
+    let channel_id = msg.original_packet.src.channel_id;
+    let provider = channel_provider(deps.storage, &channel_id)?;
 
     // We need to parse the ack based on our request
     let original_packet: ConsumerMsg = from_slice(&msg.original_packet.data)?;
-    match original_packet {
-        ConsumerMsg::Rewards {
-            validator: _,
-            total_funds,
-        } => acknowledge_rewards(deps, env, total_funds),
-        ConsumerMsg::UpdateValidators {
-            added: _,
-            removed: _,
-        } => Ok(IbcBasicResponse::new()),
+    match (original_packet, ack.is_ok()) {
+        (
+            ConsumerMsg::Rewards {
+                validator: _,
+                total_funds,
+            },
+            true,
+        ) => acknowledge_rewards(deps, env, provider, total_funds),
+        (
+            ConsumerMsg::Rewards {
+                validator,
+                total_funds,
+            },
+            false,
+        ) => fail_rewards(deps, &provider, validator, total_funds),
+        (ConsumerMsg::BatchRewards { entries }, true) => {
+            acknowledge_batch_rewards(deps, env, provider, entries)
+        }
+        (ConsumerMsg::BatchRewards { entries }, false) => {
+            fail_batch_rewards(deps, provider, entries)
+        }
+        // e.g. the provider paused receiving, queue it like on timeout
+        (ConsumerMsg::UpdateValidators { added, removed, .. }, false) => {
+            fail_update_validators(deps, provider, added, removed)
+        }
+        // e.g. the provider paused receiving, RetrySlashes sends it again
+        (ConsumerMsg::Slash { .. }, false) if ack.is_transient() => {
+            queue_slash(deps.storage, &provider, msg.original_packet.data)?;
+            Ok(IbcBasicResponse::new().add_attribute("action", "queue_slash"))
+        }
+        (ConsumerMsg::Slash { validator, .. }, false) => reject_slash(validator, ack),
+        (ConsumerMsg::UpdateValidators { .. }, true) => Ok(IbcBasicResponse::new()),
+        (ConsumerMsg::Slash { .. }, true) => Ok(IbcBasicResponse::new()),
     }
 }
 
+// The provider refused the slash, resending it would fail the same way
+pub fn reject_slash(validator: String, ack: StdAck) -> Result<IbcBasicResponse, ContractError> {
+    let mut res = IbcBasicResponse::new()
+        .add_attribute("action", "slash_rejected")
+        .add_attribute("validator", validator);
+    if let StdAck::Error(err) = ack {
+        res = res.add_attribute("error", err);
+    }
+    Ok(res)
+}
+
 // The provder received our update packet, send the ics20 tokens.
 // NOTE: This is required because ibcMsg::sendPacket can't we sent with other IbcMsgs in the same call.
 pub fn acknowledge_rewards(
     deps: DepsMut,
     env: Env,
+    provider: String,
     amount: Coin,
 ) -> Result<IbcBasicResponse, ContractError> {
-    let config = CONFIG.load(deps.storage)?;
+    let msg = build_rewards_transfer(deps, &env, &provider, amount)?;
+    Ok(IbcBasicResponse::new().add_submessage(msg))
+}
 
-    // NOTE We try to split the addr from the port_id, maybe better to set the addr in init?
-    let provider_addr = config.provider.port_id.split('.').last();
-    let provider_addr = match provider_addr {
-        Some(addr) => addr,
-        None => return Err(ContractError::ProviderAddrParsing {}),
-    };
+// Send the rewards of the batch in one transfer per denom
+pub fn acknowledge_batch_rewards(
+    mut deps: DepsMut,
+    env: Env,
+    provider: String,
+    entries: Vec<RewardsEntry>,
+) -> Result<IbcBasicResponse, ContractError> {
+    let mut totals: BTreeMap<String, Uint128> = BTreeMap::new();
+    for entry in entries {
+        *totals.entry(entry.amount.denom).or_default() += entry.amount.amount;
+    }
 
-    let msg = IbcMsg::Transfer {
-        channel_id: config.ics20_channel.clone(),
-        to_address: provider_addr.to_string(),
-        amount,
-        timeout: build_timeout(deps.as_ref(), &env)?,
+    let mut res = IbcBasicResponse::new();
+    for (denom, amount) in totals {
+        let amount = Coin { denom, amount };
+        let msg = build_rewards_transfer(deps.branch(), &env, &provider, amount)?;
+        res = res.add_submessage(msg);
+    }
+    Ok(res)
+}
+
+/// Builds the ICS20 transfer of rewards to the provider, its reply tracks the packet sequence
+pub fn build_rewards_transfer(
+    deps: DepsMut,
+    env: &Env,
+    provider: &str,
+    amount: Coin,
+) -> Result<SubMsg, ContractError> {
+    let ics20_channel = PROVIDERS.load(deps.storage, provider)?.ics20_channel;
+    let recipient = CHANNELS.load(deps.storage, provider)?.recipient;
+    PENDING_REWARDS.update::<_, ContractError>(
+        deps.storage,
+        (provider, &amount.denom),
+        |pending| Ok(pending.unwrap_or_default().saturating_sub(amount.amount)),
+    )?;
+    let transfer = RewardsTransfer {
+        provider: provider.to_string(),
+        amount: amount.clone(),
     };
+    PENDING_TRANSFERS.push_back(deps.storage, &transfer)?;
+
+    let timeout = build_timeout(deps.as_ref(), env)?;
+    let msg = build_transfer(env, &ics20_channel, &recipient, &amount, &timeout);
+
+    Ok(SubMsg::reply_on_success(msg, TRANSFER_REPLY_ID))
+}
+
+const MSG_TRANSFER_TYPE_URL: &str = "/ibc.applications.transfer.v1.MsgTransfer";
+
+/// Builds an ICS20 MsgTransfer with an `ibc_callback` memo, so ibc-hooks calls us back with
+/// the result. IbcMsg::Transfer can't set a memo before cosmwasm-std 2.0.
+pub fn build_transfer(
+    env: &Env,
+    channel: &str,
+    receiver: &str,
+    amount: &Coin,
+    timeout: &IbcTimeout,
+) -> CosmosMsg {
+    let sender = env.contract.address.as_str();
+    let memo = format!(r#"{{"ibc_callback":"{}"}}"#, sender);
+    let mut token = vec![];
+    push_proto_bytes(&mut token, 1, amount.denom.as_bytes());
+    push_proto_bytes(&mut token, 2, amount.amount.to_string().as_bytes());
+
+    let mut value = vec![];
+    push_proto_bytes(&mut value, 1, b"transfer");
+    push_proto_bytes(&mut value, 2, channel.as_bytes());
+    push_proto_bytes(&mut value, 3, &token);
+    push_proto_bytes(&mut value, 4, sender.as_bytes());
+    push_proto_bytes(&mut value, 5, receiver.as_bytes());
+    if let Some(timestamp) = timeout.timestamp() {
+        // field 7, varint
+        push_varint(&mut value, 7 << 3);
+        push_varint(&mut value, timestamp.nanos());
+    }
+    push_proto_bytes(&mut value, 8, memo.as_bytes());
+
+    CosmosMsg::Stargate {
+        type_url: MSG_TRANSFER_TYPE_URL.to_string(),
+        value: value.into(),
+    }
+}
+
+fn push_varint(buf: &mut Vec<u8>, mut value: u64) {
+    while value >= 0x80 {
+        buf.push(value as u8 | 0x80);
+        value >>= 7;
+    }
+    buf.push(value as u8);
+}
 
-    Ok(IbcBasicResponse::new().add_message(msg))
+// a length delimited field
+fn push_proto_bytes(buf: &mut Vec<u8>, field: u64, bytes: &[u8]) {
+    push_varint(buf, field << 3 | 2);
+    push_varint(buf, bytes.len() as u64);
+    buf.extend_from_slice(bytes);
+}
+
+// The entries are resent one by one with RetryRewards
+pub fn fail_batch_rewards(
+    mut deps: DepsMut,
+    provider: String,
+    entries: Vec<RewardsEntry>,
+) -> Result<IbcBasicResponse, ContractError> {
+    for entry in entries {
+        fail_rewards(deps.branch(), &provider, entry.validator, entry.amount)?;
+    }
+    Ok(IbcBasicResponse::new().add_attribute("action", "fail_batch_rewards"))
+}
+
+/// Reads the sequence from the data of a MsgTransferResponse (protobuf field 1, varint)
+pub fn parse_transfer_sequence(data: &[u8]) -> Option<u64> {
+    let (tag, varint) = data.split_first()?;
+    if *tag != 0x08 {
+        return None;
+    }
+    let mut sequence = 0u64;
+    for (i, byte) in varint.iter().take(10).enumerate() {
+        sequence |= u64::from(byte & 0x7f) << (7 * i);
+        if byte & 0x80 == 0 {
+            return Some(sequence);
+        }
+    }
+    None
 }
 
 /// Handle timeout like ack errors
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn ibc_packet_timeout(
     deps: DepsMut,
-    _env: Env,
+    env: Env,
     msg: IbcPacketTimeoutMsg,
 ) -> Result<IbcBasicResponse, ContractError> {
     // we need to parse the ack based on our request
+    let channel_id = msg.packet.src.channel_id;
+    let provider = channel_provider(deps.storage, &channel_id)?;
     let original_packet: ConsumerMsg = from_slice(&msg.packet.data)?;
     match original_packet {
         ConsumerMsg::Rewards {
-            validator: _,
-            total_funds: _,
-        } => fail_rewards(deps),
-        ConsumerMsg::UpdateValidators { added, removed } => {
-            fail_update_validators(deps, added, removed)
+            validator,
+            total_funds,
+        } => fail_rewards(deps, &provider, validator, total_funds),
+        ConsumerMsg::BatchRewards { entries } => fail_batch_rewards(deps, provider, entries),
+        ConsumerMsg::UpdateValidators { added, removed, .. } => {
+            fail_update_validators(deps, provider, added, removed)
         }
+        ConsumerMsg::Slash { .. } => fail_slash(deps, env, provider, msg.packet.data),
     }
 }
 
-pub fn fail_rewards(_deps: DepsMut) -> Result<IbcBasicResponse, ContractError> {
-    // TODO what should we do on failure to withdraw rewards?
-    Err(ContractError::RewardsFailed {})
+// Keep the rewards until someone calls RetryRewards
+pub fn fail_rewards(
+    deps: DepsMut,
+    provider: &str,
+    validator: String,
+    amount: Coin,
+) -> Result<IbcBasicResponse, ContractError> {
+    UNSENT_REWARDS.update::<_, ContractError>(
+        deps.storage,
+        (provider, &validator, &amount.denom),
+        |unsent| Ok(unsent.unwrap_or_default() + amount.amount),
+    )?;
+    Ok(IbcBasicResponse::new()
+        .add_attribute("action", "fail_rewards")
+        .add_attribute("validator", validator))
 }
 
+// Queue what still applies of the update, the next SyncValidators sends it with the current
+// epoch. The provider accepts updates with the last seen epoch, so this applies even after
+// newer ones. A reconnecting provider gets the whole set with ListValidators instead.
 pub fn fail_update_validators(
-    _deps: DepsMut,
-    _added: Vec<String>,
-    _removed: Vec<String>,
+    deps: DepsMut,
+    provider: String,
+    added: Vec<String>,
+    removed: Vec<String>,
+) -> Result<IbcBasicResponse, ContractError> {
+    let diff = take_valset_diff(deps.storage, &provider, added, removed)?;
+    if !diff.added.is_empty() || !diff.removed.is_empty() {
+        UNSENT_VALSET.save(deps.storage, &provider, &diff)?;
+    }
+    Ok(IbcBasicResponse::new().add_attribute("action", "queue_update_validators"))
+}
+
+/// Keeps a slash packet until the provider reconnects or RetrySlashes sends it
+pub fn queue_slash(storage: &mut dyn Storage, provider: &str, data: Binary) -> StdResult<()> {
+    UNSENT_SLASHES.update::<_, StdError>(storage, provider, |slashes| {
+        let mut slashes = slashes.unwrap_or_default();
+        slashes.push(data);
+        Ok(slashes)
+    })?;
+    Ok(())
+}
+
+// The provider must learn about every slash, so we resend it as is, or once it reconnects
+pub fn fail_slash(
+    deps: DepsMut,
+    env: Env,
+    provider: String,
+    data: Binary,
 ) -> Result<IbcBasicResponse, ContractError> {
-    Err(ContractError::UpdateValidatorsFailed {})
+    let res = IbcBasicResponse::new().add_attribute("action", "retry_slash");
+    let channel = match CHANNELS.may_load(deps.storage, &provider)? {
+        Some(channel) => channel,
+        None => {
+            queue_slash(deps.storage, &provider, data)?;
+            return Ok(res);
+        }
+    };
+    let msg = IbcMsg::SendPacket {
+        channel_id: channel.channel_id,
+        data,
+        timeout: build_timeout(deps.as_ref(), &env)?,
+    };
+    Ok(res.add_message(msg))
 }
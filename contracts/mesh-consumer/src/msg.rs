@@ -1,21 +1,58 @@
 use cosmwasm_schema::{cw_serde, QueryResponses};
-use cosmwasm_std::Decimal;
+use cosmwasm_std::{Coin, Decimal, Uint128};
 
-use crate::state::Config;
+use crate::state::{Config, Provider, ProviderChannel};
 
 #[cw_serde]
 pub struct InstantiateMsg {
-    pub provider: ProviderInfo,
+    /// Provider chains we accept cross-stake from
+    pub providers: Vec<ProviderInfo>,
     pub remote_to_local_exchange_rate: Decimal,
     pub meta_staking_contract_address: String,
-    pub ics20_channel: String,
     pub packet_lifetime: Option<u64>,
+    /// Limits for Rebalance, which revalues the stake when the oracle price moves
+    pub rebalance: Option<RebalanceInfo>,
+    /// If set, rewards are collected and sent with SendBatchRewards, at most once per this
+    /// many seconds
+    pub rewards_batch_interval: Option<u64>,
+}
+
+#[cw_serde]
+pub struct RebalanceInfo {
+    /// Seconds between two rebalances
+    pub epoch: u64,
+    /// Max share of the local stake on a validator that one rebalance may add or remove
+    pub max_adjustment: Decimal,
+}
+
+#[cw_serde]
+pub struct PriceFeedInfo {
+    /// Contract answering `mesh_apis::PriceFeedQueryMsg`
+    pub oracle: String,
+    /// Share taken off the oracle price, to not overvalue cross-stake
+    pub discount: Decimal,
+    /// How old the oracle price may be, in seconds
+    pub max_staleness: u64,
 }
 
 #[cw_serde]
 pub struct ProviderInfo {
     pub port_id: String,
     pub connection_id: String,
+    /// Provider contract on the provider chain, where we send the ICS20 rewards
+    #[serde(default)]
+    pub address: Option<String>,
+    /// Without an address, take the part of `port_id` after the first `.` (`wasm.<address>`)
+    #[serde(default)]
+    pub address_from_port: bool,
+    /// Channel for the ICS20 transfer of rewards to this provider chain
+    pub ics20_channel: String,
+    /// If set, we convert with the oracle price instead of `remote_to_local_exchange_rate`
+    #[serde(default)]
+    pub price_feed: Option<PriceFeedInfo>,
+    /// Max local tokens delegated for the stake of this provider
+    #[serde(default)]
+    pub max_cap: Option<Uint128>,
 }
 
 #[cw_serde]
@@ -24,4 +61,79 @@ pub enum QueryMsg {
     // Return configuration info
     #[returns(Config)]
     Config {},
+    /// Authorized providers and their channels
+    #[returns(ProvidersResponse)]
+    Providers {},
+    /// The channel to the provider with this connection id
+    #[returns(ChannelResponse)]
+    Channel { provider: String },
+    /// Stake we hold for the provider on this validator, and the rate it was converted at
+    #[returns(VirtualStakeResponse)]
+    VirtualStake { provider: String, validator: String },
+    #[returns(ListVirtualStakeResponse)]
+    ListVirtualStake {
+        provider: String,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    /// Rewards received from meta-staking that were not sent to the provider yet
+    #[returns(PendingRewardsResponse)]
+    PendingRewards { provider: String },
+    /// Rewards for the provider waiting for RetryRewards
+    #[returns(UnsentRewardsResponse)]
+    UnsentRewards { provider: String },
+}
+
+#[cw_serde]
+pub struct ProvidersResponse {
+    pub providers: Vec<ProviderResponse>,
+}
+
+#[cw_serde]
+pub struct ProviderResponse {
+    pub connection_id: String,
+    pub provider: Provider,
+    pub channel: Option<ProviderChannel>,
+}
+
+#[cw_serde]
+pub struct ChannelResponse {
+    pub channel: Option<String>,
+    pub ics20_channel: String,
+    pub packet_lifetime: u64,
+}
+
+#[cw_serde]
+pub struct ListVirtualStakeResponse {
+    pub stakes: Vec<VirtualStakeResponse>,
+}
+
+#[cw_serde]
+pub struct PendingRewardsResponse {
+    pub rewards: Vec<Coin>,
+}
+
+#[cw_serde]
+pub struct UnsentRewardsResponse {
+    /// Rewards packets to resend
+    pub rewards: Vec<UnsentReward>,
+    /// ICS20 transfers to resend, the provider already accounted for them
+    pub transfers: Vec<Coin>,
+}
+
+#[cw_serde]
+pub struct UnsentReward {
+    pub validator: String,
+    pub amount: Coin,
+}
+
+#[cw_serde]
+pub struct VirtualStakeResponse {
+    pub validator: String,
+    /// Provider tokens staked
+    pub remote: Uint128,
+    /// Local tokens delegated for them
+    pub local: Uint128,
+    /// Average rate the stake was converted at
+    pub rate: Option<Decimal>,
 }
@@ -1,17 +1,120 @@
 use cosmwasm_schema::cw_serde;
-use cosmwasm_std::{Addr, Decimal};
-use cw_storage_plus::Item;
+use cosmwasm_std::{Addr, Binary, Coin, Decimal, Timestamp, Uint128};
+use cw_storage_plus::{Deque, Item, Map};
 
-use crate::msg::ProviderInfo;
+use crate::msg::RebalanceInfo;
 
 #[cw_serde]
 pub struct Config {
-    pub provider: ProviderInfo,
     pub remote_to_local_exchange_rate: Decimal,
     pub meta_staking_contract_address: Addr,
+    pub rebalance: Option<RebalanceInfo>,
+    #[serde(default)]
+    pub rewards_batch_interval: Option<u64>,
+}
+
+/// An authorized provider chain, stored under its connection id
+#[cw_serde]
+pub struct Provider {
+    pub port_id: String,
+    pub address: Option<String>,
+    pub address_from_port: bool,
     pub ics20_channel: String,
+    pub price_feed: Option<PriceFeed>,
+    pub max_cap: Option<Uint128>,
+}
+
+/// The connected channel of a provider
+#[cw_serde]
+pub struct ProviderChannel {
+    pub channel_id: String,
+    /// recipient of the ICS20 rewards, resolved when the channel connected
+    pub recipient: String,
+}
+
+#[cw_serde]
+#[derive(Default)]
+pub struct ValsetDiff {
+    pub added: Vec<String>,
+    pub removed: Vec<String>,
+}
+
+#[cw_serde]
+pub struct PriceFeed {
+    pub oracle: Addr,
+    pub discount: Decimal,
+    pub max_staleness: u64,
+}
+
+/// Stake held for the provider on one validator
+#[cw_serde]
+#[derive(Default)]
+pub struct Position {
+    /// provider tokens
+    pub remote: Uint128,
+    /// local tokens delegated for them
+    pub local: Uint128,
+}
+
+impl Position {
+    /// Local tokens per provider token, as converted when the stake arrived
+    pub fn rate(&self) -> Option<Decimal> {
+        if self.remote.is_zero() {
+            None
+        } else {
+            Some(Decimal::from_ratio(self.local, self.remote))
+        }
+    }
+}
+
+/// Position change of the stake packet being received, undone if meta-staking fails
+#[cw_serde]
+pub struct PendingChange {
+    pub provider: String,
+    pub validator: String,
+    pub remote: Uint128,
+    pub local: Uint128,
+}
+
+/// ICS20 transfer of rewards to a provider
+#[cw_serde]
+pub struct RewardsTransfer {
+    pub provider: String,
+    pub amount: Coin,
 }
 
 pub const CONFIG: Item<Config> = Item::new("config");
 pub const PACKET_LIFETIME: Item<u64> = Item::new("packet_time");
-pub const CHANNEL: Item<String> = Item::new("channel");
+// authorized providers, by connection id
+pub const PROVIDERS: Map<&str, Provider> = Map::new("providers");
+// channel of each connected provider
+pub const CHANNELS: Map<&str, ProviderChannel> = Map::new("channels");
+// provider of each channel, kept after close for the timeouts of packets still in flight
+pub const CHANNEL_PROVIDERS: Map<&str, String> = Map::new("channel_providers");
+// slash packets the provider didn't take yet, by provider. Sent when it reconnects, or with
+// RetrySlashes if it refused them for now, e.g. while paused.
+pub const UNSENT_SLASHES: Map<&str, Vec<Binary>> = Map::new("unsent_slashes");
+// last validator set sent to the providers, sorted, and its epoch
+pub const VALSET: Item<Vec<String>> = Item::new("valset");
+pub const VALSET_EPOCH: Item<u64> = Item::new("valset_epoch");
+// validator changes whose UpdateValidators failed, by provider. Sent with the next change or
+// SyncValidators, dropped when the provider lists the whole set again.
+pub const UNSENT_VALSET: Map<&str, ValsetDiff> = Map::new("unsent_valset");
+// stake we hold per (provider, validator)
+pub const POSITIONS: Map<(&str, &str), Position> = Map::new("positions");
+pub const LAST_REBALANCE: Item<Timestamp> = Item::new("last_rebalance");
+// rewards waiting for the provider to ack the rewards packet, by (provider, denom)
+pub const PENDING_REWARDS: Map<(&str, &str), Uint128> = Map::new("pending_rewards");
+// only read in the reply to a failed meta-staking call, in the same transaction
+pub const PENDING_CHANGE: Item<PendingChange> = Item::new("pending_change");
+// rewards whose Rewards packet failed, by (provider, validator, denom)
+pub const UNSENT_REWARDS: Map<(&str, &str, &str), Uint128> = Map::new("unsent_rewards");
+// rewards the provider accounted for, but whose ICS20 transfer failed, by (provider, denom)
+pub const UNSENT_TRANSFERS: Map<(&str, &str), Uint128> = Map::new("unsent_transfers");
+// reward transfers dispatched in this transaction, waiting for the reply with their sequence
+pub const PENDING_TRANSFERS: Deque<RewardsTransfer> = Deque::new("pending_transfers");
+// reward transfers in flight, by (ICS20 channel, packet sequence)
+pub const TRANSFERS: Map<(&str, u64), RewardsTransfer> = Map::new("transfers");
+// rewards collected for the next batch, by (provider, validator, denom)
+pub const BATCH_REWARDS: Map<(&str, &str, &str), Uint128> = Map::new("batch_rewards");
+pub const LAST_BATCH: Item<Timestamp> = Item::new("last_batch");
@@ -1,25 +1,57 @@
 use cosmwasm_std::{
-    coin,
-    testing::{mock_env, mock_info},
-    to_binary, IbcMsg,
+    coin, from_slice,
+    testing::{mock_dependencies, mock_env, mock_info, MockQuerier},
+    to_binary, Addr, CosmosMsg, Decimal, Deps, DepsMut, Env, IbcAcknowledgement, IbcMsg,
+    IbcPacketAckMsg, IbcPacketReceiveMsg, IbcPacketTimeoutMsg, Reply, ReplyOn, SubMsgResponse,
+    SubMsgResult, Uint128, Validator, WasmMsg,
+};
+use mesh_apis::{ConsumerExecuteMsg, ConsumerSudoMsg, IbcLifecycleComplete, StakingExecuteMsg};
+use mesh_ibc::{
+    ConsumerMsg, ProviderMsg, RewardsEntry, RewardsResponse, StdAck, ValidatorMetadata,
+    IBC_APP_VERSION,
+};
+use mesh_testing::{
+    addr,
+    constants::{
+        CHANNEL_ID, CONNECTION_ID, CREATOR_ADDR, ICS20_CHANNEL_ID, NATIVE_DENOM, RELAYER_ADDR,
+        VALIDATOR,
+    },
+    ibc_helpers::{mock_channel, mock_packet, to_ack_error, to_ack_success},
 };
-use mesh_ibc::ConsumerMsg;
-use mesh_testing::constants::{CHANNEL_ID, NATIVE_DENOM, VALIDATOR};
 
 use crate::{
-    ibc::build_timeout,
-    testing::utils::{helpers::STAKING_ADDR, setup::setup_with_channel},
+    contract::{
+        execute, instantiate, list_virtual_stake, query_channel, query_pending_rewards,
+        query_unsent_rewards, query_virtual_stake, reply, sudo,
+    },
+    ibc::{
+        build_timeout, build_transfer, ibc_packet_ack, ibc_packet_receive, ibc_packet_timeout,
+        TRANSFER_REPLY_ID,
+    },
+    msg::{ChannelResponse, InstantiateMsg, ProviderInfo, UnsentReward, VirtualStakeResponse},
+    testing::utils::{
+        helpers::{
+            get_default_instantiate_msg, get_price_feed_instantiate_msg, get_provider_info,
+            set_price, PROVIDER_ADDR, STAKING_ADDR,
+        },
+        setup::setup_with_channel,
+    },
+    ContractError,
 };
 
-use super::utils::executes::execute_receive_rewards;
+use super::utils::executes::{
+    execute_receive_rewards, ibc_ack_rewards, ibc_ack_update_validators, ibc_connect, ibc_open,
+    ibc_receive_list_validators, ibc_receive_stake, ibc_timeout_update_validators,
+};
 
 #[test]
 fn recieve_rewards() {
     let (mut deps, _) = setup_with_channel(None);
+    ibc_receive_stake(deps.as_mut(), VALIDATOR, 1000, "key").unwrap();
 
     // test execute receive rewards
     let coin = coin(1000, NATIVE_DENOM);
-    let info = mock_info(STAKING_ADDR, &[coin.clone()]);
+    let info = mock_info(STAKING_ADDR, std::slice::from_ref(&coin));
     let res = execute_receive_rewards(deps.as_mut(), info, VALIDATOR).unwrap();
 
     assert_eq!(
@@ -36,3 +68,729 @@ fn recieve_rewards() {
         .into()
     )
 }
+
+#[test]
+fn rebalance() {
+    let rebalance = |deps: DepsMut, env: Env| {
+        execute(
+            deps,
+            env,
+            mock_info("anyone", &[]),
+            ConsumerExecuteMsg::Rebalance {},
+        )
+    };
+    let staking_msg = |msg: StakingExecuteMsg| -> CosmosMsg {
+        WasmMsg::Execute {
+            contract_addr: STAKING_ADDR.to_string(),
+            msg: to_binary(&msg).unwrap(),
+            funds: vec![],
+        }
+        .into()
+    };
+
+    // Needs a price feed
+    let (mut deps, _) = setup_with_channel(None);
+    let err = rebalance(deps.as_mut(), mock_env()).unwrap_err();
+    assert_eq!(err, ContractError::RebalanceDisabled {});
+
+    // 100 PROV * 18 * 0.6 = 1080 CONS
+    let (mut deps, _) = setup_with_channel(Some(get_price_feed_instantiate_msg()));
+    set_price(&mut deps.querier, 18, mock_env().block.time);
+    ibc_receive_stake(deps.as_mut(), VALIDATOR, 100, "key_1").unwrap();
+
+    // The price went up, 1200 CONS are due but we add at most 10%
+    set_price(&mut deps.querier, 20, mock_env().block.time);
+    let res = rebalance(deps.as_mut(), mock_env()).unwrap();
+    assert_eq!(
+        res.messages[0].msg,
+        staking_msg(StakingExecuteMsg::Delegate {
+            validator: VALIDATOR.to_string(),
+            amount: Uint128::new(108),
+        })
+    );
+    let position = query_virtual_stake(
+        deps.as_ref(),
+        CONNECTION_ID.to_string(),
+        VALIDATOR.to_string(),
+    )
+    .unwrap();
+    assert_eq!(position.remote, Uint128::new(100));
+    assert_eq!(position.local, Uint128::new(1188));
+
+    // Once per epoch
+    let err = rebalance(deps.as_mut(), mock_env()).unwrap_err();
+    let next = mock_env().block.time.plus_seconds(86400);
+    assert_eq!(err, ContractError::RebalanceTooSoon(next));
+
+    let mut env = mock_env();
+    env.block.time = next;
+    set_price(&mut deps.querier, 20, next);
+    let res = rebalance(deps.as_mut(), env.clone()).unwrap();
+    assert_eq!(
+        res.messages[0].msg,
+        staking_msg(StakingExecuteMsg::Delegate {
+            validator: VALIDATOR.to_string(),
+            amount: Uint128::new(12),
+        })
+    );
+
+    // Price went down, 600 CONS are due
+    env.block.time = env.block.time.plus_seconds(86400);
+    set_price(&mut deps.querier, 10, env.block.time);
+    let res = rebalance(deps.as_mut(), env).unwrap();
+    assert_eq!(
+        res.messages[0].msg,
+        staking_msg(StakingExecuteMsg::Undelegate {
+            validator: VALIDATOR.to_string(),
+            amount: Uint128::new(120),
+        })
+    );
+    let position = query_virtual_stake(
+        deps.as_ref(),
+        CONNECTION_ID.to_string(),
+        VALIDATOR.to_string(),
+    )
+    .unwrap();
+    assert_eq!(position.local, Uint128::new(1080));
+}
+
+#[test]
+fn rebalance_max_cap_and_stale_price() {
+    let rebalance = |deps: DepsMut, env: Env| {
+        execute(
+            deps,
+            env,
+            mock_info("anyone", &[]),
+            ConsumerExecuteMsg::Rebalance {},
+        )
+    };
+    let mut init_msg = get_price_feed_instantiate_msg();
+    init_msg.providers[0].max_cap = Some(Uint128::new(1100));
+    let (mut deps, _) = setup_with_channel(Some(init_msg));
+    set_price(&mut deps.querier, 18, mock_env().block.time);
+    ibc_receive_stake(deps.as_mut(), VALIDATOR, 100, "key_1").unwrap();
+
+    // 108 CONS would be added, but only 20 fit under the cap
+    set_price(&mut deps.querier, 20, mock_env().block.time);
+    let res = rebalance(deps.as_mut(), mock_env()).unwrap();
+    assert_eq!(
+        res.messages[0].msg,
+        WasmMsg::Execute {
+            contract_addr: STAKING_ADDR.to_string(),
+            msg: to_binary(&StakingExecuteMsg::Delegate {
+                validator: VALIDATOR.to_string(),
+                amount: Uint128::new(20),
+            })
+            .unwrap(),
+            funds: vec![],
+        }
+        .into()
+    );
+    let position = query_virtual_stake(
+        deps.as_ref(),
+        CONNECTION_ID.to_string(),
+        VALIDATOR.to_string(),
+    )
+    .unwrap();
+    assert_eq!(position.local, Uint128::new(1100));
+
+    // A stale price skips the provider instead of failing the crank
+    let mut env = mock_env();
+    env.block.time = env.block.time.plus_seconds(86400);
+    set_price(&mut deps.querier, 10, mock_env().block.time);
+    let res = rebalance(deps.as_mut(), env).unwrap();
+    assert!(res.messages.is_empty());
+    assert_eq!(res.attributes[1], ("skipped_provider", CONNECTION_ID));
+    assert_eq!(
+        res.attributes[2].value,
+        ContractError::StalePrice(mock_env().block.time).to_string()
+    );
+    let position = query_virtual_stake(
+        deps.as_ref(),
+        CONNECTION_ID.to_string(),
+        VALIDATOR.to_string(),
+    )
+    .unwrap();
+    assert_eq!(position.local, Uint128::new(1100));
+}
+
+#[test]
+fn queries() {
+    let (mut deps, _) = setup_with_channel(None);
+
+    let channel = query_channel(deps.as_ref(), CONNECTION_ID.to_string()).unwrap();
+    assert_eq!(
+        channel,
+        ChannelResponse {
+            channel: Some(CHANNEL_ID.to_string()),
+            ics20_channel: ICS20_CHANNEL_ID.to_string(),
+            packet_lifetime: 3600,
+        }
+    );
+
+    // Stake on two validators, with the fixed rate of 0.1
+    ibc_receive_stake(deps.as_mut(), VALIDATOR, 1000, "key_1").unwrap();
+    ibc_receive_stake(deps.as_mut(), "validator2", 500, "key_2").unwrap();
+    let stakes = list_virtual_stake(deps.as_ref(), CONNECTION_ID.to_string(), None, None)
+        .unwrap()
+        .stakes;
+    assert_eq!(stakes.len(), 2);
+    assert_eq!(stakes[0].validator, VALIDATOR);
+    assert_eq!(stakes[0].local, Uint128::new(100));
+    assert_eq!(
+        stakes[1],
+        VirtualStakeResponse {
+            validator: "validator2".to_string(),
+            remote: Uint128::new(500),
+            local: Uint128::new(50),
+            rate: Some(Decimal::percent(10)),
+        }
+    );
+    let stakes = list_virtual_stake(
+        deps.as_ref(),
+        CONNECTION_ID.to_string(),
+        Some(VALIDATOR.to_string()),
+        None,
+    )
+    .unwrap()
+    .stakes;
+    assert_eq!(stakes.len(), 1);
+    assert_eq!(stakes[0].validator, "validator2");
+
+    // Rewards are pending until the provider acks the rewards packet
+    let info = mock_info(STAKING_ADDR, &[coin(1000, NATIVE_DENOM)]);
+    execute_receive_rewards(deps.as_mut(), info, VALIDATOR).unwrap();
+    let pending = query_pending_rewards(deps.as_ref(), CONNECTION_ID.to_string()).unwrap();
+    assert_eq!(pending.rewards, vec![coin(1000, NATIVE_DENOM)]);
+
+    let ack = IbcAcknowledgement::new(to_ack_success(RewardsResponse {}));
+    ibc_ack_rewards(deps.as_mut(), VALIDATOR, 1000, ack).unwrap();
+    let pending = query_pending_rewards(deps.as_ref(), CONNECTION_ID.to_string()).unwrap();
+    assert_eq!(pending.rewards, vec![coin(0, NATIVE_DENOM)]);
+}
+
+#[test]
+fn sync_validators() {
+    let (mut deps, _) = setup_with_channel(None);
+    let sync = |deps: DepsMut| {
+        execute(
+            deps,
+            mock_env(),
+            mock_info("anyone", &[]),
+            ConsumerExecuteMsg::SyncValidators {},
+        )
+        .unwrap()
+    };
+    let timeout = build_timeout(deps.as_ref(), &mock_env()).unwrap();
+    let update = |added: &[&str], removed: &[&str], epoch: u64| -> CosmosMsg {
+        IbcMsg::SendPacket {
+            channel_id: CHANNEL_ID.to_string(),
+            data: to_binary(&ConsumerMsg::UpdateValidators {
+                added: added.iter().map(|v| v.to_string()).collect(),
+                removed: removed.iter().map(|v| v.to_string()).collect(),
+                epoch,
+                metadata: added
+                    .iter()
+                    .map(|v| ValidatorMetadata {
+                        address: v.to_string(),
+                        commission: Decimal::percent(5),
+                        max_commission: Decimal::one(),
+                        max_change_rate: Decimal::one(),
+                        jailed: false,
+                    })
+                    .collect(),
+            })
+            .unwrap(),
+            timeout: timeout.clone(),
+        }
+        .into()
+    };
+    let set_validators = |querier: &mut MockQuerier, validators: &[&str]| {
+        let validators: Vec<_> = validators
+            .iter()
+            .map(|v| Validator {
+                address: v.to_string(),
+                commission: Decimal::percent(5),
+                max_commission: Decimal::one(),
+                max_change_rate: Decimal::one(),
+            })
+            .collect();
+        querier.update_staking(NATIVE_DENOM, &validators, &[]);
+    };
+
+    // The provider listed the initial set
+    set_validators(&mut deps.querier, &[VALIDATOR]);
+    ibc_receive_list_validators(deps.as_mut()).unwrap();
+
+    // Nothing changed, nothing is sent
+    let res = sync(deps.as_mut());
+    assert_eq!(res.messages.len(), 0);
+
+    // A validator joined and the first one left
+    set_validators(&mut deps.querier, &["validator2", "validator3"]);
+    let res = sync(deps.as_mut());
+    assert_eq!(
+        res.messages[0].msg,
+        update(&["validator2", "validator3"], &[VALIDATOR], 2)
+    );
+    assert_eq!(sync(deps.as_mut()).messages.len(), 0);
+
+    // validator3 left again before the update timed out, so only the rest is resent
+    // with the next sync
+    set_validators(&mut deps.querier, &["validator2"]);
+    let res = sync(deps.as_mut());
+    assert_eq!(res.messages[0].msg, update(&[], &["validator3"], 3));
+    let res = ibc_timeout_update_validators(
+        deps.as_mut(),
+        &["validator2", "validator3"],
+        &[VALIDATOR],
+        2,
+    )
+    .unwrap();
+    assert_eq!(res.messages.len(), 0);
+    let res = sync(deps.as_mut());
+    assert_eq!(
+        res.messages[0].msg,
+        update(&["validator2"], &[VALIDATOR], 3)
+    );
+    assert_eq!(sync(deps.as_mut()).messages.len(), 0);
+
+    // An update that no longer changes anything is dropped
+    ibc_timeout_update_validators(deps.as_mut(), &["validator3"], &[], 2).unwrap();
+    assert_eq!(sync(deps.as_mut()).messages.len(), 0);
+
+    // An error ack, e.g. while the provider is paused, is queued like a timeout
+    let ack = IbcAcknowledgement::new(to_ack_error("receiving is paused"));
+    let res =
+        ibc_ack_update_validators(deps.as_mut(), &["validator2"], &[VALIDATOR], 2, ack).unwrap();
+    assert_eq!(res.messages.len(), 0);
+
+    // and sent together with the next change
+    set_validators(&mut deps.querier, &["validator2", "validator4"]);
+    let res = sync(deps.as_mut());
+    assert_eq!(res.messages.len(), 1);
+    assert_eq!(
+        res.messages[0].msg,
+        update(&["validator2", "validator4"], &[VALIDATOR], 4)
+    );
+}
+
+#[test]
+fn sudo_slash() {
+    let (mut deps, _) = setup_with_channel(None);
+    let packet = to_binary(&ConsumerMsg::Slash {
+        validator: VALIDATOR.to_string(),
+        percentage: Decimal::percent(5),
+        infraction_height: 42,
+        tombstone: true,
+    })
+    .unwrap();
+    let slash_msg: CosmosMsg = IbcMsg::SendPacket {
+        channel_id: CHANNEL_ID.to_string(),
+        data: packet.clone(),
+        timeout: build_timeout(deps.as_ref(), &mock_env()).unwrap(),
+    }
+    .into();
+
+    let res = sudo(
+        deps.as_mut(),
+        mock_env(),
+        ConsumerSudoMsg::Slash {
+            validator: VALIDATOR.to_string(),
+            percentage: Decimal::percent(5),
+            infraction_height: 42,
+            tombstone: true,
+        },
+    )
+    .unwrap();
+    assert_eq!(res.messages[0].msg, slash_msg);
+
+    // The slash is resent until the provider gets it
+    let res = ibc_packet_timeout(
+        deps.as_mut(),
+        mock_env(),
+        IbcPacketTimeoutMsg::new(mock_packet(packet.clone()), addr!(RELAYER_ADDR)),
+    )
+    .unwrap();
+    assert_eq!(res.messages[0].msg, slash_msg);
+
+    // A paused provider takes it later, with RetrySlashes
+    let ack = IbcAcknowledgement::new(StdAck::fail_transient(
+        "Operation is paused: IbcReceive".to_string(),
+    ));
+    let res = ibc_packet_ack(
+        deps.as_mut(),
+        mock_env(),
+        IbcPacketAckMsg::new(ack, mock_packet(packet.clone()), addr!(RELAYER_ADDR)),
+    )
+    .unwrap();
+    assert_eq!(res.messages.len(), 0);
+    let retry_slashes = |deps: DepsMut| {
+        execute(
+            deps,
+            mock_env(),
+            mock_info(RELAYER_ADDR, &[]),
+            ConsumerExecuteMsg::RetrySlashes {},
+        )
+        .unwrap()
+    };
+    let res = retry_slashes(deps.as_mut());
+    assert_eq!(res.messages.len(), 1);
+    assert_eq!(res.messages[0].msg, slash_msg);
+    assert_eq!(retry_slashes(deps.as_mut()).messages.len(), 0);
+
+    // but not if the provider refused it
+    let ack = IbcAcknowledgement::new(to_ack_error("unknown validator"));
+    let res = ibc_packet_ack(
+        deps.as_mut(),
+        mock_env(),
+        IbcPacketAckMsg::new(ack, mock_packet(packet), addr!(RELAYER_ADDR)),
+    )
+    .unwrap();
+    assert_eq!(res.messages.len(), 0);
+    assert_eq!(retry_slashes(deps.as_mut()).messages.len(), 0);
+
+    // Bad percentages are refused before they are sent
+    let err = sudo(
+        deps.as_mut(),
+        mock_env(),
+        ConsumerSudoMsg::Slash {
+            validator: VALIDATOR.to_string(),
+            percentage: Decimal::percent(150),
+            infraction_height: 42,
+            tombstone: false,
+        },
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::InvalidSlashPercentage(Decimal::percent(150))
+    );
+}
+
+#[test]
+fn retry_rewards() {
+    let (mut deps, _) = setup_with_channel(None);
+    ibc_receive_stake(deps.as_mut(), VALIDATOR, 1000, "key").unwrap();
+    let retry = |deps: DepsMut| {
+        execute(
+            deps,
+            mock_env(),
+            mock_info("anyone", &[]),
+            ConsumerExecuteMsg::RetryRewards {},
+        )
+        .unwrap()
+    };
+    let timeout = build_timeout(deps.as_ref(), &mock_env()).unwrap();
+    let rewards_packet = to_binary(&ConsumerMsg::Rewards {
+        validator: VALIDATOR.to_string(),
+        total_funds: coin(1000, NATIVE_DENOM),
+    })
+    .unwrap();
+    let transfer: CosmosMsg = build_transfer(
+        &mock_env(),
+        ICS20_CHANNEL_ID,
+        PROVIDER_ADDR,
+        &coin(1000, NATIVE_DENOM),
+        &timeout,
+    );
+
+    // The rewards packet timed out
+    let info = mock_info(STAKING_ADDR, &[coin(1000, NATIVE_DENOM)]);
+    execute_receive_rewards(deps.as_mut(), info, VALIDATOR).unwrap();
+    ibc_packet_timeout(
+        deps.as_mut(),
+        mock_env(),
+        IbcPacketTimeoutMsg::new(mock_packet(rewards_packet.clone()), addr!(RELAYER_ADDR)),
+    )
+    .unwrap();
+    let unsent = query_unsent_rewards(deps.as_ref(), CONNECTION_ID.to_string()).unwrap();
+    assert_eq!(
+        unsent.rewards,
+        vec![UnsentReward {
+            validator: VALIDATOR.to_string(),
+            amount: coin(1000, NATIVE_DENOM),
+        }]
+    );
+
+    // It is resent once
+    let res = retry(deps.as_mut());
+    assert_eq!(
+        res.messages[0].msg,
+        IbcMsg::SendPacket {
+            channel_id: CHANNEL_ID.to_string(),
+            data: rewards_packet,
+            timeout,
+        }
+        .into()
+    );
+    assert_eq!(
+        query_unsent_rewards(deps.as_ref(), CONNECTION_ID.to_string())
+            .unwrap()
+            .rewards,
+        vec![]
+    );
+    assert_eq!(retry(deps.as_mut()).messages.len(), 0);
+
+    // The provider got it, the transfer is sent with sequence 7
+    let ack = IbcAcknowledgement::new(to_ack_success(RewardsResponse {}));
+    let res = ibc_ack_rewards(deps.as_mut(), VALIDATOR, 1000, ack).unwrap();
+    assert_eq!(res.messages[0].msg, transfer);
+    assert_eq!(res.messages[0].reply_on, ReplyOn::Success);
+    let transfer_reply = Reply {
+        id: TRANSFER_REPLY_ID,
+        result: SubMsgResult::Ok(SubMsgResponse {
+            events: vec![],
+            data: Some(vec![0x08, 0x07].into()),
+        }),
+    };
+    reply(deps.as_mut(), mock_env(), transfer_reply).unwrap();
+    let pending = query_pending_rewards(deps.as_ref(), CONNECTION_ID.to_string()).unwrap();
+    assert_eq!(pending.rewards, vec![coin(0, NATIVE_DENOM)]);
+
+    // The transfer failed, the refund is pending again and only the transfer is resent
+    let failed = ConsumerSudoMsg::IbcLifecycleComplete(IbcLifecycleComplete::IbcAck {
+        channel: ICS20_CHANNEL_ID.to_string(),
+        sequence: 7,
+        ack: r#"{"error":"ABCI code: 1"}"#.to_string(),
+        success: false,
+    });
+    sudo(deps.as_mut(), mock_env(), failed.clone()).unwrap();
+    let unsent = query_unsent_rewards(deps.as_ref(), CONNECTION_ID.to_string()).unwrap();
+    assert_eq!(unsent.transfers, vec![coin(1000, NATIVE_DENOM)]);
+    let pending = query_pending_rewards(deps.as_ref(), CONNECTION_ID.to_string()).unwrap();
+    assert_eq!(pending.rewards, vec![coin(1000, NATIVE_DENOM)]);
+
+    // A second result for the same transfer is ignored, here as sent by ibc-hooks
+    let timeout = format!(
+        r#"{{"ibc_lifecycle_complete":{{"ibc_timeout":{{"channel":"{}","sequence":7}}}}}}"#,
+        ICS20_CHANNEL_ID
+    );
+    let res = sudo(
+        deps.as_mut(),
+        mock_env(),
+        from_slice(timeout.as_bytes()).unwrap(),
+    )
+    .unwrap();
+    assert_eq!(res.attributes.len(), 0);
+
+    let res = retry(deps.as_mut());
+    assert_eq!(res.messages.len(), 1);
+    assert_eq!(res.messages[0].msg, transfer);
+    assert_eq!(
+        query_unsent_rewards(deps.as_ref(), CONNECTION_ID.to_string())
+            .unwrap()
+            .transfers,
+        vec![]
+    );
+}
+
+#[test]
+fn batch_rewards() {
+    let send_batch = |deps: DepsMut, env: Env| {
+        execute(
+            deps,
+            env,
+            mock_info("anyone", &[]),
+            ConsumerExecuteMsg::SendBatchRewards {},
+        )
+    };
+
+    let (mut deps, _) = setup_with_channel(None);
+    let err = send_batch(deps.as_mut(), mock_env()).unwrap_err();
+    assert_eq!(err, ContractError::BatchRewardsDisabled {});
+
+    let init_msg = InstantiateMsg {
+        rewards_batch_interval: Some(3600),
+        ..get_default_instantiate_msg()
+    };
+    let (mut deps, _) = setup_with_channel(Some(init_msg));
+    let timeout = build_timeout(deps.as_ref(), &mock_env()).unwrap();
+    ibc_receive_stake(deps.as_mut(), VALIDATOR, 1000, "key").unwrap();
+    ibc_receive_stake(deps.as_mut(), "validator2", 1000, "key").unwrap();
+
+    // Rewards are collected instead of sent
+    for (validator, amount) in [(VALIDATOR, 100), ("validator2", 30), (VALIDATOR, 50)] {
+        let info = mock_info(STAKING_ADDR, &[coin(amount, NATIVE_DENOM)]);
+        let res = execute_receive_rewards(deps.as_mut(), info, validator).unwrap();
+        assert_eq!(res.messages.len(), 0);
+    }
+
+    let entries = vec![
+        RewardsEntry {
+            validator: VALIDATOR.to_string(),
+            amount: coin(150, NATIVE_DENOM),
+        },
+        RewardsEntry {
+            validator: "validator2".to_string(),
+            amount: coin(30, NATIVE_DENOM),
+        },
+    ];
+    let res = send_batch(deps.as_mut(), mock_env()).unwrap();
+    assert_eq!(
+        res.messages[0].msg,
+        IbcMsg::SendPacket {
+            channel_id: CHANNEL_ID.to_string(),
+            data: to_binary(&ConsumerMsg::BatchRewards {
+                entries: entries.clone()
+            })
+            .unwrap(),
+            timeout: timeout.clone(),
+        }
+        .into()
+    );
+
+    // Once per interval
+    let err = send_batch(deps.as_mut(), mock_env()).unwrap_err();
+    let next = mock_env().block.time.plus_seconds(3600);
+    assert_eq!(err, ContractError::BatchRewardsTooSoon(next));
+
+    // The sum is sent in one transfer
+    let packet = mock_packet(to_binary(&ConsumerMsg::BatchRewards { entries }).unwrap());
+    let ack = IbcAcknowledgement::new(to_ack_success(RewardsResponse {}));
+    let res = ibc_packet_ack(
+        deps.as_mut(),
+        mock_env(),
+        IbcPacketAckMsg::new(ack, packet, addr!(RELAYER_ADDR)),
+    )
+    .unwrap();
+    assert_eq!(res.messages.len(), 1);
+    assert_eq!(
+        res.messages[0].msg,
+        build_transfer(
+            &mock_env(),
+            ICS20_CHANNEL_ID,
+            PROVIDER_ADDR,
+            &coin(180, NATIVE_DENOM),
+            &timeout
+        )
+    );
+}
+
+#[test]
+fn multiple_providers() {
+    const CONNECTION_2: &str = "connection-2";
+    const CHANNEL_2: &str = "channel-3";
+    let init_msg = InstantiateMsg {
+        providers: vec![
+            get_provider_info(),
+            ProviderInfo {
+                connection_id: CONNECTION_2.to_string(),
+                address: Some("provider2".to_string()),
+                ics20_channel: "channel-4".to_string(),
+                max_cap: Some(Uint128::new(50)),
+                ..get_provider_info()
+            },
+        ],
+        ..get_default_instantiate_msg()
+    };
+
+    // A provider can only be listed once
+    let mut duplicate = init_msg.clone();
+    duplicate.providers.push(get_provider_info());
+    let err = instantiate(
+        mock_dependencies().as_mut(),
+        mock_env(),
+        mock_info(CREATOR_ADDR, &[]),
+        duplicate,
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::DuplicateProvider(CONNECTION_ID.to_string())
+    );
+
+    // Each provider connects its own channel
+    let (mut deps, _) = setup_with_channel(Some(init_msg));
+    let mut channel = mock_channel(CHANNEL_2, IBC_APP_VERSION);
+    channel.connection_id = CONNECTION_2.to_string();
+    ibc_open(deps.as_mut(), channel.clone()).unwrap();
+    ibc_connect(deps.as_mut(), channel).unwrap();
+    let channel = query_channel(deps.as_ref(), CONNECTION_2.to_string()).unwrap();
+    assert_eq!(channel.channel, Some(CHANNEL_2.to_string()));
+
+    // and stakes over it
+    let stake = |deps: DepsMut, channel_id: &str, amount: u128| {
+        let mut packet = mock_packet(
+            to_binary(&ProviderMsg::Stake {
+                validator: VALIDATOR.to_string(),
+                amount: Uint128::new(amount),
+                key: "key".to_string(),
+            })
+            .unwrap(),
+        );
+        packet.dest.channel_id = channel_id.to_string();
+        ibc_packet_receive(
+            deps,
+            mock_env(),
+            IbcPacketReceiveMsg::new(packet, addr!(RELAYER_ADDR)),
+        )
+        .unwrap()
+    };
+    let local_stake = |deps: Deps, provider: &str| {
+        query_virtual_stake(deps, provider.to_string(), VALIDATOR.to_string())
+            .unwrap()
+            .local
+    };
+    stake(deps.as_mut(), CHANNEL_ID, 1000);
+    stake(deps.as_mut(), CHANNEL_2, 300);
+    assert_eq!(local_stake(deps.as_ref(), CONNECTION_ID), Uint128::new(100));
+    assert_eq!(local_stake(deps.as_ref(), CONNECTION_2), Uint128::new(30));
+
+    // Past its max cap, the stake is refused with an error ack
+    let res = stake(deps.as_mut(), CHANNEL_2, 300);
+    assert_eq!(
+        res.acknowledgement,
+        StdAck::fail(ContractError::MaxCapReached(Uint128::new(50)).to_string())
+    );
+    assert_eq!(res.messages.len(), 0);
+    assert_eq!(local_stake(deps.as_ref(), CONNECTION_2), Uint128::new(30));
+
+    // Rewards are split by the local stake of each provider
+    let timeout = build_timeout(deps.as_ref(), &mock_env()).unwrap();
+    let rewards = |channel_id: &str, amount: u128| -> CosmosMsg {
+        IbcMsg::SendPacket {
+            channel_id: channel_id.to_string(),
+            data: to_binary(&ConsumerMsg::Rewards {
+                validator: VALIDATOR.to_string(),
+                total_funds: coin(amount, NATIVE_DENOM),
+            })
+            .unwrap(),
+            timeout: timeout.clone(),
+        }
+        .into()
+    };
+    let info = mock_info(STAKING_ADDR, &[coin(130, NATIVE_DENOM)]);
+    let res = execute_receive_rewards(deps.as_mut(), info, VALIDATOR).unwrap();
+    let msgs: Vec<_> = res.messages.into_iter().map(|m| m.msg).collect();
+    assert_eq!(msgs, vec![rewards(CHANNEL_ID, 100), rewards(CHANNEL_2, 30)]);
+    let pending = query_pending_rewards(deps.as_ref(), CONNECTION_2.to_string()).unwrap();
+    assert_eq!(pending.rewards, vec![coin(30, NATIVE_DENOM)]);
+
+    // and transferred to the provider that acked them
+    let mut packet = mock_packet(
+        to_binary(&ConsumerMsg::Rewards {
+            validator: VALIDATOR.to_string(),
+            total_funds: coin(30, NATIVE_DENOM),
+        })
+        .unwrap(),
+    );
+    packet.src.channel_id = CHANNEL_2.to_string();
+    let ack = IbcAcknowledgement::new(to_ack_success(RewardsResponse {}));
+    let res = ibc_packet_ack(
+        deps.as_mut(),
+        mock_env(),
+        IbcPacketAckMsg::new(ack, packet, addr!(RELAYER_ADDR)),
+    )
+    .unwrap();
+    assert_eq!(
+        res.messages[0].msg,
+        build_transfer(
+            &mock_env(),
+            "channel-4",
+            "provider2",
+            &coin(30, NATIVE_DENOM),
+            &timeout
+        )
+    );
+}
@@ -1,23 +1,89 @@
-use cosmwasm_std::{testing::mock_env, IbcChannelCloseMsg};
-use mesh_ibc::IBC_APP_VERSION;
-use mesh_testing::{constants::CHANNEL_ID, ibc_helpers::mock_channel};
+use cosmwasm_std::{
+    coin,
+    testing::{mock_env, mock_info},
+    to_binary, Addr, Decimal, DepsMut, IbcChannelCloseMsg, IbcMsg, IbcPacketTimeoutMsg,
+};
+use mesh_apis::ConsumerSudoMsg;
+use mesh_ibc::{ConsumerMsg, IBC_APP_VERSION};
+use mesh_testing::{
+    addr,
+    constants::{CHANNEL_ID, CONNECTION_ID, NATIVE_DENOM, RELAYER_ADDR, VALIDATOR},
+    ibc_helpers::{mock_channel, mock_packet},
+};
 
 use crate::{
-    ibc::ibc_channel_close,
-    testing::utils::{executes::ibc_connect, helpers::get_default_instantiate_msg},
+    contract::{query_unsent_rewards, sudo},
+    ibc::{build_timeout, ibc_channel_close, ibc_packet_timeout},
+    msg::InstantiateMsg,
+    state::CHANNELS,
+    testing::utils::{
+        executes::ibc_connect,
+        helpers::{get_default_instantiate_msg, STAKING_ADDR},
+    },
     ContractError,
 };
 
 use super::utils::{
-    executes::{ibc_close_channel, ibc_open, ibc_open_channel},
+    executes::{
+        execute_receive_rewards, ibc_close_channel, ibc_open, ibc_open_channel,
+        ibc_receive_list_validators, ibc_receive_stake,
+    },
     setup::{setup, setup_with_channel},
 };
 
 #[test]
 fn close_channel() {
     let (mut deps, _) = setup_with_channel(None);
+    ibc_receive_stake(deps.as_mut(), VALIDATOR, 1000, "key").unwrap();
+    let info = mock_info(STAKING_ADDR, &[coin(100, NATIVE_DENOM)]);
+    execute_receive_rewards(deps.as_mut(), info, VALIDATOR).unwrap();
 
     ibc_close_channel(deps.as_mut()).unwrap();
+    let err = ibc_close_channel(deps.as_mut()).unwrap_err();
+    assert_eq!(err, ContractError::UnknownChannel(CHANNEL_ID.to_string()));
+    let err = ibc_receive_list_validators(deps.as_mut()).unwrap_err();
+    assert_eq!(err, ContractError::UnknownChannel(CHANNEL_ID.to_string()));
+
+    // Packets in flight on the closed channel still time out into the retry queues
+    let timeout = |deps: DepsMut, msg: &ConsumerMsg| {
+        let packet = mock_packet(to_binary(msg).unwrap());
+        ibc_packet_timeout(
+            deps,
+            mock_env(),
+            IbcPacketTimeoutMsg::new(packet, addr!(RELAYER_ADDR)),
+        )
+        .unwrap()
+    };
+    let rewards = ConsumerMsg::Rewards {
+        validator: VALIDATOR.to_string(),
+        total_funds: coin(100, NATIVE_DENOM),
+    };
+    timeout(deps.as_mut(), &rewards);
+    let unsent = query_unsent_rewards(deps.as_ref(), CONNECTION_ID.to_string()).unwrap();
+    assert_eq!(unsent.rewards.len(), 1);
+
+    let slash = ConsumerMsg::Slash {
+        validator: VALIDATOR.to_string(),
+        percentage: Decimal::percent(5),
+        infraction_height: 10,
+        tombstone: false,
+    };
+    let res = timeout(deps.as_mut(), &slash);
+    assert_eq!(res.messages.len(), 0);
+
+    // The slash is sent once the provider reconnects
+    let channel = mock_channel(CHANNEL_ID, IBC_APP_VERSION);
+    ibc_open(deps.as_mut(), channel.clone()).unwrap();
+    let res = ibc_connect(deps.as_mut(), channel).unwrap();
+    assert_eq!(
+        res.messages[0].msg,
+        IbcMsg::SendPacket {
+            channel_id: CHANNEL_ID.to_string(),
+            data: to_binary(&slash).unwrap(),
+            timeout: build_timeout(deps.as_ref(), &mock_env()).unwrap(),
+        }
+        .into()
+    );
 }
 
 #[test]
@@ -26,12 +92,15 @@ fn test_wrong_connection() {
     let mut init_msg = get_default_instantiate_msg();
     let channel = mock_channel(CHANNEL_ID, IBC_APP_VERSION);
 
-    // Make sure we detect wrong connection
-    init_msg.provider.connection_id = wrong_connection.clone();
+    // Make sure we detect a connection without provider
+    init_msg.providers[0].connection_id = wrong_connection;
     let (mut deps, _) = setup(Some(init_msg));
     let err = ibc_open(deps.as_mut(), channel).unwrap_err();
 
-    assert_eq!(err, ContractError::WrongConnection(wrong_connection));
+    assert_eq!(
+        err,
+        ContractError::WrongConnection(CONNECTION_ID.to_string())
+    );
 }
 
 #[test]
@@ -40,7 +109,7 @@ fn test_wrong_port() {
     let mut init_msg = get_default_instantiate_msg();
     let channel = mock_channel(CHANNEL_ID, IBC_APP_VERSION);
     // Check we detect wrong port
-    init_msg.provider.port_id = wrong_port.clone();
+    init_msg.providers[0].port_id = wrong_port.clone();
     let (mut deps, _) = setup(Some(init_msg));
     let err = ibc_open(deps.as_mut(), channel).unwrap_err();
 
@@ -70,3 +139,75 @@ fn try_close_wrong_channel() {
 
     assert_eq!(err, ContractError::UnknownChannel(some_channel.to_string()))
 }
+
+#[test]
+fn provider_address_on_connect() {
+    let connect = |init_msg: InstantiateMsg, port_id: &str| {
+        let mut channel = mock_channel(CHANNEL_ID, IBC_APP_VERSION);
+        channel.counterparty_endpoint.port_id = port_id.to_string();
+        let (mut deps, _) = setup(Some(init_msg));
+        ibc_connect(deps.as_mut(), channel)?;
+        Ok::<_, ContractError>(CHANNELS.load(&deps.storage, CONNECTION_ID)?.recipient)
+    };
+    let mut init_msg = get_default_instantiate_msg();
+
+    // The configured address is used as is
+    let addr = connect(init_msg.clone(), "stars.address1").unwrap();
+    assert_eq!(addr, "provider");
+
+    // But must match a wasmd port
+    let err = connect(init_msg.clone(), "wasm.address1").unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::WrongProviderAddr("provider".to_string())
+    );
+
+    // Without an address, the port is only used if allowed
+    init_msg.providers[0].address = None;
+    let err = connect(init_msg.clone(), "wasm.address1").unwrap_err();
+    assert_eq!(err, ContractError::NoProviderAddr {});
+
+    init_msg.providers[0].address_from_port = true;
+    let addr = connect(init_msg.clone(), "wasm.address1").unwrap();
+    assert_eq!(addr, "address1");
+    let err = connect(init_msg, "transfer").unwrap_err();
+    assert_eq!(err, ContractError::ProviderAddrParsing {});
+}
+
+#[test]
+fn slash_before_connect() {
+    let (mut deps, _) = setup(None);
+
+    // No channel yet, the slash waits for the provider
+    let res = sudo(
+        deps.as_mut(),
+        mock_env(),
+        ConsumerSudoMsg::Slash {
+            validator: VALIDATOR.to_string(),
+            percentage: Decimal::percent(5),
+            infraction_height: 10,
+            tombstone: false,
+        },
+    )
+    .unwrap();
+    assert_eq!(res.messages.len(), 0);
+
+    let channel = mock_channel(CHANNEL_ID, IBC_APP_VERSION);
+    ibc_open(deps.as_mut(), channel.clone()).unwrap();
+    let res = ibc_connect(deps.as_mut(), channel).unwrap();
+    assert_eq!(
+        res.messages[0].msg,
+        IbcMsg::SendPacket {
+            channel_id: CHANNEL_ID.to_string(),
+            data: to_binary(&ConsumerMsg::Slash {
+                validator: VALIDATOR.to_string(),
+                percentage: Decimal::percent(5),
+                infraction_height: 10,
+                tombstone: false,
+            })
+            .unwrap(),
+            timeout: build_timeout(deps.as_ref(), &mock_env()).unwrap(),
+        }
+        .into()
+    );
+}
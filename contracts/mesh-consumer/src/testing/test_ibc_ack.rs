@@ -1,13 +1,31 @@
-use cosmwasm_std::{coin, testing::mock_env, IbcAcknowledgement, IbcMsg};
-use mesh_ibc::RewardsResponse;
+use cosmwasm_std::{
+    coin, from_binary,
+    testing::{mock_env, mock_info, MockQuerier},
+    to_binary, Addr, CosmosMsg, Decimal, DepsMut, IbcAcknowledgement, IbcMsg, IbcPacketAckMsg,
+    IbcTimeout, Response, Timestamp, Validator,
+};
+use mesh_apis::ConsumerExecuteMsg;
+use mesh_ibc::{ConsumerMsg, RewardsResponse, UpdateValidatorsResponse};
 use mesh_testing::{
-    constants::{ICS20_CHANNEL_ID, NATIVE_DENOM, REMOTE_PORT, VALIDATOR},
-    ibc_helpers::{to_ack_error, to_ack_success},
+    addr,
+    constants::{CONNECTION_ID, ICS20_CHANNEL_ID, NATIVE_DENOM, RELAYER_ADDR, VALIDATOR},
+    ibc_helpers::{mock_packet, to_ack_error, to_ack_success},
 };
 
-use crate::{ibc::build_timeout, ContractError};
+use crate::{
+    contract::{execute, query_unsent_rewards},
+    ibc::{build_timeout, build_transfer, ibc_packet_ack},
+    msg::UnsentReward,
+};
 
-use super::utils::{executes::ibc_ack_rewards, setup::setup_with_channel};
+use super::utils::{
+    executes::{
+        ibc_ack_rewards, ibc_ack_update_validators, ibc_receive_list_validators,
+        ibc_timeout_update_validators,
+    },
+    helpers::PROVIDER_ADDR,
+    setup::setup_with_channel,
+};
 
 #[test]
 fn test_ibc_ack_rewards() {
@@ -20,36 +38,147 @@ fn test_ibc_ack_rewards() {
 
     assert_eq!(
         res.messages[0].msg,
-        IbcMsg::Transfer {
-            channel_id: ICS20_CHANNEL_ID.to_string(),
-            to_address: REMOTE_PORT
-                .to_string()
-                .split('.')
-                .last()
-                .unwrap()
-                .to_string(), // port - prefix
-            amount: coin(100, NATIVE_DENOM),
-            timeout: build_timeout(deps.as_ref(), &mock_env()).unwrap(),
+        build_transfer(
+            &mock_env(),
+            ICS20_CHANNEL_ID,
+            PROVIDER_ADDR,
+            &coin(100, NATIVE_DENOM),
+            &build_timeout(deps.as_ref(), &mock_env()).unwrap()
+        )
+    );
+
+    // The transfer asks ibc-hooks to call us back with its result
+    let env = mock_env();
+    let timeout = IbcTimeout::with_timestamp(Timestamp::from_nanos(300));
+    let msg = build_transfer(&env, "channel-1", "prov", &coin(7, "ustake"), &timeout);
+    let memo = format!(r#"{{"ibc_callback":"{}"}}"#, env.contract.address);
+    let mut value = b"\n\x08transfer\x12\x09channel-1\x1a\x0b\n\x06ustake\x12\x017\"".to_vec();
+    value.push(env.contract.address.as_str().len() as u8);
+    value.extend_from_slice(env.contract.address.as_bytes());
+    value.extend_from_slice(b"*\x04prov8\xac\x02B");
+    value.push(memo.len() as u8);
+    value.extend_from_slice(memo.as_bytes());
+    assert_eq!(
+        msg,
+        CosmosMsg::Stargate {
+            type_url: "/ibc.applications.transfer.v1.MsgTransfer".to_string(),
+            value: value.into(),
         }
-        .into()
     );
 }
 
-// TODO: update_validators doesn't do anything, should we just delete it?
 #[test]
-#[ignore]
 fn test_ibc_update_validators() {
-    unimplemented!()
+    let (mut deps, _) = setup_with_channel(None);
+    let set_validators = |querier: &mut MockQuerier, validators: &[&str]| {
+        let validators: Vec<_> = validators
+            .iter()
+            .map(|v| Validator {
+                address: v.to_string(),
+                commission: Decimal::percent(5),
+                max_commission: Decimal::one(),
+                max_change_rate: Decimal::one(),
+            })
+            .collect();
+        querier.update_staking(NATIVE_DENOM, &validators, &[]);
+    };
+    let sync = |deps: DepsMut| {
+        execute(
+            deps,
+            mock_env(),
+            mock_info(RELAYER_ADDR, &[]),
+            ConsumerExecuteMsg::SyncValidators {},
+        )
+        .unwrap()
+    };
+    // the added, removed and epoch of the update packet we send
+    let sent = |res: Response| match &res.messages[..] {
+        [msg] => match &msg.msg {
+            CosmosMsg::Ibc(IbcMsg::SendPacket { data, .. }) => match from_binary(data).unwrap() {
+                ConsumerMsg::UpdateValidators {
+                    added,
+                    removed,
+                    epoch,
+                    ..
+                } => (added, removed, epoch),
+                msg => panic!("unexpected packet {:?}", msg),
+            },
+            msg => panic!("unexpected message {:?}", msg),
+        },
+        msgs => panic!("expected one message, got {:?}", msgs),
+    };
+    let strings = |v: &[&str]| v.iter().map(|v| v.to_string()).collect::<Vec<_>>();
+
+    // epoch 1 is listed, epoch 2 swaps the first validator for validator3
+    set_validators(&mut deps.querier, &[VALIDATOR, "validator2"]);
+    ibc_receive_list_validators(deps.as_mut()).unwrap();
+    set_validators(&mut deps.querier, &["validator2", "validator3"]);
+    let res = sync(deps.as_mut());
+    assert_eq!(
+        sent(res),
+        (strings(&["validator3"]), strings(&[VALIDATOR]), 2)
+    );
+
+    // The update times out and validator3 leaves again, so the next sync only removes
+    let res =
+        ibc_timeout_update_validators(deps.as_mut(), &["validator3"], &[VALIDATOR], 2).unwrap();
+    assert_eq!(res.messages.len(), 0);
+    set_validators(&mut deps.querier, &["validator2"]);
+    let res = sync(deps.as_mut());
+    assert_eq!(sent(res), (vec![], strings(&[VALIDATOR, "validator3"]), 3));
+
+    // An error ack is queued the same way and resent with the current epoch
+    let ack = IbcAcknowledgement::new(to_ack_error("Something went wrong"));
+    ibc_ack_update_validators(deps.as_mut(), &[], &[VALIDATOR, "validator3"], 3, ack).unwrap();
+    let res = sync(deps.as_mut());
+    assert_eq!(sent(res), (vec![], strings(&[VALIDATOR, "validator3"]), 3));
+
+    // A provider that lists the whole set again doesn't need the queued update
+    let ack = IbcAcknowledgement::new(to_ack_error("Something went wrong"));
+    ibc_ack_update_validators(deps.as_mut(), &[], &[VALIDATOR], 3, ack).unwrap();
+    ibc_receive_list_validators(deps.as_mut()).unwrap();
+    assert_eq!(sync(deps.as_mut()).messages.len(), 0);
+
+    // A successful ack leaves nothing to resend
+    let ack = IbcAcknowledgement::new(to_ack_success(UpdateValidatorsResponse {}));
+    let res = ibc_ack_update_validators(deps.as_mut(), &[], &[VALIDATOR], 3, ack).unwrap();
+    assert_eq!(res.messages.len(), 0);
+    assert_eq!(sync(deps.as_mut()).messages.len(), 0);
 }
 
-// TODO: on failed ack we should revert stuff based on sent msg,
-// leaving todo here to make the tests better later.
 #[test]
 fn test_ibc_ack_failed() {
     let (mut deps, _) = setup_with_channel(None);
 
-    // We test a error ack
+    // Failed rewards are kept for RetryRewards
+    let ack = IbcAcknowledgement::new(to_ack_error("Something went wrong"));
+    let res = ibc_ack_rewards(deps.as_mut(), VALIDATOR, 100, ack).unwrap();
+    assert_eq!(res.messages.len(), 0);
+    let unsent = query_unsent_rewards(deps.as_ref(), CONNECTION_ID.to_string()).unwrap();
+    assert_eq!(
+        unsent.rewards,
+        vec![UnsentReward {
+            validator: VALIDATOR.to_string(),
+            amount: coin(100, NATIVE_DENOM),
+        }]
+    );
+
+    // A failed update is queued with what still applies, here nothing
     let ack = IbcAcknowledgement::new(to_ack_error("Something went wrong"));
-    let err = ibc_ack_rewards(deps.as_mut(), VALIDATOR, 100, ack).unwrap_err();
-    assert_eq!(err, ContractError::AckFailed {})
+    let packet = mock_packet(
+        to_binary(&ConsumerMsg::UpdateValidators {
+            added: vec![],
+            removed: vec![],
+            epoch: 1,
+            metadata: vec![],
+        })
+        .unwrap(),
+    );
+    let res = ibc_packet_ack(
+        deps.as_mut(),
+        mock_env(),
+        IbcPacketAckMsg::new(ack, packet, addr!(RELAYER_ADDR)),
+    )
+    .unwrap();
+    assert_eq!(res.messages.len(), 0);
 }
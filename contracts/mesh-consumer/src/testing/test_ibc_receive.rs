@@ -1,20 +1,32 @@
+use std::str::FromStr;
+
 use cosmwasm_std::{
-    testing::mock_env, to_binary, Addr, Decimal, IbcPacketReceiveMsg, Uint128, Validator, WasmMsg,
+    from_binary, testing::mock_env, to_binary, Addr, ContractResult, Decimal, IbcPacketReceiveMsg,
+    IbcReceiveResponse, Reply, ReplyOn, SubMsgResult, SystemResult, Uint128, Validator, WasmMsg,
+    WasmQuery,
+};
+use mesh_apis::{Delegation, StakingExecuteMsg, StakingQueryMsg};
+use mesh_ibc::{
+    ListValidatorsResponse, ProviderMsg, StakeResponse, StdAck, SyncStateResponse, UnstakeResponse,
+    ValidatorMetadata, ValidatorStake,
 };
-use mesh_apis::StakingExecuteMsg;
-use mesh_ibc::{ListValidatorsResponse, ProviderMsg, StakeResponse, UnstakeResponse};
 use mesh_testing::{
     addr,
-    constants::{NATIVE_DENOM, RELAYER_ADDR, VALIDATOR},
+    constants::{CONNECTION_ID, NATIVE_DENOM, RELAYER_ADDR, VALIDATOR},
     ibc_helpers::{ack_unwrap, mock_packet},
 };
 
-use crate::{ibc::ibc_packet_receive, ContractError};
+use crate::{
+    contract::{query_virtual_stake, reply},
+    ibc::ibc_packet_receive,
+    msg::VirtualStakeResponse,
+    ContractError,
+};
 
 use super::utils::{
     executes::ibc_receive_list_validators,
-    executes::{ibc_receive_stake, ibc_receive_unstake},
-    helpers::STAKING_ADDR,
+    executes::{ibc_receive_stake, ibc_receive_sync_state, ibc_receive_unstake},
+    helpers::{get_price_feed_instantiate_msg, set_price, STAKING_ADDR},
     setup::setup_with_channel,
 };
 
@@ -27,7 +39,7 @@ fn test_ibc_receive_list_validators() {
         NATIVE_DENOM,
         &[Validator {
             address: VALIDATOR.to_string(),
-            commission: Decimal::zero(),
+            commission: Decimal::percent(5),
             max_commission: Decimal::one(),
             max_change_rate: Decimal::one(),
         }],
@@ -38,6 +50,29 @@ fn test_ibc_receive_list_validators() {
     let ack_res: ListValidatorsResponse = ack_unwrap(res.acknowledgement);
 
     assert_eq!(ack_res.validators, vec![VALIDATOR.to_string()],);
+    assert_eq!(ack_res.epoch, 1);
+    assert_eq!(
+        ack_res.metadata,
+        vec![ValidatorMetadata {
+            address: VALIDATOR.to_string(),
+            commission: Decimal::percent(5),
+            max_commission: Decimal::one(),
+            max_change_rate: Decimal::one(),
+            jailed: false,
+        }]
+    );
+
+    // Same set, same epoch
+    let res = ibc_receive_list_validators(deps.as_mut()).unwrap();
+    let ack_res: ListValidatorsResponse = ack_unwrap(res.acknowledgement);
+    assert_eq!(ack_res.epoch, 1);
+
+    // The set changed, so the epoch is bumped
+    deps.querier.update_staking(NATIVE_DENOM, &[], &[]);
+    let res = ibc_receive_list_validators(deps.as_mut()).unwrap();
+    let ack_res: ListValidatorsResponse = ack_unwrap(res.acknowledgement);
+    assert_eq!(ack_res.validators, Vec::<String>::new());
+    assert_eq!(ack_res.epoch, 2);
 }
 
 #[test]
@@ -68,6 +103,15 @@ fn test_ibc_receive_stake() {
 fn test_ibc_receive_unstake() {
     let (mut deps, _) = setup_with_channel(None);
 
+    // Can't unstake more than the provider staked
+    let res = ibc_receive_unstake(deps.as_mut(), VALIDATOR, 1000, "key_1").unwrap();
+    assert_eq!(
+        res.acknowledgement,
+        StdAck::fail(ContractError::InsufficientStake(Uint128::zero()).to_string())
+    );
+    assert_eq!(res.messages.len(), 0);
+
+    ibc_receive_stake(deps.as_mut(), VALIDATOR, 1000, "key_1").unwrap();
     let res = ibc_receive_unstake(deps.as_mut(), VALIDATOR, 1000, "key_1").unwrap();
 
     // Verify ack is success
@@ -105,3 +149,166 @@ fn test_ibc_receive_wrong_channel() {
         ContractError::UnknownChannel("some_channel".to_string())
     )
 }
+
+#[test]
+fn test_ibc_receive_sync_state() {
+    let (mut deps, _) = setup_with_channel(None);
+    ibc_receive_stake(deps.as_mut(), VALIDATOR, 1000, "key").unwrap();
+
+    // meta-staking holds 100 local tokens for us
+    deps.querier.update_wasm(|query| match query {
+        WasmQuery::Smart { contract_addr, msg } if contract_addr == STAKING_ADDR => {
+            let msg: StakingQueryMsg = from_binary(msg).unwrap();
+            assert_eq!(
+                msg,
+                StakingQueryMsg::AllDelegations {
+                    consumer: mock_env().contract.address.into_string()
+                }
+            );
+            let delegations = vec![Delegation {
+                validator: VALIDATOR.to_string(),
+                delegation: Uint128::new(100),
+            }];
+            SystemResult::Ok(ContractResult::Ok(to_binary(&delegations).unwrap()))
+        }
+        _ => panic!("unexpected query"),
+    });
+
+    let res = ibc_receive_sync_state(deps.as_mut()).unwrap();
+    let ack_res: SyncStateResponse = ack_unwrap(res.acknowledgement);
+
+    // The stake is reported in remote tokens, at the rate it came in (delegation / 0.1)
+    assert_eq!(
+        ack_res.validators,
+        vec![ValidatorStake {
+            validator: VALIDATOR.to_string(),
+            stake: Uint128::new(1000),
+        }]
+    );
+}
+
+#[test]
+fn test_ibc_receive_stake_with_price_feed() {
+    let (mut deps, _) = setup_with_channel(Some(get_price_feed_instantiate_msg()));
+    let delegated = |res: IbcReceiveResponse, undelegate: bool, amount: u128| {
+        let msg = if undelegate {
+            StakingExecuteMsg::Undelegate {
+                validator: VALIDATOR.to_string(),
+                amount: Uint128::new(amount),
+            }
+        } else {
+            StakingExecuteMsg::Delegate {
+                validator: VALIDATOR.to_string(),
+                amount: Uint128::new(amount),
+            }
+        };
+        assert_eq!(
+            res.messages[0].msg,
+            WasmMsg::Execute {
+                contract_addr: STAKING_ADDR.to_string(),
+                msg: to_binary(&msg).unwrap(),
+                funds: vec![],
+            }
+            .into()
+        );
+    };
+
+    // 100 PROV * 18 CONS/PROV * (1 - 0.4) = 1080 CONS
+    set_price(&mut deps.querier, 18, mock_env().block.time);
+    let res = ibc_receive_stake(deps.as_mut(), VALIDATOR, 100, "key_1").unwrap();
+    delegated(res, false, 1080);
+
+    let position = query_virtual_stake(
+        deps.as_ref(),
+        CONNECTION_ID.to_string(),
+        VALIDATOR.to_string(),
+    )
+    .unwrap();
+    assert_eq!(
+        position,
+        VirtualStakeResponse {
+            validator: VALIDATOR.to_string(),
+            remote: Uint128::new(100),
+            local: Uint128::new(1080),
+            rate: Some(Decimal::from_str("10.8").unwrap()),
+        }
+    );
+
+    // Unstaking uses the rate the stake came in at, not the current price
+    set_price(&mut deps.querier, 20, mock_env().block.time);
+    let res = ibc_receive_unstake(deps.as_mut(), VALIDATOR, 50, "key_1").unwrap();
+    delegated(res, true, 540);
+    let position = query_virtual_stake(
+        deps.as_ref(),
+        CONNECTION_ID.to_string(),
+        VALIDATOR.to_string(),
+    )
+    .unwrap();
+    assert_eq!(position.remote, Uint128::new(50));
+    assert_eq!(position.local, Uint128::new(540));
+
+    // A stale price is refused with an error ack
+    let updated_at = mock_env().block.time.minus_seconds(3601);
+    set_price(&mut deps.querier, 18, updated_at);
+    let res = ibc_receive_stake(deps.as_mut(), VALIDATOR, 100, "key_1").unwrap();
+    assert_eq!(
+        res.acknowledgement,
+        StdAck::fail(ContractError::StalePrice(updated_at).to_string())
+    );
+    assert_eq!(res.messages.len(), 0);
+
+    // and so is a failing oracle
+    deps.querier
+        .update_wasm(|_| SystemResult::Ok(ContractResult::Err("oracle down".to_string())));
+    let res = ibc_receive_stake(deps.as_mut(), VALIDATOR, 100, "key_1").unwrap();
+    assert!(!from_binary::<StdAck>(&res.acknowledgement).unwrap().is_ok());
+}
+
+#[test]
+fn test_ibc_receive_stake_meta_staking_fails() {
+    let (mut deps, _) = setup_with_channel(None);
+    let failed_reply = |id: u64| Reply {
+        id,
+        result: SubMsgResult::Err("No funds to delegate".to_string()),
+    };
+
+    ibc_receive_stake(deps.as_mut(), VALIDATOR, 1000, "key_1").unwrap();
+    let res = ibc_receive_stake(deps.as_mut(), VALIDATOR, 500, "key_1").unwrap();
+    assert_eq!(res.messages[0].reply_on, ReplyOn::Error);
+
+    // meta-staking failed, the ack becomes an error and the position is restored
+    let res = reply(deps.as_mut(), mock_env(), failed_reply(res.messages[0].id)).unwrap();
+    let err = ContractError::DelegateFailed("No funds to delegate".to_string());
+    assert_eq!(res.data, Some(StdAck::fail(err.to_string())));
+    let position = query_virtual_stake(
+        deps.as_ref(),
+        CONNECTION_ID.to_string(),
+        VALIDATOR.to_string(),
+    )
+    .unwrap();
+    assert_eq!(position.remote, Uint128::new(1000));
+    assert_eq!(position.local, Uint128::new(100));
+
+    let res = ibc_receive_unstake(deps.as_mut(), VALIDATOR, 400, "key_1").unwrap();
+    let position = query_virtual_stake(
+        deps.as_ref(),
+        CONNECTION_ID.to_string(),
+        VALIDATOR.to_string(),
+    )
+    .unwrap();
+    assert_eq!(position.remote, Uint128::new(600));
+    let res = reply(deps.as_mut(), mock_env(), failed_reply(res.messages[0].id)).unwrap();
+    let err = ContractError::UndelegateFailed("No funds to delegate".to_string());
+    assert_eq!(res.data, Some(StdAck::fail(err.to_string())));
+    let position = query_virtual_stake(
+        deps.as_ref(),
+        CONNECTION_ID.to_string(),
+        VALIDATOR.to_string(),
+    )
+    .unwrap();
+    assert_eq!(position.remote, Uint128::new(1000));
+    assert_eq!(position.local, Uint128::new(100));
+
+    let err = reply(deps.as_mut(), mock_env(), failed_reply(99)).unwrap_err();
+    assert_eq!(err, ContractError::InvalidReplyId(99));
+}
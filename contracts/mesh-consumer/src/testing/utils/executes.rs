@@ -3,7 +3,7 @@ use cosmwasm_std::{
     testing::{mock_env, mock_info},
     to_binary, Addr, DepsMut, Empty, Ibc3ChannelOpenResponse, IbcAcknowledgement, IbcBasicResponse,
     IbcChannel, IbcChannelCloseMsg, IbcChannelConnectMsg, IbcChannelOpenMsg, IbcPacketAckMsg,
-    IbcPacketReceiveMsg, IbcReceiveResponse, MessageInfo, Response, Uint128,
+    IbcPacketReceiveMsg, IbcPacketTimeoutMsg, IbcReceiveResponse, MessageInfo, Response, Uint128,
 };
 use mesh_apis::ConsumerExecuteMsg;
 use mesh_ibc::{ConsumerMsg, ProviderMsg, IBC_APP_VERSION};
@@ -17,7 +17,7 @@ use crate::{
     contract::{execute, instantiate},
     ibc::{
         ibc_channel_close, ibc_channel_connect, ibc_channel_open, ibc_packet_ack,
-        ibc_packet_receive,
+        ibc_packet_receive, ibc_packet_timeout,
     },
     msg::InstantiateMsg,
     ContractError,
@@ -136,6 +136,16 @@ pub fn ibc_receive_unstake(
     )
 }
 
+pub fn ibc_receive_sync_state(deps: DepsMut) -> Result<IbcReceiveResponse, ContractError> {
+    let packet = mock_packet(to_binary(&ProviderMsg::SyncState {}).unwrap());
+
+    ibc_packet_receive(
+        deps,
+        mock_env(),
+        IbcPacketReceiveMsg::new(packet, addr!(RELAYER_ADDR)),
+    )
+}
+
 pub fn ibc_ack_rewards(
     deps: DepsMut,
     validator: &str,
@@ -157,4 +167,49 @@ pub fn ibc_ack_rewards(
     )
 }
 
-pub fn _ibc_ack_update_validators() {}
+pub fn ibc_ack_update_validators(
+    deps: DepsMut,
+    added: &[&str],
+    removed: &[&str],
+    epoch: u64,
+    ack: IbcAcknowledgement,
+) -> Result<IbcBasicResponse, ContractError> {
+    let original_packet = mock_packet(
+        to_binary(&ConsumerMsg::UpdateValidators {
+            added: added.iter().map(|v| v.to_string()).collect(),
+            removed: removed.iter().map(|v| v.to_string()).collect(),
+            epoch,
+            metadata: vec![],
+        })
+        .unwrap(),
+    );
+
+    ibc_packet_ack(
+        deps,
+        mock_env(),
+        IbcPacketAckMsg::new(ack, original_packet, addr!(RELAYER_ADDR)),
+    )
+}
+
+pub fn ibc_timeout_update_validators(
+    deps: DepsMut,
+    added: &[&str],
+    removed: &[&str],
+    epoch: u64,
+) -> Result<IbcBasicResponse, ContractError> {
+    let packet = mock_packet(
+        to_binary(&ConsumerMsg::UpdateValidators {
+            added: added.iter().map(|v| v.to_string()).collect(),
+            removed: removed.iter().map(|v| v.to_string()).collect(),
+            epoch,
+            metadata: vec![],
+        })
+        .unwrap(),
+    );
+
+    ibc_packet_timeout(
+        deps,
+        mock_env(),
+        IbcPacketTimeoutMsg::new(packet, addr!(RELAYER_ADDR)),
+    )
+}
@@ -1,21 +1,68 @@
 use std::str::FromStr;
 
-use cosmwasm_std::Decimal;
+use cosmwasm_std::{
+    testing::MockQuerier, to_binary, ContractResult, Decimal, SystemResult, Timestamp, WasmQuery,
+};
+use mesh_apis::PriceResponse;
 use mesh_testing::constants::{CONNECTION_ID, ICS20_CHANNEL_ID, REMOTE_PORT};
 
-use crate::msg::{InstantiateMsg, ProviderInfo};
+use crate::msg::{InstantiateMsg, PriceFeedInfo, ProviderInfo, RebalanceInfo};
 
 pub const STAKING_ADDR: &str = "meta_staking";
+pub const ORACLE_ADDR: &str = "oracle";
+pub const PROVIDER_ADDR: &str = "provider";
 
 pub fn get_default_instantiate_msg() -> InstantiateMsg {
     InstantiateMsg {
-        provider: ProviderInfo {
-            port_id: REMOTE_PORT.to_string(),
-            connection_id: CONNECTION_ID.to_string(),
-        },
+        providers: vec![get_provider_info()],
         remote_to_local_exchange_rate: Decimal::from_str("0.1").unwrap(),
         meta_staking_contract_address: STAKING_ADDR.to_string(),
-        ics20_channel: ICS20_CHANNEL_ID.to_string(),
         packet_lifetime: None,
+        rebalance: None,
+        rewards_batch_interval: None,
+    }
+}
+
+pub fn get_provider_info() -> ProviderInfo {
+    ProviderInfo {
+        port_id: REMOTE_PORT.to_string(),
+        connection_id: CONNECTION_ID.to_string(),
+        address: Some(PROVIDER_ADDR.to_string()),
+        address_from_port: false,
+        ics20_channel: ICS20_CHANNEL_ID.to_string(),
+        price_feed: None,
+        max_cap: None,
     }
 }
+
+pub fn get_price_feed_instantiate_msg() -> InstantiateMsg {
+    InstantiateMsg {
+        providers: vec![ProviderInfo {
+            price_feed: Some(PriceFeedInfo {
+                oracle: ORACLE_ADDR.to_string(),
+                discount: Decimal::percent(40),
+                max_staleness: 3600,
+            }),
+            ..get_provider_info()
+        }],
+        rebalance: Some(RebalanceInfo {
+            epoch: 86400,
+            max_adjustment: Decimal::percent(10),
+        }),
+        ..get_default_instantiate_msg()
+    }
+}
+
+/// Makes the oracle answer with this price
+pub fn set_price(querier: &mut MockQuerier, price: u64, updated_at: Timestamp) {
+    querier.update_wasm(move |query| match query {
+        WasmQuery::Smart { contract_addr, .. } if contract_addr == ORACLE_ADDR => {
+            let res = PriceResponse {
+                price: Decimal::from_atomics(price, 0).unwrap(),
+                updated_at,
+            };
+            SystemResult::Ok(ContractResult::Ok(to_binary(&res).unwrap()))
+        }
+        _ => panic!("unexpected query"),
+    });
+}
@@ -1,15 +1,17 @@
 #[cfg(not(feature = "library"))]
 use cosmwasm_std::entry_point;
 use cosmwasm_std::{
-    to_binary, BankMsg, Binary, Coin, Deps, DepsMut, Env, MessageInfo, Response, StdResult,
-    Uint128, WasmMsg,
+    to_binary, BankMsg, Binary, Coin, Deps, DepsMut, Env, MessageInfo, Response, StdError,
+    StdResult, Uint128, WasmMsg,
 };
 use cw2::set_contract_version;
 use cw_utils::{must_pay, nonpayable};
 
 use crate::error::ContractError;
-use crate::msg::{BalanceResponse, ExecuteMsg, InstantiateMsg, QueryMsg};
-use crate::state::{Config, BALANCES, CONFIG};
+use crate::msg::{
+    BalanceResponse, ExecuteMsg, InstantiateMsg, QueryMsg, SlashDestination, SlashedResponse,
+};
+use crate::state::{Config, BALANCES, CONFIG, SLASHED};
 use mesh_apis::ClaimReceiverMsg;
 
 // version info for migration info
@@ -25,8 +27,19 @@ pub fn instantiate(
 ) -> Result<Response, ContractError> {
     set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
 
-    let cfg = Config { denom: msg.denom };
+    match &msg.slash_destination {
+        SlashDestination::Burn => {}
+        SlashDestination::CommunityPool { address }
+        | SlashDestination::InsuranceFund { address } => {
+            deps.api.addr_validate(address)?;
+        }
+    }
+    let cfg = Config {
+        denom: msg.denom,
+        slash_destination: msg.slash_destination,
+    };
     CONFIG.save(deps.storage, &cfg)?;
+    SLASHED.save(deps.storage, &Uint128::zero())?;
 
     Ok(Response::new()
         .add_attribute("method", "instantiate")
@@ -160,19 +173,43 @@ pub fn execute_slash_claim(
     let owner = deps.api.addr_validate(&owner)?;
 
     // ensure we have balance for this, and update count
-    BALANCES.update::<_, ContractError>(deps.storage, &owner, |bal| {
-        let mut bal = bal.unwrap_or_default();
-        bal.slash_claim(&info.sender, amount)?;
-        Ok(bal)
-    })?;
+    let mut bal = BALANCES.may_load(deps.storage, &owner)?.unwrap_or_default();
+    let bonded = bal.bonded;
+    bal.slash_claim(&info.sender, amount)?;
+    BALANCES.save(deps.storage, &owner, &bal)?;
 
-    Ok(Response::new())
+    // bonded never goes below zero, so we may slash less than asked for
+    let slashed = bonded - bal.bonded;
+    if slashed.is_zero() {
+        return Ok(Response::new());
+    }
+    SLASHED.update::<_, StdError>(deps.storage, |total| Ok(total + slashed))?;
+
+    let cfg = CONFIG.load(deps.storage)?;
+    let amount = vec![Coin {
+        denom: cfg.denom,
+        amount: slashed,
+    }];
+    let msg = match cfg.slash_destination {
+        SlashDestination::Burn => BankMsg::Burn { amount },
+        SlashDestination::CommunityPool { address }
+        | SlashDestination::InsuranceFund { address } => BankMsg::Send {
+            to_address: address,
+            amount,
+        },
+    };
+
+    Ok(Response::new()
+        .add_message(msg)
+        .add_attribute("action", "slash_claim")
+        .add_attribute("slashed", slashed))
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
     match msg {
         QueryMsg::Balance { account } => to_binary(&query_balance(deps, account)?),
+        QueryMsg::Slashed {} => to_binary(&query_slashed(deps)?),
     }
 }
 
@@ -187,6 +224,13 @@ pub fn query_balance(deps: Deps, account: String) -> StdResult<BalanceResponse>
     })
 }
 
+pub fn query_slashed(deps: Deps) -> StdResult<SlashedResponse> {
+    Ok(SlashedResponse {
+        total: SLASHED.load(deps.storage)?,
+        destination: CONFIG.load(deps.storage)?.slash_destination,
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -201,6 +245,7 @@ mod tests {
 
         let msg = InstantiateMsg {
             denom: DENOM.to_string(),
+            slash_destination: SlashDestination::Burn,
         };
         let info = mock_info("creator", &coins(1000, "earth"));
 
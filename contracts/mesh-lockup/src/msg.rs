@@ -5,6 +5,23 @@ use cosmwasm_std::Uint128;
 #[cw_serde]
 pub struct InstantiateMsg {
     pub denom: String,
+    /// Where slashed collateral goes, burned by default
+    #[serde(default)]
+    pub slash_destination: SlashDestination,
+}
+
+#[cw_serde]
+#[derive(Default)]
+pub enum SlashDestination {
+    #[default]
+    Burn,
+    CommunityPool {
+        address: String,
+    },
+    /// Insurance fund contract, receives the tokens with a plain bank send
+    InsuranceFund {
+        address: String,
+    },
 }
 
 #[cw_serde]
@@ -33,6 +50,15 @@ pub enum ExecuteMsg {
 pub enum QueryMsg {
     #[returns(BalanceResponse)]
     Balance { account: String },
+    /// Total collateral slashed so far and where it went
+    #[returns(SlashedResponse)]
+    Slashed {},
+}
+
+#[cw_serde]
+pub struct SlashedResponse {
+    pub total: Uint128,
+    pub destination: SlashDestination,
 }
 
 #[cw_serde]
@@ -1,4 +1,5 @@
 use super::suite::SuiteBuilder;
+use crate::msg::SlashDestination;
 use crate::multitest::suite::Suite;
 
 #[test]
@@ -120,4 +121,39 @@ fn slashing_tokens() {
     suite.unbond(actor, start - slash + 1).unwrap_err();
     suite.unbond(actor, start - slash).unwrap();
     assert_eq!(suite.balance(actor).unwrap().u128(), start - slash);
+
+    // slashed tokens were burned
+    let slashed = suite.slashed().unwrap();
+    assert_eq!(slashed.total.u128(), slash);
+    assert_eq!(slashed.destination, SlashDestination::Burn);
+    assert_eq!(
+        suite.balance(suite.lockup_contract.clone()).unwrap().u128(),
+        0
+    );
+}
+
+#[test]
+fn slashed_tokens_go_to_community_pool() {
+    let actor = "jakub";
+    let pool = "community_pool";
+    let start = 1234000u128;
+
+    let mut suite: Suite = SuiteBuilder::new()
+        .with_funds(actor, start)
+        .with_slash_destination(SlashDestination::CommunityPool {
+            address: pool.to_string(),
+        })
+        .build();
+
+    suite.bond(actor, start).unwrap();
+    suite.grant_claim(actor, 500_000, "val").unwrap();
+    suite.slash_claim(actor, 200_000).unwrap();
+    suite.slash_claim(actor, 100_000).unwrap();
+
+    assert_eq!(suite.balance(pool).unwrap().u128(), 300_000);
+    assert_eq!(
+        suite.balance(suite.lockup_contract.clone()).unwrap().u128(),
+        start - 300_000
+    );
+    assert_eq!(suite.slashed().unwrap().total.u128(), 300_000);
 }
@@ -5,7 +5,9 @@ use cosmwasm_std::{coin, coins, Addr, Empty, StdResult, Uint128};
 use cw_multi_test::{App, AppBuilder, AppResponse, Contract, ContractWrapper, Executor};
 
 use super::mock_grantee::contract_mock;
-use crate::msg::{BalanceResponse, ExecuteMsg, InstantiateMsg, QueryMsg};
+use crate::msg::{
+    BalanceResponse, ExecuteMsg, InstantiateMsg, QueryMsg, SlashDestination, SlashedResponse,
+};
 
 pub fn contract_lockup() -> Box<dyn Contract<Empty>> {
     let contract = ContractWrapper::new(
@@ -22,6 +24,7 @@ pub struct SuiteBuilder {
     funds: Vec<(Addr, u128)>,
     #[derivative(Default(value = "\"uosmo\".to_owned()"))]
     denom: String,
+    slash_destination: SlashDestination,
 }
 
 impl SuiteBuilder {
@@ -36,6 +39,11 @@ impl SuiteBuilder {
         self
     }
 
+    pub fn with_slash_destination(mut self, slash_destination: SlashDestination) -> Self {
+        self.slash_destination = slash_destination;
+        self
+    }
+
     #[track_caller]
     pub fn build(self) -> Suite {
         let denom = self.denom;
@@ -59,6 +67,7 @@ impl SuiteBuilder {
                 owner.clone(),
                 &InstantiateMsg {
                     denom: denom.clone(),
+                    slash_destination: self.slash_destination,
                 },
                 &[],
                 "lockup demo",
@@ -175,6 +184,12 @@ impl Suite {
         )
     }
 
+    pub fn slashed(&self) -> StdResult<SlashedResponse> {
+        self.app
+            .wrap()
+            .query_wasm_smart(self.lockup_contract.clone(), &QueryMsg::Slashed {})
+    }
+
     pub fn balance(&self, account: impl Into<String>) -> StdResult<Uint128> {
         Ok(self.app.wrap().query_balance(account, &self.denom)?.amount)
     }
@@ -1,12 +1,14 @@
 use cosmwasm_schema::cw_serde;
 use cosmwasm_std::{Addr, Uint128};
 
+use crate::msg::SlashDestination;
 use crate::ContractError;
 use cw_storage_plus::{Item, Map};
 
 #[cw_serde]
 pub struct Config {
     pub denom: String,
+    pub slash_destination: SlashDestination,
 }
 
 #[cw_serde]
@@ -107,6 +109,8 @@ impl Balance {
 
 pub const CONFIG: Item<Config> = Item::new("config");
 pub const BALANCES: Map<&Addr, Balance> = Map::new("balances");
+// running total of slashed collateral
+pub const SLASHED: Item<Uint128> = Item::new("slashed");
 
 #[cfg(test)]
 mod tests {
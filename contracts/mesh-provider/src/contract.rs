@@ -1,24 +1,37 @@
+use std::collections::BTreeMap;
+
 #[cfg(not(feature = "library"))]
 use cosmwasm_std::entry_point;
 use cosmwasm_std::{
-    coin, ensure_eq, to_binary, BankMsg, Binary, Decimal, Deps, DepsMut, Env, IbcMsg, MessageInfo,
-    Order, Reply, Response, StdResult, SubMsg, SubMsgResponse, Uint128, WasmMsg,
+    coin, ensure_eq, to_binary, Addr, BankMsg, Binary, Decimal, Deps, DepsMut, Env, IbcMsg,
+    MessageInfo, Order, Reply, Response, StdError, StdResult, Storage, SubMsg, SubMsgResponse,
+    Uint128, WasmMsg,
 };
 use cw2::set_contract_version;
 use cw_storage_plus::Bound;
 use cw_utils::parse_instantiate_response_data;
-use mesh_apis::ClaimProviderMsg;
+use mesh_apis::{ClaimProviderMsg, LockupBalanceResponse, LockupQueryMsg};
 use mesh_ibc::ProviderMsg;
 
 use crate::error::ContractError;
-use crate::ibc::build_timeout;
+use crate::ibc::{build_timeout, fail_stake, fail_unstake, retry_packet};
 use crate::msg::{
-    AccountResponse, ConfigResponse, ExecuteMsg, InstantiateMsg, ListValidatorsResponse, QueryMsg,
-    StakeInfo, ValidatorResponse,
+    AccountResponse, AccountSlash, AccountSlashesResponse, ChannelResponse, ConfigResponse,
+    ConfigUpdate, ExecuteMsg, InstantiateMsg, ListValidatorsResponse, PausedResponse,
+    PortfolioResponse, Position, QueryMsg, RetriesResponse, RetryInfo, SlashInfo,
+    SlashingHistoryResponse, StakeCaps, StakeInfo, StateDriftResponse, TotalPowerAtHeightResponse,
+    UtilizationResponse, ValidatorDrift, ValidatorResponse, ValidatorUtilization,
+    VotingPowerAtHeightResponse, WithdrawAddressResponse,
 };
 use crate::state::{
-    Config, ValStatus, Validator, CHANNEL, CLAIMS, CONFIG, LIST_VALIDATORS_MAX_RETRIES,
-    LIST_VALIDATORS_RETRIES, PACKET_LIFETIME, STAKED, VALIDATORS,
+    assert_not_paused, take_account_slash, total_stake, untaken_slashes, update_voting_power,
+    validator_stake, withdraw_address, Config, Operation, RetryStatus, SlashEvent, ValStatus,
+    Validator, ACCOUNT_SLASHES, ADMIN, CHANNEL, CHANNEL_CLOSED_AT, CHANNEL_RECOVERY_PERIOD, CLAIMS,
+    CONFIG, CONSUMER_STAKE, EXITED_STAKE, FORCE_UNBOND, GUARDIAN, LAST_STATE_SYNC,
+    LIST_VALIDATORS_MAX_RETRIES, LIST_VALIDATORS_RETRIES, OWNER_RETRIES, PACKET_LIFETIME, PAUSED,
+    PENDING_ADMIN, PENDING_STAKE, PROCESS_SLASH, RETRIES, RETRY_POLICY, SLASHES, SLASH_COUNTER,
+    STAKED, STAKE_CAPS, STATE_DRIFT, TOTAL_POWER, VALIDATORS, VALIDATOR_METADATA,
+    VALIDATOR_STAKERS, VOTING_POWER, WITHDRAW_ADDRESSES,
 };
 
 // version info for migration info
@@ -31,9 +44,12 @@ const INIT_CALLBACK_ID: u64 = 1;
 // Default packet life time = 1 hour
 const DEFAULT_PACKET_LIFETIME: u64 = 60 * 60;
 
+// evidence of the slash recorded when correcting a state drift
+pub const STATE_DRIFT_EVIDENCE: &str = "state drift correction";
+
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn instantiate(
-    deps: DepsMut,
+    mut deps: DepsMut,
     env: Env,
     info: MessageInfo,
     msg: InstantiateMsg,
@@ -47,6 +63,24 @@ pub fn instantiate(
         rewards_ibc_denom: msg.rewards_ibc_denom,
     };
     CONFIG.save(deps.storage, &state)?;
+    let admin = match msg.admin {
+        Some(admin) => deps.api.addr_validate(&admin)?,
+        None => info.sender.clone(),
+    };
+    ADMIN.set(deps.branch(), Some(admin))?;
+    let guardian = msg
+        .guardian
+        .map(|guardian| deps.api.addr_validate(&guardian))
+        .transpose()?;
+    GUARDIAN.set(deps.branch(), guardian)?;
+    let stake_caps = msg.stake_caps.unwrap_or_default();
+    validate_stake_caps(&stake_caps)?;
+    STAKE_CAPS.save(deps.storage, &stake_caps)?;
+    CHANNEL_RECOVERY_PERIOD.save(
+        deps.storage,
+        &msg.channel_recovery_period.unwrap_or(msg.unbonding_period),
+    )?;
+    RETRY_POLICY.save(deps.storage, &msg.retry_policy.unwrap_or_default())?;
     LIST_VALIDATORS_RETRIES.save(deps.storage, &LIST_VALIDATORS_MAX_RETRIES)?;
 
     // Set packet time from msg or set default
@@ -105,12 +139,45 @@ pub fn execute(
             validator,
             percentage,
             force_unbond,
-        } => execute_slash(deps, info, env, validator, percentage, force_unbond),
+            evidence,
+        } => execute_slash(
+            deps,
+            info,
+            env,
+            validator,
+            percentage,
+            force_unbond,
+            evidence,
+        ),
         ExecuteMsg::Unstake { amount, validator } => {
             execute_unstake(deps, info, env, validator, amount)
         }
         ExecuteMsg::Unbond {} => execute_unbond(deps, info, env),
         ExecuteMsg::ClaimRewards { validator } => execute_claim_rewards(deps, env, info, validator),
+        ExecuteMsg::ProcessSlash {
+            validator,
+            start_after,
+            limit,
+        } => execute_process_slash(deps, env, validator, start_after, limit),
+        ExecuteMsg::ForceUnbondValidator { validator } => {
+            execute_force_unbond_validator(deps, env, validator)
+        }
+        ExecuteMsg::UpdateConfig(update) => execute_update_config(deps, info, update),
+        ExecuteMsg::TransferAdmin { new_admin } => execute_transfer_admin(deps, info, new_admin),
+        ExecuteMsg::AcceptAdmin {} => execute_accept_admin(deps, info),
+        ExecuteMsg::UpdateGuardian { guardian } => execute_update_guardian(deps, info, guardian),
+        ExecuteMsg::Pause { operations } => execute_pause(deps, info, operations),
+        ExecuteMsg::Unpause { operations } => execute_unpause(deps, info, operations),
+        ExecuteMsg::ExitStake {} => execute_exit_stake(deps, info, env),
+        ExecuteMsg::RefreshValidators {} => execute_refresh_validators(deps, env),
+        ExecuteMsg::SyncState {} => execute_sync_state(deps, env),
+        ExecuteMsg::CorrectStateDrift { validator } => {
+            execute_correct_state_drift(deps, env, info, validator)
+        }
+        ExecuteMsg::RetryPending { key } => execute_retry_pending(deps, env, key),
+        ExecuteMsg::SetWithdrawAddress { address } => {
+            execute_set_withdraw_address(deps, info, address)
+        }
     }
 }
 
@@ -124,16 +191,26 @@ pub fn execute_receive_claim(
 ) -> Result<Response, ContractError> {
     let cfg = CONFIG.load(deps.storage)?;
     ensure_eq!(cfg.lockup, info.sender, ContractError::Unauthorized);
+    assert_not_paused(deps.storage, Operation::Staking)?;
     let owner = deps.api.addr_validate(&owner)?;
 
     if amount.is_zero() {
         return Err(ContractError::ZeroAmount);
     }
 
-    // Verify we have this validator, if not we can't continue.
-    if !VALIDATORS.has(deps.storage, &validator) {
-        return Err(ContractError::UnknownValidator(validator));
+    // Verify we have this validator and it is active, if not we can't continue.
+    let val = VALIDATORS
+        .may_load(deps.storage, &validator)?
+        .ok_or_else(|| ContractError::UnknownValidator(validator.clone()))?;
+    if val.status != ValStatus::Active {
+        return Err(ContractError::RemovedValidator(validator));
     }
+    check_stake_caps(deps.as_ref(), &validator, &val, amount)?;
+
+    // track the stake until the consumer acks it, so it counts for the caps
+    PENDING_STAKE.update::<_, ContractError>(deps.storage, &validator, |pending| {
+        Ok(pending.unwrap_or_default() + amount)
+    })?;
 
     // send out IBC packet for staking change, update contract state on ack
     let packet = ProviderMsg::Stake {
@@ -149,32 +226,129 @@ pub fn execute_receive_claim(
     Ok(Response::new().add_message(msg))
 }
 
+fn check_stake_caps(
+    deps: Deps,
+    validator: &str,
+    val: &Validator,
+    amount: Uint128,
+) -> Result<(), ContractError> {
+    let caps = STAKE_CAPS.may_load(deps.storage)?.unwrap_or_default();
+    let new_validator_stake = validator_stake(deps.storage, validator, val)? + amount;
+
+    if let Some(max) = caps.max_validator_stake {
+        if new_validator_stake > max {
+            return Err(ContractError::StakeCapExceeded(format!(
+                "validator stake would be {}, max is {}",
+                new_validator_stake, max
+            )));
+        }
+    }
+
+    // only calculate the total if we need it
+    if caps.max_total_stake.is_none() && caps.max_validator_share.is_none() {
+        return Ok(());
+    }
+    let new_total_stake = total_stake(deps.storage)? + amount;
+
+    if let Some(max) = caps.max_total_stake {
+        if new_total_stake > max {
+            return Err(ContractError::StakeCapExceeded(format!(
+                "total stake would be {}, max is {}",
+                new_total_stake, max
+            )));
+        }
+    }
+
+    if let Some(max_share) = caps.max_validator_share {
+        if new_total_stake > caps.share_cap_min_total {
+            let share = Decimal::from_ratio(new_validator_stake, new_total_stake);
+            if share > max_share {
+                return Err(ContractError::StakeCapExceeded(format!(
+                    "validator share would be {}, max is {}",
+                    share, max_share
+                )));
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn validate_stake_caps(caps: &StakeCaps) -> Result<(), ContractError> {
+    if let Some(share) = caps.max_validator_share {
+        if share.is_zero() || share > Decimal::one() {
+            return Err(ContractError::InvalidConfig(
+                "max_validator_share must be between 0 and 1".to_string(),
+            ));
+        }
+    }
+    Ok(())
+}
+
 pub fn execute_slash(
     deps: DepsMut,
     info: MessageInfo,
-    _env: Env,
+    env: Env,
     validator: String,
     percentage: Decimal,
     force_unbond: bool,
+    evidence: Option<String>,
 ) -> Result<Response, ContractError> {
     let cfg = CONFIG.load(deps.storage)?;
     ensure_eq!(cfg.slasher, Some(info.sender), ContractError::Unauthorized);
+    slash_validator(
+        deps.storage,
+        &env,
+        &validator,
+        percentage,
+        force_unbond,
+        evidence,
+    )?;
+
+    Ok(Response::new()
+        .add_attribute("action", "slash")
+        .add_attribute("validator", validator))
+}
+
+/// Slashes the validator and records it in the slashing history.
+/// Stakers are slashed lazily, see `ProcessSlash`.
+pub fn slash_validator(
+    storage: &mut dyn Storage,
+    env: &Env,
+    validator: &str,
+    percentage: Decimal,
+    tombstone: bool,
+    evidence: Option<String>,
+) -> Result<(), ContractError> {
     if percentage.is_zero() {
         return Err(ContractError::ZeroAmount);
     }
+    if percentage > Decimal::one() {
+        return Err(ContractError::InvalidSlashPercentage(percentage));
+    }
+    // nothing is left to stake on, and new shares could not be priced
+    let tombstone = tombstone || percentage == Decimal::one();
 
-    VALIDATORS.update::<_, ContractError>(deps.storage, &validator, |val| {
-        let mut val = val.ok_or_else(|| ContractError::UnknownValidator(validator.clone()))?;
+    VALIDATORS.update::<_, ContractError>(storage, validator, |val| {
+        let mut val = val.ok_or_else(|| ContractError::UnknownValidator(validator.to_string()))?;
         val.slash(percentage);
-        if force_unbond {
+        if tombstone {
             val.status = ValStatus::Tombstoned;
         }
         Ok(val)
     })?;
 
-    Ok(Response::new()
-        .add_attribute("action", "slash")
-        .add_attribute("validator", validator))
+    let id = SLASH_COUNTER.may_load(storage)?.unwrap_or_default() + 1;
+    SLASH_COUNTER.save(storage, &id)?;
+    let event = SlashEvent {
+        height: env.block.height,
+        time: env.block.time,
+        percentage,
+        evidence,
+        tombstoned: tombstone,
+    };
+    SLASHES.save(storage, (validator, id), &event)?;
+    Ok(())
 }
 
 pub fn execute_unstake(
@@ -184,16 +358,14 @@ pub fn execute_unstake(
     validator: String,
     amount: Uint128,
 ) -> Result<Response, ContractError> {
+    assert_not_paused(deps.storage, Operation::Unstaking)?;
     if amount.is_zero() {
         return Err(ContractError::ZeroAmount);
     }
 
-    // Verify validator exists and active
-    let val = VALIDATORS
-        .may_load(deps.storage, &validator)?
-        .ok_or_else(|| ContractError::UnknownValidator(validator.clone()))?;
-    if val.status != ValStatus::Active {
-        return Err(ContractError::RemovedValidator(validator));
+    // Verify validator exists, we can always unstake, even from removed or tombstoned validators
+    if !VALIDATORS.has(deps.storage, &validator) {
+        return Err(ContractError::UnknownValidator(validator));
     }
 
     // send out IBC packet for staking change
@@ -211,6 +383,146 @@ pub fn execute_unstake(
     Ok(Response::new().add_message(msg))
 }
 
+// how many stakers we unbond in one ForceUnbondValidator call
+const FORCE_UNBOND_BATCH: usize = 30;
+
+pub fn execute_force_unbond_validator(
+    deps: DepsMut,
+    env: Env,
+    validator: String,
+) -> Result<Response, ContractError> {
+    assert_not_paused(deps.storage, Operation::Unstaking)?;
+    let val = VALIDATORS
+        .may_load(deps.storage, &validator)?
+        .ok_or_else(|| ContractError::UnknownValidator(validator.clone()))?;
+    if val.status == ValStatus::Active {
+        return Err(ContractError::ActiveValidator(validator));
+    }
+
+    let mut progress = FORCE_UNBOND
+        .may_load(deps.storage, &validator)?
+        .unwrap_or_default();
+    if progress.finished {
+        return Err(ContractError::ForceUnbondFinished(validator));
+    }
+
+    let start = progress.last_staker.as_ref().map(Bound::exclusive);
+    let stakers = VALIDATOR_STAKERS
+        .prefix(&validator)
+        .keys(deps.storage, start, None, Order::Ascending)
+        .take(FORCE_UNBOND_BATCH)
+        .collect::<StdResult<Vec<_>>>()?;
+
+    let channel_id = CHANNEL.load(deps.storage)?;
+    let timeout = build_timeout(deps.as_ref(), &env)?;
+    let mut msgs = vec![];
+    for staker in &stakers {
+        let stake = STAKED.load(deps.storage, (staker, &validator))?;
+        let amount = stake.current_value(&val);
+        if amount.is_zero() {
+            continue;
+        }
+
+        // send out IBC packet for staking change, same as if the staker unstaked themselves
+        let packet = ProviderMsg::Unstake {
+            validator: validator.clone(),
+            amount,
+            key: staker.to_string(),
+        };
+        msgs.push(IbcMsg::SendPacket {
+            channel_id: channel_id.clone(),
+            data: to_binary(&packet)?,
+            timeout: timeout.clone(),
+        });
+    }
+
+    progress.finished = stakers.len() < FORCE_UNBOND_BATCH;
+    if let Some(last) = stakers.last() {
+        progress.last_staker = Some(last.clone());
+    }
+    FORCE_UNBOND.save(deps.storage, &validator, &progress)?;
+
+    Ok(Response::new()
+        .add_messages(msgs)
+        .add_attribute("action", "force_unbond_validator")
+        .add_attribute("validator", validator)
+        .add_attribute("stakers", stakers.len().to_string())
+        .add_attribute("finished", progress.finished.to_string()))
+}
+
+pub fn execute_process_slash(
+    deps: DepsMut,
+    env: Env,
+    validator: String,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> Result<Response, ContractError> {
+    let val = VALIDATORS
+        .may_load(deps.storage, &validator)?
+        .ok_or_else(|| ContractError::UnknownValidator(validator.clone()))?;
+
+    // an explicit start doesn't move the shared cursor, so nobody can skip stakers for others
+    let (start_after, use_cursor) = match start_after {
+        Some(addr) => (Some(deps.api.addr_validate(&addr)?), false),
+        None => (PROCESS_SLASH.may_load(deps.storage, &validator)?, true),
+    };
+    let limit = limit.unwrap_or(FORCE_UNBOND_BATCH as u32).min(MAX_LIMIT) as usize;
+    let stakers = VALIDATOR_STAKERS
+        .prefix(&validator)
+        .keys(
+            deps.storage,
+            start_after.as_ref().map(Bound::exclusive),
+            None,
+            Order::Ascending,
+        )
+        .take(limit)
+        .collect::<StdResult<Vec<_>>>()?;
+
+    let lockup = CONFIG.load(deps.storage)?.lockup;
+    let mut msgs = vec![];
+    let mut total = Uint128::zero();
+    for staker in &stakers {
+        let mut stake = STAKED.load(deps.storage, (staker, &validator))?;
+        let old_locked = stake.locked;
+        if let Some(slash) = take_account_slash(deps.storage, staker, &validator, &mut stake, &val)?
+        {
+            update_voting_power(
+                deps.storage,
+                staker,
+                env.block.height,
+                old_locked,
+                stake.locked,
+            )?;
+            STAKED.save(deps.storage, (staker, &validator), &stake)?;
+            total += slash;
+            msgs.push(WasmMsg::Execute {
+                contract_addr: lockup.to_string(),
+                msg: to_binary(&ClaimProviderMsg::SlashClaim {
+                    owner: staker.to_string(),
+                    amount: slash,
+                })?,
+                funds: vec![],
+            });
+        }
+    }
+
+    let finished = stakers.len() < limit;
+    if use_cursor {
+        match stakers.last() {
+            Some(last) if !finished => PROCESS_SLASH.save(deps.storage, &validator, last)?,
+            _ => PROCESS_SLASH.remove(deps.storage, &validator),
+        }
+    }
+
+    Ok(Response::new()
+        .add_messages(msgs)
+        .add_attribute("action", "process_slash")
+        .add_attribute("validator", validator)
+        .add_attribute("stakers", stakers.len().to_string())
+        .add_attribute("slashed", total)
+        .add_attribute("finished", finished.to_string()))
+}
+
 pub fn execute_unbond(
     deps: DepsMut,
     info: MessageInfo,
@@ -223,9 +535,10 @@ pub fn execute_unbond(
     }
 
     let cfg = CONFIG.load(deps.storage)?;
+    // the unbonded tokens are free again, so the lien is released rather than slashed
     let msg = WasmMsg::Execute {
         contract_addr: cfg.lockup.into_string(),
-        msg: to_binary(&ClaimProviderMsg::SlashClaim {
+        msg: to_binary(&ClaimProviderMsg::ReleaseClaim {
             owner: info.sender.into_string(),
             amount: mature,
         })?,
@@ -234,6 +547,224 @@ pub fn execute_unbond(
     Ok(Response::new().add_message(msg))
 }
 
+/// The consumer is unreachable, so we drop the stake on our side and release the claims.
+/// If the channel is recovered later, the consumer still holds this stake until reconciled.
+pub fn execute_exit_stake(
+    deps: DepsMut,
+    info: MessageInfo,
+    env: Env,
+) -> Result<Response, ContractError> {
+    assert_not_paused(deps.storage, Operation::Unstaking)?;
+    let closed_at = CHANNEL_CLOSED_AT
+        .may_load(deps.storage)?
+        .ok_or(ContractError::ChannelOpen)?;
+    let exit_at = closed_at.plus_seconds(CHANNEL_RECOVERY_PERIOD.load(deps.storage)?);
+    if env.block.time < exit_at {
+        return Err(ContractError::ChannelRecovery(exit_at));
+    }
+
+    let stakes = STAKED
+        .prefix(&info.sender)
+        .range(deps.storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<_>>>()?;
+
+    let mut released = Uint128::zero();
+    let mut slashed = Uint128::zero();
+    for (validator, mut stake) in stakes {
+        let mut val = VALIDATORS.load(deps.storage, &validator)?;
+        let amount = stake.current_value(&val);
+
+        // keep the rewards earned so far claimable
+        stake.calc_pending_rewards(
+            val.rewards.rewards_per_token,
+            val.shares_to_tokens(stake.shares),
+        )?;
+        let old_locked = stake.locked;
+        slashed += take_account_slash(deps.storage, &info.sender, &validator, &mut stake, &val)?
+            .unwrap_or_default();
+        if !amount.is_zero() {
+            stake.unstake_validator(&mut val, amount)?;
+            EXITED_STAKE.update::<_, StdError>(deps.storage, (&info.sender, &validator), |e| {
+                Ok(e.unwrap_or_default() + amount)
+            })?;
+        }
+        released += amount;
+        update_voting_power(
+            deps.storage,
+            &info.sender,
+            env.block.height,
+            old_locked,
+            stake.locked,
+        )?;
+
+        STAKED.save(deps.storage, (&info.sender, &validator), &stake)?;
+        VALIDATORS.save(deps.storage, &validator, &val)?;
+    }
+
+    if released.is_zero() && slashed.is_zero() {
+        return Err(ContractError::InsufficientStake);
+    }
+
+    // the exit unstakes everything on the consumer, resending older unstakes would do it twice
+    let retries = OWNER_RETRIES
+        .prefix(&info.sender)
+        .keys(deps.storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<_>>>()?;
+    for key in retries {
+        let mut retry = RETRIES.load(deps.storage, key)?;
+        if matches!(retry.packet, ProviderMsg::Unstake { .. })
+            && retry.status == RetryStatus::Pending
+        {
+            retry.status = RetryStatus::Failed;
+            RETRIES.save(deps.storage, key, &retry)?;
+        }
+    }
+
+    let cfg = CONFIG.load(deps.storage)?;
+    let mut msgs = vec![];
+    if !released.is_zero() {
+        msgs.push(WasmMsg::Execute {
+            contract_addr: cfg.lockup.to_string(),
+            msg: to_binary(&ClaimProviderMsg::ReleaseClaim {
+                owner: info.sender.to_string(),
+                amount: released,
+            })?,
+            funds: vec![],
+        });
+    }
+    if !slashed.is_zero() {
+        msgs.push(WasmMsg::Execute {
+            contract_addr: cfg.lockup.to_string(),
+            msg: to_binary(&ClaimProviderMsg::SlashClaim {
+                owner: info.sender.to_string(),
+                amount: slashed,
+            })?,
+            funds: vec![],
+        });
+    }
+
+    Ok(Response::new()
+        .add_messages(msgs)
+        .add_attribute("action", "exit_stake")
+        .add_attribute("released", released)
+        .add_attribute("slashed", slashed))
+}
+
+pub fn execute_refresh_validators(deps: DepsMut, env: Env) -> Result<Response, ContractError> {
+    LIST_VALIDATORS_RETRIES.save(deps.storage, &LIST_VALIDATORS_MAX_RETRIES)?;
+    let msg = IbcMsg::SendPacket {
+        channel_id: CHANNEL.load(deps.storage)?,
+        data: to_binary(&ProviderMsg::ListValidators {})?,
+        timeout: build_timeout(deps.as_ref(), &env)?,
+    };
+    Ok(Response::new()
+        .add_message(msg)
+        .add_attribute("action", "refresh_validators"))
+}
+
+pub fn execute_sync_state(deps: DepsMut, env: Env) -> Result<Response, ContractError> {
+    let msg = IbcMsg::SendPacket {
+        channel_id: CHANNEL.load(deps.storage)?,
+        data: to_binary(&ProviderMsg::SyncState {})?,
+        timeout: build_timeout(deps.as_ref(), &env)?,
+    };
+    Ok(Response::new()
+        .add_message(msg)
+        .add_attribute("action", "sync_state"))
+}
+
+pub fn execute_retry_pending(deps: DepsMut, env: Env, key: u64) -> Result<Response, ContractError> {
+    let mut retry = RETRIES
+        .may_load(deps.storage, key)?
+        .ok_or(ContractError::UnknownRetry(key))?;
+    if retry.status != RetryStatus::Pending {
+        return Err(ContractError::RetryNotPending(key));
+    }
+    if env.block.height < retry.retry_after {
+        return Err(ContractError::RetryBackoff(retry.retry_after));
+    }
+    match retry.packet {
+        ProviderMsg::Stake { .. } => assert_not_paused(deps.storage, Operation::Staking)?,
+        _ => assert_not_paused(deps.storage, Operation::Unstaking)?,
+    }
+
+    // Without a channel the packet can't be delivered anymore, so we fail it
+    let channel_id = match CHANNEL.may_load(deps.storage)? {
+        Some(channel_id) => channel_id,
+        None => {
+            retry.status = RetryStatus::Failed;
+            RETRIES.save(deps.storage, key, &retry)?;
+            let res = match retry.packet {
+                ProviderMsg::Stake {
+                    key,
+                    validator,
+                    amount,
+                } => fail_stake(deps, key, validator, amount)?,
+                _ => fail_unstake()?,
+            };
+            return Ok(Response::new()
+                .add_submessages(res.messages)
+                .add_events(res.events)
+                .add_attribute("action", "retry_pending")
+                .add_attribute("key", key.to_string())
+                .add_attribute("status", "failed"));
+        }
+    };
+
+    retry.attempts += 1;
+    retry.status = RetryStatus::Resent;
+    RETRIES.save(deps.storage, key, &retry)?;
+
+    let msg = IbcMsg::SendPacket {
+        channel_id,
+        data: to_binary(&retry_packet(retry.packet, key))?,
+        timeout: build_timeout(deps.as_ref(), &env)?,
+    };
+    Ok(Response::new()
+        .add_message(msg)
+        .add_attribute("action", "retry_pending")
+        .add_attribute("key", key.to_string())
+        .add_attribute("attempts", retry.attempts.to_string()))
+}
+
+pub fn execute_correct_state_drift(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    validator: String,
+) -> Result<Response, ContractError> {
+    ADMIN.assert_admin(deps.as_ref(), &info.sender)?;
+    let drift = STATE_DRIFT
+        .may_load(deps.storage, &validator)?
+        .ok_or_else(|| ContractError::NoStateDrift(validator.clone()))?;
+    // we can only take stake away, there is nobody to give the extra stake to
+    if drift.consumer_stake > drift.provider_stake {
+        return Err(ContractError::ConsumerStakeHigher(validator));
+    }
+
+    // Stakers see the lost stake as a slash, and release less on unstake
+    let current = VALIDATORS.load(deps.storage, &validator)?.stake_value();
+    let lost = (drift.provider_stake - drift.consumer_stake).min(current);
+    if !lost.is_zero() {
+        slash_validator(
+            deps.storage,
+            &env,
+            &validator,
+            Decimal::from_ratio(lost, current),
+            false,
+            Some(STATE_DRIFT_EVIDENCE.to_string()),
+        )?;
+    }
+    CONSUMER_STAKE.save(deps.storage, &validator, &drift.consumer_stake)?;
+    STATE_DRIFT.remove(deps.storage, &validator);
+
+    Ok(Response::new()
+        .add_attribute("action", "correct_state_drift")
+        .add_attribute("validator", validator)
+        .add_attribute("provider_stake", drift.provider_stake)
+        .add_attribute("consumer_stake", drift.consumer_stake))
+}
+
 // HACK this implementation of claiming rewards is not performant or robust
 // It is intended for proof of concept only.
 pub fn execute_claim_rewards(
@@ -242,6 +773,7 @@ pub fn execute_claim_rewards(
     info: MessageInfo,
     validator: String,
 ) -> Result<Response, ContractError> {
+    assert_not_paused(deps.storage, Operation::Rewards)?;
     let config = CONFIG.load(deps.storage)?;
 
     // calculate rewards
@@ -251,7 +783,7 @@ pub fn execute_claim_rewards(
     // We calculate the rewards
     delegator_stake.calc_pending_rewards(
         validator_info.rewards.rewards_per_token,
-        validator_info.stake,
+        delegator_stake.current_value(&validator_info),
     )?;
 
     if delegator_stake.rewards.pending.floor().is_zero() {
@@ -273,7 +805,7 @@ pub fn execute_claim_rewards(
     let send_amount = delegator_stake.pending_to_u128()?;
 
     let msg = BankMsg::Send {
-        to_address: info.sender.to_string(),
+        to_address: withdraw_address(deps.storage, &info.sender)?.into_string(),
         amount: vec![coin(send_amount, config.rewards_ibc_denom)],
     };
 
@@ -284,15 +816,226 @@ pub fn execute_claim_rewards(
     Ok(Response::new().add_message(msg))
 }
 
+pub fn execute_update_config(
+    deps: DepsMut,
+    info: MessageInfo,
+    update: ConfigUpdate,
+) -> Result<Response, ContractError> {
+    ADMIN.assert_admin(deps.as_ref(), &info.sender)?;
+    let mut cfg = CONFIG.load(deps.storage)?;
+
+    // Existing claims keep their release time, this only affects future unstaking
+    if let Some(unbonding_period) = update.unbonding_period {
+        if unbonding_period == 0 {
+            return Err(ContractError::InvalidConfig(
+                "unbonding_period must be positive".to_string(),
+            ));
+        }
+        cfg.unbonding_period = unbonding_period;
+    }
+
+    if let Some(rewards_ibc_denom) = update.rewards_ibc_denom {
+        if rewards_ibc_denom.is_empty() {
+            return Err(ContractError::InvalidConfig(
+                "rewards_ibc_denom cannot be empty".to_string(),
+            ));
+        }
+        cfg.rewards_ibc_denom = rewards_ibc_denom;
+    }
+
+    if let Some(packet_lifetime) = update.packet_lifetime {
+        if packet_lifetime == 0 {
+            return Err(ContractError::InvalidConfig(
+                "packet_lifetime must be positive".to_string(),
+            ));
+        }
+        PACKET_LIFETIME.save(deps.storage, &packet_lifetime)?;
+    }
+
+    if let Some(connection_id) = update.connection_id {
+        if connection_id.is_empty() {
+            return Err(ContractError::InvalidConfig(
+                "connection_id cannot be empty".to_string(),
+            ));
+        }
+        // The open channel is bound to the old connection
+        if let Some(chan) = CHANNEL.may_load(deps.storage)? {
+            return Err(ContractError::ChannelExists(chan));
+        }
+        cfg.consumer.connection_id = connection_id;
+    }
+
+    if let Some(stake_caps) = update.stake_caps {
+        validate_stake_caps(&stake_caps)?;
+        STAKE_CAPS.save(deps.storage, &stake_caps)?;
+    }
+
+    if let Some(channel_recovery_period) = update.channel_recovery_period {
+        CHANNEL_RECOVERY_PERIOD.save(deps.storage, &channel_recovery_period)?;
+    }
+
+    if let Some(retry_policy) = update.retry_policy {
+        RETRY_POLICY.save(deps.storage, &retry_policy)?;
+    }
+
+    CONFIG.save(deps.storage, &cfg)?;
+
+    Ok(Response::new().add_attribute("action", "update_config"))
+}
+
+pub fn execute_transfer_admin(
+    deps: DepsMut,
+    info: MessageInfo,
+    new_admin: String,
+) -> Result<Response, ContractError> {
+    ADMIN.assert_admin(deps.as_ref(), &info.sender)?;
+    let new_admin = deps.api.addr_validate(&new_admin)?;
+    PENDING_ADMIN.save(deps.storage, &new_admin)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "transfer_admin")
+        .add_attribute("pending_admin", new_admin))
+}
+
+pub fn execute_accept_admin(deps: DepsMut, info: MessageInfo) -> Result<Response, ContractError> {
+    let pending = PENDING_ADMIN
+        .may_load(deps.storage)?
+        .ok_or(ContractError::NoPendingAdmin)?;
+    ensure_eq!(pending, info.sender, ContractError::Unauthorized);
+
+    PENDING_ADMIN.remove(deps.storage);
+    ADMIN.set(deps, Some(info.sender.clone()))?;
+
+    Ok(Response::new()
+        .add_attribute("action", "accept_admin")
+        .add_attribute("admin", info.sender))
+}
+
+pub fn execute_update_guardian(
+    deps: DepsMut,
+    info: MessageInfo,
+    guardian: Option<String>,
+) -> Result<Response, ContractError> {
+    ADMIN.assert_admin(deps.as_ref(), &info.sender)?;
+    let guardian = guardian
+        .map(|guardian| deps.api.addr_validate(&guardian))
+        .transpose()?;
+    let attr = guardian
+        .as_ref()
+        .map(|x| x.to_string())
+        .unwrap_or_else(|| "none".to_string());
+    GUARDIAN.set(deps, guardian)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "update_guardian")
+        .add_attribute("guardian", attr))
+}
+
+fn assert_guardian_or_admin(deps: Deps, sender: &Addr) -> Result<(), ContractError> {
+    if GUARDIAN.is_admin(deps, sender)? || ADMIN.is_admin(deps, sender)? {
+        Ok(())
+    } else {
+        Err(ContractError::Unauthorized)
+    }
+}
+
+pub fn execute_pause(
+    deps: DepsMut,
+    info: MessageInfo,
+    operations: Vec<Operation>,
+) -> Result<Response, ContractError> {
+    assert_guardian_or_admin(deps.as_ref(), &info.sender)?;
+
+    let mut paused = PAUSED.may_load(deps.storage)?.unwrap_or_default();
+    for op in operations {
+        if !paused.contains(&op) {
+            paused.push(op);
+        }
+    }
+    PAUSED.save(deps.storage, &paused)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "pause")
+        .add_attribute("paused", format!("{:?}", paused)))
+}
+
+pub fn execute_unpause(
+    deps: DepsMut,
+    info: MessageInfo,
+    operations: Vec<Operation>,
+) -> Result<Response, ContractError> {
+    assert_guardian_or_admin(deps.as_ref(), &info.sender)?;
+
+    let mut paused = PAUSED.may_load(deps.storage)?.unwrap_or_default();
+    paused.retain(|op| !operations.contains(op));
+    PAUSED.save(deps.storage, &paused)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "unpause")
+        .add_attribute("paused", format!("{:?}", paused)))
+}
+
+pub fn execute_set_withdraw_address(
+    deps: DepsMut,
+    info: MessageInfo,
+    address: String,
+) -> Result<Response, ContractError> {
+    let address = deps.api.addr_validate(&address)?;
+
+    // Setting it to yourself is the same as not having one
+    if address == info.sender {
+        WITHDRAW_ADDRESSES.remove(deps.storage, &info.sender);
+    } else {
+        WITHDRAW_ADDRESSES.save(deps.storage, &info.sender, &address)?;
+    }
+
+    Ok(Response::new()
+        .add_attribute("action", "set_withdraw_address")
+        .add_attribute("owner", info.sender)
+        .add_attribute("withdraw_address", address))
+}
+
 #[cfg_attr(not(feature = "library"), entry_point)]
-pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
+pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
     match msg {
         QueryMsg::Config {} => to_binary(&query_config(deps)?),
         QueryMsg::Account { address } => to_binary(&query_account(deps, address)?),
+        QueryMsg::Portfolio { address } => to_binary(&query_portfolio(deps, env, address)?),
         QueryMsg::Validator { address } => to_binary(&query_validator(deps, address)?),
         QueryMsg::ListValidators { start_after, limit } => {
             to_binary(&list_validators(deps, start_after, limit)?)
         }
+        QueryMsg::Utilization { start_after, limit } => {
+            to_binary(&query_utilization(deps, start_after, limit)?)
+        }
+        QueryMsg::Paused {} => to_binary(&query_paused(deps)?),
+        QueryMsg::WithdrawAddress { address } => to_binary(&query_withdraw_address(deps, address)?),
+        QueryMsg::Channel {} => to_binary(&query_channel(deps)?),
+        QueryMsg::StateDrift { start_after, limit } => {
+            to_binary(&query_state_drift(deps, start_after, limit)?)
+        }
+        QueryMsg::Retries {
+            owner,
+            start_after,
+            limit,
+        } => to_binary(&query_retries(deps, owner, start_after, limit)?),
+        QueryMsg::SlashingHistory {
+            validator,
+            start_after,
+            limit,
+        } => to_binary(&query_slashing_history(
+            deps,
+            validator,
+            start_after,
+            limit,
+        )?),
+        QueryMsg::AccountSlashes { address } => to_binary(&query_account_slashes(deps, address)?),
+        QueryMsg::VotingPowerAtHeight { address, height } => {
+            to_binary(&query_voting_power_at_height(deps, env, address, height)?)
+        }
+        QueryMsg::TotalPowerAtHeight { height } => {
+            to_binary(&query_total_power_at_height(deps, env, height)?)
+        }
     }
 }
 
@@ -301,6 +1044,16 @@ pub fn query_config(deps: Deps) -> StdResult<ConfigResponse> {
     Ok(ConfigResponse {
         consumer: cfg.consumer,
         slasher: cfg.slasher.map(|x| x.into_string()),
+        lockup: cfg.lockup.into_string(),
+        unbonding_period: cfg.unbonding_period,
+        rewards_ibc_denom: cfg.rewards_ibc_denom,
+        packet_lifetime: PACKET_LIFETIME.load(deps.storage)?,
+        channel_recovery_period: CHANNEL_RECOVERY_PERIOD.load(deps.storage)?,
+        retry_policy: RETRY_POLICY.may_load(deps.storage)?.unwrap_or_default(),
+        admin: ADMIN.get(deps)?.map(|x| x.into_string()),
+        pending_admin: PENDING_ADMIN
+            .may_load(deps.storage)?
+            .map(|x| x.into_string()),
     })
 }
 
@@ -325,9 +1078,86 @@ pub fn query_account(deps: Deps, address: String) -> StdResult<AccountResponse>
     Ok(AccountResponse { staked })
 }
 
+pub fn query_portfolio(deps: Deps, env: Env, address: String) -> StdResult<PortfolioResponse> {
+    let account = deps.api.addr_validate(&address)?;
+    let positions = STAKED
+        .prefix(&account)
+        .range(deps.storage, None, None, Order::Ascending)
+        .map(|res| {
+            let (validator, mut stake) = res?;
+            let val = VALIDATORS.load(deps.storage, &validator)?;
+            let tokens = stake.current_value(&val);
+            stake
+                .calc_pending_rewards(val.rewards.rewards_per_token, tokens)
+                .map_err(|e| StdError::generic_err(e.to_string()))?;
+            Ok(Position {
+                validator,
+                status: val.status,
+                tokens,
+                shares: stake.shares,
+                slashed: stake.locked - tokens,
+                pending_rewards: stake.rewards.pending * Uint128::one(),
+            })
+        })
+        .collect::<StdResult<Vec<_>>>()?;
+    let unbonding = CLAIMS.query_claims(deps, &account)?.claims;
+
+    // an address that never staked has no balance in lockup
+    let lien = if positions.is_empty() && unbonding.is_empty() {
+        Uint128::zero()
+    } else {
+        let balance: LockupBalanceResponse = deps.querier.query_wasm_smart(
+            CONFIG.load(deps.storage)?.lockup,
+            &LockupQueryMsg::Balance { account: address },
+        )?;
+        balance
+            .claims
+            .into_iter()
+            .find(|lein| lein.leinholder == env.contract.address.as_str())
+            .map(|lein| lein.amount)
+            .unwrap_or_default()
+    };
+
+    Ok(PortfolioResponse {
+        positions,
+        unbonding,
+        lien,
+    })
+}
+
+pub fn query_paused(deps: Deps) -> StdResult<PausedResponse> {
+    Ok(PausedResponse {
+        guardian: GUARDIAN.get(deps)?.map(|x| x.into_string()),
+        paused: PAUSED.may_load(deps.storage)?.unwrap_or_default(),
+    })
+}
+
+pub fn query_channel(deps: Deps) -> StdResult<ChannelResponse> {
+    let closed_at = CHANNEL_CLOSED_AT.may_load(deps.storage)?;
+    let exit_at = match closed_at {
+        Some(closed_at) => {
+            Some(closed_at.plus_seconds(CHANNEL_RECOVERY_PERIOD.load(deps.storage)?))
+        }
+        None => None,
+    };
+    Ok(ChannelResponse {
+        channel: CHANNEL.may_load(deps.storage)?,
+        closed_at,
+        exit_at,
+    })
+}
+
+pub fn query_withdraw_address(deps: Deps, address: String) -> StdResult<WithdrawAddressResponse> {
+    let account = deps.api.addr_validate(&address)?;
+    let withdraw_address = withdraw_address(deps.storage, &account)?;
+    Ok(WithdrawAddressResponse {
+        withdraw_address: withdraw_address.into_string(),
+    })
+}
+
 pub fn query_validator(deps: Deps, address: String) -> StdResult<ValidatorResponse> {
     let val = VALIDATORS.load(deps.storage, &address)?;
-    Ok(build_response((address, val)))
+    build_response(deps.storage, (address, val))
 }
 
 // settings for pagination
@@ -345,17 +1175,204 @@ pub fn list_validators(
     let validators = VALIDATORS
         .range(deps.storage, start, None, Order::Ascending)
         .take(limit)
-        .map(|r| Ok(build_response(r?)))
+        .map(|r| build_response(deps.storage, r?))
         .collect::<StdResult<Vec<_>>>()?;
 
     Ok(ListValidatorsResponse { validators })
 }
 
-fn build_response((address, val): (String, Validator)) -> ValidatorResponse {
-    ValidatorResponse {
+pub fn query_voting_power_at_height(
+    deps: Deps,
+    env: Env,
+    address: String,
+    height: Option<u64>,
+) -> StdResult<VotingPowerAtHeightResponse> {
+    let address = deps.api.addr_validate(&address)?;
+    let height = height.unwrap_or(env.block.height);
+    let power = VOTING_POWER
+        .may_load_at_height(deps.storage, &address, height)?
+        .unwrap_or_default();
+    Ok(VotingPowerAtHeightResponse { power, height })
+}
+
+pub fn query_total_power_at_height(
+    deps: Deps,
+    env: Env,
+    height: Option<u64>,
+) -> StdResult<TotalPowerAtHeightResponse> {
+    let height = height.unwrap_or(env.block.height);
+    let power = TOTAL_POWER
+        .may_load_at_height(deps.storage, height)?
+        .unwrap_or_default();
+    Ok(TotalPowerAtHeightResponse { power, height })
+}
+
+pub fn query_retries(
+    deps: Deps,
+    owner: String,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> StdResult<RetriesResponse> {
+    let owner = deps.api.addr_validate(&owner)?;
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.map(Bound::exclusive);
+
+    let retries = OWNER_RETRIES
+        .prefix(&owner)
+        .keys(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|key| {
+            let key = key?;
+            let retry = RETRIES.load(deps.storage, key)?;
+            Ok(RetryInfo {
+                key,
+                packet: retry.packet,
+                attempts: retry.attempts,
+                retry_after: retry.retry_after,
+                status: retry.status,
+            })
+        })
+        .collect::<StdResult<Vec<_>>>()?;
+
+    Ok(RetriesResponse { retries })
+}
+
+pub fn query_slashing_history(
+    deps: Deps,
+    validator: String,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> StdResult<SlashingHistoryResponse> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.map(Bound::exclusive);
+
+    let slashes = SLASHES
+        .prefix(&validator)
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|res| {
+            let (id, event) = res?;
+            Ok(SlashInfo {
+                id,
+                height: event.height,
+                time: event.time,
+                percentage: event.percentage,
+                evidence: event.evidence,
+                tombstoned: event.tombstoned,
+            })
+        })
+        .collect::<StdResult<Vec<_>>>()?;
+
+    Ok(SlashingHistoryResponse { slashes })
+}
+
+/// Slashes that already were taken from the account's stakes, plus the ones still pending
+/// on them, with how much each slash took. Sorted by validator, then slash id.
+pub fn query_account_slashes(deps: Deps, address: String) -> StdResult<AccountSlashesResponse> {
+    let account = deps.api.addr_validate(&address)?;
+    let mut amounts = ACCOUNT_SLASHES
+        .sub_prefix(&account)
+        .range(deps.storage, None, None, Order::Ascending)
+        .collect::<StdResult<BTreeMap<_, _>>>()?;
+    for res in STAKED
+        .prefix(&account)
+        .range(deps.storage, None, None, Order::Ascending)
+    {
+        let (validator, stake) = res?;
+        let val = VALIDATORS.load(deps.storage, &validator)?;
+        for (id, amount) in untaken_slashes(deps.storage, &validator, &stake, &val)? {
+            *amounts.entry((validator.clone(), id)).or_default() += amount;
+        }
+    }
+
+    let slashes = amounts
+        .into_iter()
+        .map(|((validator, id), amount)| {
+            let event = SLASHES.load(deps.storage, (&validator, id))?;
+            Ok(AccountSlash {
+                validator,
+                id,
+                height: event.height,
+                percentage: event.percentage,
+                amount,
+            })
+        })
+        .collect::<StdResult<Vec<_>>>()?;
+
+    Ok(AccountSlashesResponse { slashes })
+}
+
+pub fn query_state_drift(
+    deps: Deps,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<StateDriftResponse> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.as_ref().map(|x| Bound::exclusive(x.as_str()));
+
+    let validators = STATE_DRIFT
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|r| {
+            let (address, drift) = r?;
+            Ok(ValidatorDrift {
+                address,
+                provider_stake: drift.provider_stake,
+                consumer_stake: drift.consumer_stake,
+            })
+        })
+        .collect::<StdResult<Vec<_>>>()?;
+
+    Ok(StateDriftResponse {
+        last_sync: LAST_STATE_SYNC.may_load(deps.storage)?,
+        validators,
+    })
+}
+
+pub fn query_utilization(
+    deps: Deps,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<UtilizationResponse> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.as_ref().map(|x| Bound::exclusive(x.as_str()));
+    let total_stake = total_stake(deps.storage)?;
+
+    let validators = VALIDATORS
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|r| {
+            let (address, val) = r?;
+            let stake = validator_stake(deps.storage, &address, &val)?;
+            let share = if total_stake.is_zero() {
+                Decimal::zero()
+            } else {
+                Decimal::from_ratio(stake, total_stake)
+            };
+            Ok(ValidatorUtilization {
+                address,
+                stake,
+                share,
+            })
+        })
+        .collect::<StdResult<Vec<_>>>()?;
+
+    Ok(UtilizationResponse {
+        caps: STAKE_CAPS.may_load(deps.storage)?.unwrap_or_default(),
+        total_stake,
+        validators,
+    })
+}
+
+fn build_response(
+    storage: &dyn Storage,
+    (address, val): (String, Validator),
+) -> StdResult<ValidatorResponse> {
+    Ok(ValidatorResponse {
+        metadata: VALIDATOR_METADATA.may_load(storage, &address)?,
         address,
         tokens: val.stake_value(),
         status: val.status,
         multiplier: val.multiplier,
-    }
+    })
 }
@@ -1,12 +1,16 @@
 use thiserror::Error;
 
 use cosmwasm_std::{
-    CheckedFromRatioError, DecimalRangeExceeded, DivideByZeroError, OverflowError, StdError,
+    CheckedFromRatioError, Decimal, DecimalRangeExceeded, DivideByZeroError, OverflowError,
+    StdError, Timestamp,
 };
+use cw_controllers::AdminError;
 use cw_utils::ParseReplyError;
 
 use mesh_ibc::MeshSecurityError;
 
+use crate::state::Operation;
+
 #[derive(Error, Debug, PartialEq)]
 pub enum ContractError {
     #[error("{0}")]
@@ -33,6 +37,21 @@ pub enum ContractError {
     #[error("Unauthorized")]
     Unauthorized,
 
+    #[error("{0}")]
+    Admin(#[from] AdminError),
+
+    #[error("Invalid config: {0}")]
+    InvalidConfig(String),
+
+    #[error("No admin transfer is pending")]
+    NoPendingAdmin,
+
+    #[error("Stake cap exceeded: {0}")]
+    StakeCapExceeded(String),
+
+    #[error("Operation is paused: {0:?}")]
+    Paused(Operation),
+
     #[error("Contract already has a bound channel: {0}")]
     ChannelExists(String),
 
@@ -42,6 +61,12 @@ pub enum ContractError {
     #[error("Unauthorized counterparty chain, awaiting connection '{0}'")]
     WrongConnection(String),
 
+    #[error("Channel is open, unstake through the consumer instead")]
+    ChannelOpen,
+
+    #[error("Channel can still be recovered, exit is possible at {0}")]
+    ChannelRecovery(Timestamp),
+
     #[error("Refuse to respond on unregistered channel '{0}'")]
     UnknownChannel(String),
 
@@ -54,6 +79,9 @@ pub enum ContractError {
     #[error("Cannot send zero tokens to any methods")]
     ZeroAmount,
 
+    #[error("Slash percentage must be at most 100%, got {0}")]
+    InvalidSlashPercentage(Decimal),
+
     #[error("No tokens are ready to be unbonded")]
     NothingToClaim,
 
@@ -72,6 +100,27 @@ pub enum ContractError {
     #[error("Validator was removed from valset: {0}")]
     RemovedValidator(String),
 
+    #[error("Validator is still active: {0}")]
+    ActiveValidator(String),
+
+    #[error("All stakers of this validator are already unbonding: {0}")]
+    ForceUnbondFinished(String),
+
+    #[error("No retry with key: {0}")]
+    UnknownRetry(u64),
+
+    #[error("Retry {0} is not pending")]
+    RetryNotPending(u64),
+
+    #[error("Retry is possible from block {0}")]
+    RetryBackoff(u64),
+
+    #[error("No state drift recorded for this validator: {0}")]
+    NoStateDrift(String),
+
+    #[error("Consumer holds more stake than we track for this validator: {0}")]
+    ConsumerStakeHigher(String),
+
     #[error("Something went wrong in the rewards calculation of the validator")]
     ValidatorRewardsCalculationWrong {},
 
@@ -1,24 +1,32 @@
 #[cfg(not(feature = "library"))]
 use cosmwasm_std::entry_point;
 
+use std::collections::BTreeMap;
+
 use cosmwasm_std::{
-    from_slice, to_binary, Coin, Deps, DepsMut, Empty, Env, Event, Ibc3ChannelOpenResponse,
-    IbcBasicResponse, IbcChannelCloseMsg, IbcChannelConnectMsg, IbcChannelOpenMsg, IbcMsg,
-    IbcPacketAckMsg, IbcPacketReceiveMsg, IbcPacketTimeoutMsg, IbcReceiveResponse, IbcTimeout,
-    Uint128, WasmMsg,
+    from_slice, to_binary, Coin, Decimal, Deps, DepsMut, Empty, Env, Event,
+    Ibc3ChannelOpenResponse, IbcBasicResponse, IbcChannelCloseMsg, IbcChannelConnectMsg,
+    IbcChannelOpenMsg, IbcMsg, IbcPacketAckMsg, IbcPacketReceiveMsg, IbcPacketTimeoutMsg,
+    IbcReceiveResponse, IbcTimeout, Order, StdError, StdResult, Storage, Uint128, WasmMsg,
 };
 
 use cw_utils::Expiration;
 use mesh_apis::ClaimProviderMsg;
 use mesh_ibc::{
-    check_order, check_version, ConsumerMsg, ListValidatorsResponse, ProviderMsg, RewardsResponse,
-    StdAck, UpdateValidatorsResponse,
+    check_order, check_version, ConsumerMsg, ListValidatorsResponse, ProviderMsg, RewardsEntry,
+    RewardsResponse, SlashResponse, StdAck, SyncStateResponse, UpdateValidatorsResponse,
+    ValidatorMetadata,
 };
 
+use crate::contract::slash_validator;
 use crate::error::ContractError;
 use crate::state::{
-    ValStatus, Validator, CHANNEL, CLAIMS, CONFIG, LIST_VALIDATORS_MAX_RETRIES,
-    LIST_VALIDATORS_RETRIES, PACKET_LIFETIME, PORT, STAKED, VALIDATORS,
+    assert_not_paused, set_jailed, take_account_slash, update_voting_power, Operation, Retry,
+    RetryStatus, StakeDrift, ValStatus, Validator, CHANNEL, CHANNEL_CLOSED_AT, CLAIMS, CONFIG,
+    CONSUMER_STAKE, EXITED_STAKE, FORCE_UNBOND, LAST_STATE_SYNC, LIST_VALIDATORS_MAX_RETRIES,
+    LIST_VALIDATORS_RETRIES, OWNER_RETRIES, PACKET_LIFETIME, PENDING_STAKE, PORT, RETRIES,
+    RETRY_COUNTER, RETRY_POLICY, STAKED, STATE_DRIFT, VALIDATORS, VALIDATOR_METADATA,
+    VALIDATOR_STAKERS, VALSET_EPOCH,
 };
 
 pub fn build_timeout(deps: Deps, env: &Env) -> Result<IbcTimeout, ContractError> {
@@ -74,19 +82,42 @@ pub fn ibc_channel_connect(
     // save the port id for future use
     PORT.save(deps.storage, port_id)?;
 
+    LIST_VALIDATORS_RETRIES.save(deps.storage, &LIST_VALIDATORS_MAX_RETRIES)?;
     let packet = ProviderMsg::ListValidators {};
     let msg = IbcMsg::SendPacket {
         channel_id: channel_id.to_string(),
         data: to_binary(&packet)?,
         timeout: build_timeout(deps.as_ref(), &env)?,
     };
-    Ok(IbcBasicResponse::new().add_message(msg))
+    let mut res = IbcBasicResponse::new().add_message(msg);
+
+    // a new channel recovers from a closed one, the validator set is re-synced on ack
+    // and we check if our stake still matches the consumer
+    if CHANNEL_CLOSED_AT.may_load(deps.storage)?.is_some() {
+        CHANNEL_CLOSED_AT.remove(deps.storage);
+        let msg = IbcMsg::SendPacket {
+            channel_id: channel_id.to_string(),
+            data: to_binary(&ProviderMsg::SyncState {})?,
+            timeout: build_timeout(deps.as_ref(), &env)?,
+        };
+        res = res.add_message(msg);
+        // the consumer still holds the stake that exited while we were closed
+        for packet in exit_packets(deps.storage)? {
+            res = res.add_message(IbcMsg::SendPacket {
+                channel_id: channel_id.to_string(),
+                data: to_binary(&packet)?,
+                timeout: build_timeout(deps.as_ref(), &env)?,
+            });
+        }
+        res = res.add_event(Event::new("channel_recovered").add_attribute("channel", channel_id));
+    }
+    Ok(res)
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn ibc_channel_close(
     deps: DepsMut,
-    _env: Env,
+    env: Env,
     msg: IbcChannelCloseMsg,
 ) -> Result<IbcBasicResponse, ContractError> {
     let channel = msg.channel();
@@ -97,6 +128,8 @@ pub fn ibc_channel_close(
     let channel = CHANNEL.load(deps.storage)?;
     if &channel == channel_id {
         CHANNEL.remove(deps.storage);
+        // stakers can exit to lockup if no new channel connects in time
+        CHANNEL_CLOSED_AT.save(deps.storage, &env.block.time)?;
     } else {
         return Err(ContractError::UnknownChannel(channel_id.clone()));
     }
@@ -119,15 +152,37 @@ pub fn ibc_packet_receive(
         return Err(ContractError::UnknownChannel(caller));
     }
 
+    // while paused, the consumer gets an error ack instead of a failed tx, and can resend later
+    if let Err(err) = assert_not_paused(deps.storage, Operation::IbcReceive) {
+        return Ok(IbcReceiveResponse::new().set_ack(StdAck::fail_transient(err.to_string())));
+    }
+
     let msg: ConsumerMsg = from_slice(&msg.packet.data)?;
     match msg {
         ConsumerMsg::Rewards {
             validator,
             total_funds,
         } => receive_rewards(deps, env, validator, total_funds),
-        ConsumerMsg::UpdateValidators { added, removed } => {
-            receive_update_validators(deps, env, added, removed)
-        }
+        ConsumerMsg::BatchRewards { entries } => receive_batch_rewards(deps, entries),
+        ConsumerMsg::UpdateValidators {
+            added,
+            removed,
+            epoch,
+            metadata,
+        } => receive_update_validators(deps, env, added, removed, epoch, metadata),
+        ConsumerMsg::Slash {
+            validator,
+            percentage,
+            infraction_height,
+            tombstone,
+        } => receive_slash(
+            deps,
+            env,
+            validator,
+            percentage,
+            infraction_height,
+            tombstone,
+        ),
     }
 }
 
@@ -137,30 +192,60 @@ pub fn receive_rewards(
     validator: String,
     total_funds: Coin,
 ) -> Result<IbcReceiveResponse, ContractError> {
-    // Update the rewards of this validator
-    // This will fail if we didn't add the validator before it, we cannot init the validator and calculate rewards in the same msg. (same block)
-    VALIDATORS.update::<_, ContractError>(deps.storage, &validator, |val| {
-        let mut val = match val {
-            Some(val) => val,
-            None => return Err(ContractError::UnknownValidator(validator.clone())),
-        };
-
-        let total_staked = val.shares_to_tokens(val.stake);
+    add_rewards(deps.storage, &validator, &total_funds)?;
 
-        if total_staked.is_zero() {
-            return Err(ContractError::NoStakedTokens(validator.clone()));
-        }
+    // TODO: if calculation failed, we want to handle it as leftover funds? or send funds back to consumer and handle it there?
+    let ack = StdAck::success(&RewardsResponse {});
 
-        val.rewards
-            .calc_rewards(total_funds.amount, val.shares_to_tokens(val.stake))?;
+    Ok(IbcReceiveResponse::new().set_ack(ack))
+}
 
-        Ok(val)
-    })?;
+pub fn receive_batch_rewards(
+    deps: DepsMut,
+    entries: Vec<RewardsEntry>,
+) -> Result<IbcReceiveResponse, ContractError> {
+    // A bad entry fails the whole batch with an error ack, before any rewards are added,
+    // so the consumer requeues all of it
+    for entry in &entries {
+        if let Err(err) = rewarded_validator(deps.storage, &entry.validator) {
+            return Ok(IbcReceiveResponse::new()
+                .set_ack(StdAck::fail(err.to_string()))
+                .add_attribute("error", err.to_string()));
+        }
+    }
+    for entry in &entries {
+        add_rewards(deps.storage, &entry.validator, &entry.amount)?;
+    }
 
-    // TODO: if calculation failed, we want to handle it as leftover funds? or send funds back to consumer and handle it there?
     let ack = StdAck::success(&RewardsResponse {});
+    Ok(IbcReceiveResponse::new()
+        .set_ack(ack)
+        .add_attribute("entries", entries.len().to_string()))
+}
 
-    Ok(IbcReceiveResponse::new().set_ack(ack))
+/// The validator to add rewards to, it must be known and have stake
+fn rewarded_validator(storage: &dyn Storage, validator: &str) -> Result<Validator, ContractError> {
+    // This will fail if we didn't add the validator before it, we cannot init the validator and calculate rewards in the same msg. (same block)
+    let val = VALIDATORS
+        .may_load(storage, validator)?
+        .ok_or_else(|| ContractError::UnknownValidator(validator.to_string()))?;
+    if val.shares_to_tokens(val.stake).is_zero() {
+        return Err(ContractError::NoStakedTokens(validator.to_string()));
+    }
+    Ok(val)
+}
+
+fn add_rewards(
+    storage: &mut dyn Storage,
+    validator: &str,
+    total_funds: &Coin,
+) -> Result<(), ContractError> {
+    // Update the rewards of this validator
+    let mut val = rewarded_validator(storage, validator)?;
+    val.rewards
+        .calc_rewards(total_funds.amount, val.shares_to_tokens(val.stake))?;
+    VALIDATORS.save(storage, validator, &val)?;
+    Ok(())
 }
 
 pub fn receive_update_validators(
@@ -168,22 +253,89 @@ pub fn receive_update_validators(
     _env: Env,
     added: Vec<String>,
     removed: Vec<String>,
+    epoch: u64,
+    metadata: Vec<ValidatorMetadata>,
 ) -> Result<IbcReceiveResponse, ContractError> {
+    let ack = StdAck::success(&UpdateValidatorsResponse {});
+    if !update_valset_epoch(deps.storage, epoch)? {
+        return Ok(IbcReceiveResponse::new()
+            .set_ack(ack)
+            .add_attribute("stale_epoch", epoch.to_string()));
+    }
+
     for add in added {
-        if !VALIDATORS.has(deps.storage, &add) {
-            VALIDATORS.save(deps.storage, &add, &Validator::new())?;
-        }
+        activate_validator(deps.storage, &add)?;
     }
     for remove in removed {
         if let Some(mut val) = VALIDATORS.may_load(deps.storage, &remove)? {
             val.status = ValStatus::Removed;
             VALIDATORS.save(deps.storage, &remove, &val)?;
+            set_jailed(deps.storage, &remove)?;
         }
     }
-    let ack = StdAck::success(&UpdateValidatorsResponse {});
+    for metadata in metadata {
+        VALIDATOR_METADATA.save(deps.storage, &metadata.address, &metadata)?;
+    }
     Ok(IbcReceiveResponse::new().set_ack(ack))
 }
 
+/// Adds a validator of the consumer's active set, or makes a removed one active again
+fn activate_validator(storage: &mut dyn Storage, address: &str) -> StdResult<()> {
+    match VALIDATORS.may_load(storage, address)? {
+        Some(mut val) => {
+            // tombstoned validators stay tombstoned
+            if val.status == ValStatus::Removed {
+                val.status = ValStatus::Active;
+                VALIDATORS.save(storage, address, &val)?;
+                // its stakers may stay, so a later removal can force unbond them again
+                FORCE_UNBOND.remove(storage, address);
+            }
+        }
+        None => VALIDATORS.save(storage, address, &Validator::new())?,
+    }
+    Ok(())
+}
+
+pub fn receive_slash(
+    deps: DepsMut,
+    env: Env,
+    validator: String,
+    percentage: Decimal,
+    infraction_height: u64,
+    tombstone: bool,
+) -> Result<IbcReceiveResponse, ContractError> {
+    let evidence = format!("consumer infraction at height {}", infraction_height);
+    let res = slash_validator(
+        deps.storage,
+        &env,
+        &validator,
+        percentage,
+        tombstone,
+        Some(evidence),
+    );
+    // a bad slash gets an error ack, so the consumer doesn't resend it
+    if let Err(err) = res {
+        return Ok(IbcReceiveResponse::new()
+            .set_ack(StdAck::fail(err.to_string()))
+            .add_attribute("action", "slash")
+            .add_attribute("error", err.to_string()));
+    }
+
+    Ok(IbcReceiveResponse::new()
+        .set_ack(StdAck::success(&SlashResponse {}))
+        .add_attribute("action", "slash")
+        .add_attribute("validator", validator))
+}
+
+/// Records the epoch of a validator set update, returns false if it is older than the last one
+fn update_valset_epoch(storage: &mut dyn Storage, epoch: u64) -> StdResult<bool> {
+    if epoch < VALSET_EPOCH.may_load(storage)?.unwrap_or_default() {
+        return Ok(false);
+    }
+    VALSET_EPOCH.save(storage, &epoch)?;
+    Ok(true)
+}
+
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn ibc_packet_ack(
     deps: DepsMut,
@@ -193,6 +345,22 @@ pub fn ibc_packet_ack(
     let res: StdAck = from_slice(&msg.acknowledgement.data)?;
     // we need to handle the ack based on our request
     let original_packet: ProviderMsg = from_slice(&msg.original_packet.data)?;
+    if let Some((staker, validator, amount)) = parse_exit_packet(&original_packet) {
+        if res.is_ok() {
+            return ack_exit_unstake(deps, staker, validator, amount);
+        }
+        // the exit is sent again on the next channel recovery
+        return Ok(IbcBasicResponse::new().add_event(Event::new("failed_exit_unstake")));
+    }
+    let (original_packet, retry) = parse_retry_packet(original_packet);
+    if let Some(key) = retry {
+        let status = if res.is_ok() {
+            RetryStatus::Succeeded
+        } else {
+            RetryStatus::Failed
+        };
+        finish_retry(deps.storage, key, status)?;
+    }
     match (original_packet.clone(), res.is_ok()) {
         (ProviderMsg::ListValidators {}, true) => {
             let val: ListValidatorsResponse = from_slice(&res.unwrap())?;
@@ -206,15 +374,15 @@ pub fn ibc_packet_ack(
                 amount,
             },
             true,
-        ) => ack_stake(deps, key, validator, amount),
+        ) => ack_stake(deps, env, key, validator, amount),
         (
             ProviderMsg::Stake {
                 key,
-                validator: _,
+                validator,
                 amount,
             },
             false,
-        ) => fail_stake(deps, key, amount),
+        ) => fail_stake(deps, key, validator, amount),
         (
             ProviderMsg::Unstake {
                 key,
@@ -231,29 +399,170 @@ pub fn ibc_packet_ack(
             },
             false,
         ) => fail_unstake(),
+        (ProviderMsg::SyncState {}, true) => {
+            let res: SyncStateResponse = from_slice(&res.unwrap())?;
+            ack_sync_state(deps, env, res)
+        }
+        (ProviderMsg::SyncState {}, false) => fail_sync_state(),
     }
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn ibc_packet_timeout(
-    deps: DepsMut,
+    mut deps: DepsMut,
     env: Env,
     msg: IbcPacketTimeoutMsg,
 ) -> Result<IbcBasicResponse, ContractError> {
     let original_packet: ProviderMsg = from_slice(&msg.packet.data)?;
+    if parse_exit_packet(&original_packet).is_some() {
+        // the exit is sent again on the next channel recovery
+        return Ok(IbcBasicResponse::new().add_event(Event::new("failed_exit_unstake")));
+    }
+    let (original_packet, retry) = parse_retry_packet(original_packet);
+    if let Some(res) = schedule_retry(deps.branch(), &env, &original_packet, retry)? {
+        return Ok(res);
+    }
     match original_packet {
         ProviderMsg::ListValidators {} => fail_list_validators(deps, env, original_packet),
         ProviderMsg::Stake {
             key,
-            validator: _,
+            validator,
             amount,
-        } => fail_stake(deps, key, amount),
+        } => fail_stake(deps, key, validator, amount),
         ProviderMsg::Unstake {
             key: _,
             validator: _,
             amount: _,
         } => fail_unstake(),
+        ProviderMsg::SyncState {} => fail_sync_state(),
+    }
+}
+
+// Resent packets carry the retry key after the staker in the packet key
+const RETRY_KEY_SEPARATOR: char = '/';
+
+/// Sets the packet key of a resent packet, so we can find the retry on ack or timeout
+pub fn retry_packet(mut packet: ProviderMsg, retry: u64) -> ProviderMsg {
+    if let ProviderMsg::Stake { key, .. } | ProviderMsg::Unstake { key, .. } = &mut packet {
+        *key = format!("{}{}{}", key, RETRY_KEY_SEPARATOR, retry);
+    }
+    packet
+}
+
+/// Returns the packet with the staker as key again, and the retry key if it was resent
+fn parse_retry_packet(mut packet: ProviderMsg) -> (ProviderMsg, Option<u64>) {
+    let mut retry = None;
+    if let ProviderMsg::Stake { key, .. } | ProviderMsg::Unstake { key, .. } = &mut packet {
+        if let Some((staker, key_retry)) = key.split_once(RETRY_KEY_SEPARATOR) {
+            retry = key_retry.parse().ok();
+            *key = staker.to_string();
+        }
     }
+    (packet, retry)
+}
+
+// Unstakes of exited stake carry this after the staker in the packet key
+const EXIT_KEY: &str = "exit";
+
+/// Unstakes the stake released by ExitStake on the consumer
+fn exit_packets(storage: &dyn Storage) -> StdResult<Vec<ProviderMsg>> {
+    EXITED_STAKE
+        .range(storage, None, None, Order::Ascending)
+        .map(|item| {
+            let ((staker, validator), amount) = item?;
+            Ok(ProviderMsg::Unstake {
+                key: format!("{}{}{}", staker, RETRY_KEY_SEPARATOR, EXIT_KEY),
+                validator,
+                amount,
+            })
+        })
+        .collect()
+}
+
+/// Returns the staker, validator and amount if this unstakes exited stake
+fn parse_exit_packet(packet: &ProviderMsg) -> Option<(String, String, Uint128)> {
+    match packet {
+        ProviderMsg::Unstake {
+            key,
+            validator,
+            amount,
+        } => match key.split_once(RETRY_KEY_SEPARATOR) {
+            Some((staker, EXIT_KEY)) => Some((staker.to_string(), validator.clone(), *amount)),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+fn finish_retry(
+    storage: &mut dyn Storage,
+    key: u64,
+    status: RetryStatus,
+) -> Result<(), ContractError> {
+    RETRIES.update::<_, ContractError>(storage, key, |retry| {
+        let mut retry = retry.ok_or(ContractError::UnknownRetry(key))?;
+        retry.status = status;
+        Ok(retry)
+    })?;
+    Ok(())
+}
+
+/// Keeps a timed out stake or unstake packet around to be resent, if the retry policy allows it.
+/// Returns None if the packet should fail instead.
+fn schedule_retry(
+    deps: DepsMut,
+    env: &Env,
+    packet: &ProviderMsg,
+    retry: Option<u64>,
+) -> Result<Option<IbcBasicResponse>, ContractError> {
+    let owner = match packet {
+        ProviderMsg::Stake { key, .. } | ProviderMsg::Unstake { key, .. } => {
+            deps.api.addr_validate(key)?
+        }
+        // fail_list_validators resends it with its own retry counter
+        ProviderMsg::ListValidators {} => return Ok(None),
+        // holds no staker funds, the SyncState crank asks again
+        ProviderMsg::SyncState {} => return Ok(None),
+    };
+    let policy = RETRY_POLICY.may_load(deps.storage)?.unwrap_or_default();
+
+    let (key, mut retry) = match retry {
+        Some(key) => (key, RETRIES.load(deps.storage, key)?),
+        None => {
+            if policy.max_attempts == 0 {
+                return Ok(None);
+            }
+            let key = RETRY_COUNTER.may_load(deps.storage)?.unwrap_or_default() + 1;
+            RETRY_COUNTER.save(deps.storage, &key)?;
+            OWNER_RETRIES.save(deps.storage, (&owner, key), &Empty {})?;
+            let retry = Retry {
+                owner,
+                packet: packet.clone(),
+                attempts: 0,
+                retry_after: 0,
+                status: RetryStatus::Pending,
+            };
+            (key, retry)
+        }
+    };
+
+    if retry.attempts >= policy.max_attempts {
+        retry.status = RetryStatus::Failed;
+        RETRIES.save(deps.storage, key, &retry)?;
+        return Ok(None);
+    }
+    retry.status = RetryStatus::Pending;
+    retry.retry_after = env.block.height + policy.backoff_blocks;
+    RETRIES.save(deps.storage, key, &retry)?;
+
+    Ok(Some(
+        IbcBasicResponse::new().add_event(
+            Event::new("retry_scheduled")
+                .add_attribute("key", key.to_string())
+                .add_attribute("owner", retry.owner)
+                .add_attribute("retry_after", retry.retry_after.to_string()),
+        ),
+    ))
 }
 
 pub fn ack_list_validators(
@@ -261,10 +570,31 @@ pub fn ack_list_validators(
     _env: Env,
     res: ListValidatorsResponse,
 ) -> Result<IbcBasicResponse, ContractError> {
-    for val in res.validators {
-        VALIDATORS.save(deps.storage, &val, &Validator::new())?;
-    }
     LIST_VALIDATORS_RETRIES.save(deps.storage, &LIST_VALIDATORS_MAX_RETRIES)?;
+    if !update_valset_epoch(deps.storage, res.epoch)? {
+        return Ok(IbcBasicResponse::new()
+            .add_attribute("action", "ack list_validators")
+            .add_attribute("stale_epoch", res.epoch.to_string()));
+    }
+
+    // We might already know validators from a previous channel, so keep their stake
+    // and only update the status to match the consumer
+    let known = VALIDATORS
+        .range(deps.storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<_>>>()?;
+    for (address, mut val) in known {
+        if val.status == ValStatus::Active && !res.validators.contains(&address) {
+            val.status = ValStatus::Removed;
+            VALIDATORS.save(deps.storage, &address, &val)?;
+            set_jailed(deps.storage, &address)?;
+        }
+    }
+    for address in res.validators {
+        activate_validator(deps.storage, &address)?;
+    }
+    for metadata in res.metadata {
+        VALIDATOR_METADATA.save(deps.storage, &metadata.address, &metadata)?;
+    }
     Ok(IbcBasicResponse::new().add_attribute("action", "ack list_validators"))
 }
 
@@ -281,8 +611,11 @@ pub fn fail_list_validators(
     }
     LIST_VALIDATORS_RETRIES.save(deps.storage, &(retries - 1))?;
 
-    // do retry
-    let channel_id = CHANNEL.load(deps.storage)?;
+    // no retry if the channel was closed in the meantime, a new channel lists again
+    let channel_id = match CHANNEL.may_load(deps.storage)? {
+        Some(channel_id) => channel_id,
+        None => return Ok(IbcBasicResponse::new().add_event(Event::new("list_validators_fail"))),
+    };
     let msg = IbcMsg::SendPacket {
         channel_id,
         data: to_binary(&packet)?,
@@ -295,6 +628,7 @@ pub fn fail_list_validators(
 
 fn ack_stake(
     deps: DepsMut,
+    env: Env,
     staker: String,
     validator: String,
     amount: Uint128,
@@ -302,6 +636,11 @@ fn ack_stake(
     let staker = deps.api.addr_validate(&staker)?;
 
     let mut val = VALIDATORS.load(deps.storage, &validator)?;
+    // the validator lost all its stake while the packet was in flight, there are no shares
+    // to give for the new stake, so it is handled like a failed stake
+    if val.multiplier.is_zero() {
+        return fail_stake(deps, staker.into_string(), validator, amount);
+    }
     let mut stake = STAKED
         .may_load(deps.storage, (&staker, &validator))?
         .unwrap_or_default();
@@ -312,25 +651,55 @@ fn ack_stake(
         val.shares_to_tokens(stake.shares),
     )?;
 
+    let old_locked = stake.locked;
+    // take slashes first, so they are attributed with the stake they hit
+    let slash = take_account_slash(deps.storage, &staker, &validator, &mut stake, &val)?;
     stake.stake_validator(&mut val, amount);
+    update_voting_power(
+        deps.storage,
+        &staker,
+        env.block.height,
+        old_locked,
+        stake.locked,
+    )?;
+    release_pending_stake(deps.storage, &validator, amount)?;
+    CONSUMER_STAKE.update::<_, StdError>(deps.storage, &validator, |stake| {
+        Ok(stake.unwrap_or_default() + amount)
+    })?;
     STAKED.save(deps.storage, (&staker, &validator), &stake)?;
+    VALIDATOR_STAKERS.save(deps.storage, (&validator, &staker), &Empty {})?;
     VALIDATORS.save(deps.storage, &validator, &val)?;
 
-    Ok(IbcBasicResponse::new().add_event(Event::new("ack_stake")))
+    let mut res = IbcBasicResponse::new().add_event(Event::new("ack_stake"));
+    if let Some(slash) = slash {
+        let msg = WasmMsg::Execute {
+            contract_addr: CONFIG.load(deps.storage)?.lockup.into_string(),
+            msg: to_binary(&ClaimProviderMsg::SlashClaim {
+                owner: staker.into_string(),
+                amount: slash,
+            })?,
+            funds: vec![],
+        };
+        res = res.add_message(msg);
+    }
+
+    Ok(res)
 }
 
 pub fn fail_stake(
     deps: DepsMut,
     staker: String,
+    validator: String,
     amount: Uint128,
 ) -> Result<IbcBasicResponse, ContractError> {
     let staker = deps.api.addr_validate(&staker)?;
+    release_pending_stake(deps.storage, &validator, amount)?;
     let cfg = CONFIG.load(deps.storage)?;
 
     // We failed to stake, so we return the funds back to lockup
     let msg = WasmMsg::Execute {
         contract_addr: cfg.lockup.into_string(),
-        msg: to_binary(&ClaimProviderMsg::SlashClaim {
+        msg: to_binary(&ClaimProviderMsg::ReleaseClaim {
             owner: staker.into_string(),
             amount,
         })?,
@@ -342,6 +711,24 @@ pub fn fail_stake(
         .add_message(msg))
 }
 
+/// The consumer answered our stake packet, so it no longer counts as pending
+fn release_pending_stake(
+    storage: &mut dyn Storage,
+    validator: &str,
+    amount: Uint128,
+) -> Result<(), ContractError> {
+    let pending = PENDING_STAKE
+        .may_load(storage, validator)?
+        .unwrap_or_default();
+    let pending = pending.saturating_sub(amount);
+    if pending.is_zero() {
+        PENDING_STAKE.remove(storage, validator);
+    } else {
+        PENDING_STAKE.save(storage, validator, &pending)?;
+    }
+    Ok(())
+}
+
 pub fn ack_unstake(
     deps: DepsMut,
     env: Env,
@@ -353,17 +740,36 @@ pub fn ack_unstake(
     let mut val = VALIDATORS.load(deps.storage, &validator)?;
     let mut stake = STAKED.load(deps.storage, (&staker, &validator))?;
 
+    // the stake was already released by ExitStake, the consumer just caught up
+    let exited = EXITED_STAKE
+        .may_load(deps.storage, (&staker, &validator))?
+        .unwrap_or_default();
+    if stake.current_value(&val) < amount && exited >= amount {
+        return ack_exit_unstake(deps, staker.into_string(), validator, amount);
+    }
+
     // Calculate rewards with old stake
     stake.calc_pending_rewards(
         val.rewards.rewards_per_token,
         val.shares_to_tokens(stake.shares),
     )?;
 
+    let old_locked = stake.locked;
     stake.unstake_validator(&mut val, amount)?;
     // check if we need to slash
-    let slash = stake.take_slash(&val);
+    let slash = take_account_slash(deps.storage, &staker, &validator, &mut stake, &val)?;
+    update_voting_power(
+        deps.storage,
+        &staker,
+        env.block.height,
+        old_locked,
+        stake.locked,
+    )?;
     STAKED.save(deps.storage, (&staker, &validator), &stake)?;
     VALIDATORS.save(deps.storage, &validator, &val)?;
+    CONSUMER_STAKE.update::<_, StdError>(deps.storage, &validator, |stake| {
+        Ok(stake.unwrap_or_default().saturating_sub(amount))
+    })?;
 
     // create a future claim on number of shares (so we can adjust for later slashing)
     let cfg = CONFIG.load(deps.storage)?;
@@ -388,6 +794,89 @@ pub fn ack_unstake(
     Ok(res)
 }
 
+fn ack_exit_unstake(
+    deps: DepsMut,
+    staker: String,
+    validator: String,
+    amount: Uint128,
+) -> Result<IbcBasicResponse, ContractError> {
+    let staker = deps.api.addr_validate(&staker)?;
+    let key = (&staker, validator.as_str());
+    let exited = EXITED_STAKE
+        .may_load(deps.storage, key)?
+        .unwrap_or_default()
+        .saturating_sub(amount);
+    if exited.is_zero() {
+        EXITED_STAKE.remove(deps.storage, key);
+    } else {
+        EXITED_STAKE.save(deps.storage, key, &exited)?;
+    }
+    CONSUMER_STAKE.update::<_, StdError>(deps.storage, &validator, |stake| {
+        Ok(stake.unwrap_or_default().saturating_sub(amount))
+    })?;
+    Ok(IbcBasicResponse::new().add_event(Event::new("ack_exit_unstake")))
+}
+
 pub fn fail_unstake() -> Result<IbcBasicResponse, ContractError> {
     Ok(IbcBasicResponse::new().add_event(Event::new("failed_unstake")))
 }
+
+/// Compares the consumer stake with the stake we expect it to hold, and records every validator
+/// where they differ. Our slashes are not drift, they never reach the consumer.
+/// Note: stake packets that are still in flight show up as drift until they are acked.
+pub fn ack_sync_state(
+    deps: DepsMut,
+    env: Env,
+    res: SyncStateResponse,
+) -> Result<IbcBasicResponse, ContractError> {
+    let mut consumer: BTreeMap<String, Uint128> = res
+        .validators
+        .into_iter()
+        .map(|v| (v.validator, v.stake))
+        .collect();
+
+    let mut stakes = VALIDATORS
+        .keys(deps.storage, None, None, Order::Ascending)
+        .map(|address| {
+            let address = address?;
+            let stake = CONSUMER_STAKE
+                .may_load(deps.storage, &address)?
+                .unwrap_or_default();
+            Ok((address, stake))
+        })
+        .collect::<StdResult<Vec<_>>>()?;
+    // the consumer might hold stake on validators we don't know
+    for (address, _) in consumer.iter() {
+        if !VALIDATORS.has(deps.storage, address) {
+            stakes.push((address.clone(), Uint128::zero()));
+        }
+    }
+
+    STATE_DRIFT.clear(deps.storage);
+    let mut res = IbcBasicResponse::new().add_attribute("action", "ack sync_state");
+    for (address, provider_stake) in stakes {
+        let consumer_stake = consumer.remove(&address).unwrap_or_default();
+        if provider_stake == consumer_stake {
+            continue;
+        }
+        let drift = StakeDrift {
+            provider_stake,
+            consumer_stake,
+        };
+        STATE_DRIFT.save(deps.storage, &address, &drift)?;
+        res = res.add_event(
+            Event::new("state_drift")
+                .add_attribute("validator", address)
+                .add_attribute("provider_stake", provider_stake)
+                .add_attribute("consumer_stake", consumer_stake),
+        );
+    }
+    LAST_STATE_SYNC.save(deps.storage, &env.block.time)?;
+
+    Ok(res)
+}
+
+pub fn fail_sync_state() -> Result<IbcBasicResponse, ContractError> {
+    // not retried, ExecuteMsg::SyncState can be called again
+    Ok(IbcBasicResponse::new().add_event(Event::new("failed_sync_state")))
+}
@@ -1,8 +1,10 @@
 use serde::Serialize;
 
-use crate::state::ValStatus;
+use crate::state::{Operation, RetryStatus, ValStatus};
 use cosmwasm_schema::{cw_serde, QueryResponses};
-use cosmwasm_std::{to_binary, Binary, Decimal, StdResult, Uint128};
+use cosmwasm_std::{to_binary, Binary, Decimal, StdResult, Timestamp, Uint128};
+use cw_controllers::Claim;
+use mesh_ibc::{ProviderMsg, ValidatorMetadata};
 
 #[cw_serde]
 pub struct InstantiateMsg {
@@ -17,6 +19,17 @@ pub struct InstantiateMsg {
     pub rewards_ibc_denom: String,
     /// Packet time for ibc calls
     pub packet_lifetime: Option<u64>,
+    /// Admin that can update the config, defaults to the sender
+    pub admin: Option<String>,
+    /// Guardian that can pause operations during incidents
+    pub guardian: Option<String>,
+    /// Limits on how much can be staked, no limits if not set
+    pub stake_caps: Option<StakeCaps>,
+    /// Seconds the channel may stay closed before stakers can exit to lockup,
+    /// defaults to the unbonding period
+    pub channel_recovery_period: Option<u64>,
+    /// Retries for timed out stake and unstake packets, no retries if not set
+    pub retry_policy: Option<RetryPolicy>,
 }
 
 #[cw_serde]
@@ -25,6 +38,50 @@ pub struct ConsumerInfo {
     pub connection_id: String,
 }
 
+#[cw_serde]
+#[derive(Default)]
+pub struct StakeCaps {
+    /// Max tokens staked over all validators
+    pub max_total_stake: Option<Uint128>,
+    /// Max tokens staked on a single validator
+    pub max_validator_stake: Option<Uint128>,
+    /// Max share of the total stake a single validator may have
+    pub max_validator_share: Option<Decimal>,
+    /// The share cap is only enforced once the total stake is above this,
+    /// so the first stakers can bootstrap the validator set
+    pub share_cap_min_total: Uint128,
+}
+
+/// Config fields the admin can update, unset fields are left as they are
+#[cw_serde]
+#[derive(Default)]
+pub struct ConfigUpdate {
+    /// Unbonding period of the remote chain in seconds, only applies to new claims
+    pub unbonding_period: Option<u64>,
+    /// IBC denom string - "port_id/channel_id/denom"
+    pub rewards_ibc_denom: Option<String>,
+    /// Packet time for ibc calls
+    pub packet_lifetime: Option<u64>,
+    /// Only possible while no channel is open
+    pub connection_id: Option<String>,
+    /// Replaces the current stake caps
+    pub stake_caps: Option<StakeCaps>,
+    /// Seconds the channel may stay closed before stakers can exit to lockup
+    pub channel_recovery_period: Option<u64>,
+    /// Only applies to future timeouts
+    pub retry_policy: Option<RetryPolicy>,
+}
+
+#[cw_serde]
+#[derive(Default)]
+pub struct RetryPolicy {
+    /// How often a timed out stake or unstake packet can be resent, 0 fails it right away.
+    /// ListValidators has its own retries, and a failed SyncState is sent again by calling it.
+    pub max_attempts: u32,
+    /// Blocks to wait after a timeout before the packet can be resent
+    pub backoff_blocks: u64,
+}
+
 #[cw_serde]
 pub struct SlasherInfo {
     pub code_id: u64,
@@ -50,6 +107,8 @@ pub enum ExecuteMsg {
         /// do we forcibly unbond this validator on the provider side,
         /// regardless of the behavior of the consumer?
         force_unbond: bool,
+        /// reference to the misbehaviour, kept in the slashing history
+        evidence: Option<String>,
     },
     /// This gives the receiver access to slash part up to this much claim
     ReceiveClaim {
@@ -67,6 +126,62 @@ pub enum ExecuteMsg {
     ClaimRewards {
         validator: String,
     },
+    /// Starts unbonding for all stakers of a removed or tombstoned validator.
+    /// Permissionless, every call processes the next batch of stakers.
+    ForceUnbondValidator {
+        validator: String,
+    },
+    /// Passes the slashes of this validator on to lockup for every staker.
+    /// Permissionless, every call continues after the last processed staker.
+    /// With `start_after`, processes the stakers after it without moving that cursor.
+    ProcessSlash {
+        validator: String,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    /// Admin only, updates the given config fields
+    UpdateConfig(ConfigUpdate),
+    /// Admin only, proposes a new admin that has to accept it
+    TransferAdmin {
+        new_admin: String,
+    },
+    /// Called by the proposed admin to take over
+    AcceptAdmin {},
+    /// Admin only, sets or removes the guardian
+    UpdateGuardian {
+        guardian: Option<String>,
+    },
+    /// Guardian or admin only, stops the given operations until unpaused
+    Pause {
+        operations: Vec<Operation>,
+    },
+    /// Guardian or admin only, resumes the given operations
+    Unpause {
+        operations: Vec<Operation>,
+    },
+    /// Sets the address that receives all future rewards of the sender.
+    /// Setting it back to the sender's own address clears it.
+    SetWithdrawAddress {
+        address: String,
+    },
+    /// Only possible once the channel was closed for longer than the recovery period.
+    /// Unstakes all stake of the sender and releases it on lockup right away. The consumer
+    /// unstakes it once a channel connects again.
+    ExitStake {},
+    /// Asks the consumer for the validator set again, to refresh validator status and metadata
+    RefreshValidators {},
+    /// Asks the consumer for its stake per validator, to detect drift from our state
+    SyncState {},
+    /// Admin only, lowers our stake on this validator to what the consumer reported
+    /// in the last state sync. The difference is slashed from all stakers.
+    CorrectStateDrift {
+        validator: String,
+    },
+    /// Resends a timed out packet once its backoff passed. Permissionless.
+    /// If the channel was closed in the meantime, the packet fails instead.
+    RetryPending {
+        key: u64,
+    },
 }
 
 #[cw_serde]
@@ -77,6 +192,9 @@ pub enum QueryMsg {
     /// how much this account has staked where
     #[returns(AccountResponse)]
     Account { address: String },
+    /// Full position of the address: stakes, rewards, unbonding and the lien in lockup
+    #[returns(PortfolioResponse)]
+    Portfolio { address: String },
     /// Details of one validator
     #[returns(ValidatorResponse)]
     Validator { address: String },
@@ -86,12 +204,68 @@ pub enum QueryMsg {
         start_after: Option<String>,
         limit: Option<u32>,
     },
+    /// Current stake of the validators compared to the stake caps
+    #[returns(UtilizationResponse)]
+    Utilization {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    /// Which operations are currently paused
+    #[returns(PausedResponse)]
+    Paused {},
+    /// Where the rewards of this account are sent to
+    #[returns(WithdrawAddressResponse)]
+    WithdrawAddress { address: String },
+    /// Whether the channel is open, or since when it is closed
+    #[returns(ChannelResponse)]
+    Channel {},
+    /// Validators where our stake differed from the consumer in the last state sync
+    #[returns(StateDriftResponse)]
+    StateDrift {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    /// Timed out packets of this staker and what happened to them
+    #[returns(RetriesResponse)]
+    Retries {
+        owner: String,
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
+    /// Slashes of this validator, oldest first
+    #[returns(SlashingHistoryResponse)]
+    SlashingHistory {
+        validator: String,
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
+    /// How much each slash took from the stake of this address
+    #[returns(AccountSlashesResponse)]
+    AccountSlashes { address: String },
+    /// Cross-staked value of this address at the given height, defaults to the current height.
+    /// Follows the dao-dao voting module interface.
+    #[returns(VotingPowerAtHeightResponse)]
+    VotingPowerAtHeight {
+        address: String,
+        height: Option<u64>,
+    },
+    /// Total cross-staked value at the given height, defaults to the current height
+    #[returns(TotalPowerAtHeightResponse)]
+    TotalPowerAtHeight { height: Option<u64> },
 }
 
 #[cw_serde]
 pub struct ConfigResponse {
     pub consumer: ConsumerInfo,
     pub slasher: Option<String>,
+    pub lockup: String,
+    pub unbonding_period: u64,
+    pub rewards_ibc_denom: String,
+    pub packet_lifetime: u64,
+    pub channel_recovery_period: u64,
+    pub retry_policy: RetryPolicy,
+    pub admin: Option<String>,
+    pub pending_admin: Option<String>,
 }
 
 #[cw_serde]
@@ -106,15 +280,143 @@ pub struct StakeInfo {
     pub slashed: Uint128,
 }
 
+#[cw_serde]
+pub struct PortfolioResponse {
+    pub positions: Vec<Position>,
+    /// Unbonding tokens, released to lockup once mature
+    pub unbonding: Vec<Claim>,
+    /// Claim this contract holds on the address' collateral in lockup
+    pub lien: Uint128,
+}
+
+#[cw_serde]
+pub struct Position {
+    pub validator: String,
+    pub status: ValStatus,
+    pub tokens: Uint128,
+    pub shares: Uint128,
+    pub slashed: Uint128,
+    /// Rewards that can be claimed now
+    pub pending_rewards: Uint128,
+}
+
 #[cw_serde]
 pub struct ValidatorResponse {
     pub address: String,
     pub tokens: Uint128,
     pub status: ValStatus,
     pub multiplier: Decimal,
+    /// Commission and jailed status on the consumer chain, if the consumer sent it
+    pub metadata: Option<ValidatorMetadata>,
 }
 
 #[cw_serde]
 pub struct ListValidatorsResponse {
     pub validators: Vec<ValidatorResponse>,
 }
+
+#[cw_serde]
+pub struct WithdrawAddressResponse {
+    pub withdraw_address: String,
+}
+
+#[cw_serde]
+pub struct PausedResponse {
+    pub guardian: Option<String>,
+    pub paused: Vec<Operation>,
+}
+
+#[cw_serde]
+pub struct ChannelResponse {
+    pub channel: Option<String>,
+    pub closed_at: Option<Timestamp>,
+    /// When stakers can exit to lockup, if the channel is not recovered before
+    pub exit_at: Option<Timestamp>,
+}
+
+#[cw_serde]
+pub struct VotingPowerAtHeightResponse {
+    pub power: Uint128,
+    pub height: u64,
+}
+
+#[cw_serde]
+pub struct TotalPowerAtHeightResponse {
+    pub power: Uint128,
+    pub height: u64,
+}
+
+#[cw_serde]
+pub struct RetriesResponse {
+    pub retries: Vec<RetryInfo>,
+}
+
+#[cw_serde]
+pub struct RetryInfo {
+    pub key: u64,
+    pub packet: ProviderMsg,
+    pub attempts: u32,
+    pub retry_after: u64,
+    pub status: RetryStatus,
+}
+
+#[cw_serde]
+pub struct SlashingHistoryResponse {
+    pub slashes: Vec<SlashInfo>,
+}
+
+#[cw_serde]
+pub struct SlashInfo {
+    pub id: u64,
+    pub height: u64,
+    pub time: Timestamp,
+    pub percentage: Decimal,
+    pub evidence: Option<String>,
+    pub tombstoned: bool,
+}
+
+#[cw_serde]
+pub struct AccountSlashesResponse {
+    pub slashes: Vec<AccountSlash>,
+}
+
+#[cw_serde]
+pub struct AccountSlash {
+    pub validator: String,
+    pub id: u64,
+    pub height: u64,
+    pub percentage: Decimal,
+    /// tokens this slash took from the account
+    pub amount: Uint128,
+}
+
+#[cw_serde]
+pub struct StateDriftResponse {
+    /// When the consumer last answered a state sync
+    pub last_sync: Option<Timestamp>,
+    pub validators: Vec<ValidatorDrift>,
+}
+
+#[cw_serde]
+pub struct ValidatorDrift {
+    pub address: String,
+    pub provider_stake: Uint128,
+    pub consumer_stake: Uint128,
+}
+
+#[cw_serde]
+pub struct UtilizationResponse {
+    pub caps: StakeCaps,
+    /// Total stake, including stake waiting for the consumer to ack
+    pub total_stake: Uint128,
+    pub validators: Vec<ValidatorUtilization>,
+}
+
+#[cw_serde]
+pub struct ValidatorUtilization {
+    pub address: String,
+    /// Stake on this validator, including stake waiting for the consumer to ack
+    pub stake: Uint128,
+    /// Share of the total stake
+    pub share: Decimal,
+}
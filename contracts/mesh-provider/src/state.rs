@@ -1,10 +1,12 @@
 use cosmwasm_schema::cw_serde;
 
-use cosmwasm_std::{Addr, Decimal, Fraction, Uint128};
-use cw_controllers::Claims;
-use cw_storage_plus::{Item, Map};
+use cosmwasm_std::{Addr, Decimal, Empty, Fraction, Order, StdResult, Storage, Timestamp, Uint128};
+use cw_controllers::{Admin, Claims};
+use cw_storage_plus::{Item, Map, SnapshotItem, SnapshotMap, Strategy};
 
-use crate::msg::ConsumerInfo;
+use mesh_ibc::{ProviderMsg, ValidatorMetadata};
+
+use crate::msg::{ConsumerInfo, RetryPolicy, StakeCaps};
 use crate::ContractError;
 
 #[cw_serde]
@@ -22,9 +24,21 @@ pub struct Config {
 pub const LIST_VALIDATORS_MAX_RETRIES: u8 = 5;
 
 pub const CONFIG: Item<Config> = Item::new("config");
+pub const ADMIN: Admin = Admin::new("admin");
+/// Proposed admin, waiting to accept the transfer
+pub const PENDING_ADMIN: Item<Addr> = Item::new("pending_admin");
+/// Can pause operations, but not change the config
+pub const GUARDIAN: Admin = Admin::new("guardian");
+pub const PAUSED: Item<Vec<Operation>> = Item::new("paused");
+pub const STAKE_CAPS: Item<StakeCaps> = Item::new("stake_caps");
+
 pub const PACKET_LIFETIME: Item<u64> = Item::new("packet_time");
 pub const CHANNEL: Item<String> = Item::new("channel");
 pub const PORT: Item<String> = Item::new("port");
+/// When the channel was closed, removed again once a new channel connects
+pub const CHANNEL_CLOSED_AT: Item<Timestamp> = Item::new("channel_closed_at");
+/// Seconds the channel may stay closed before stakers can exit to lockup
+pub const CHANNEL_RECOVERY_PERIOD: Item<u64> = Item::new("channel_recovery_period");
 
 /// The number of retries remaining to query mesh-consumer
 pub const LIST_VALIDATORS_RETRIES: Item<u8> = Item::new("list_validators_retry_state");
@@ -32,11 +46,252 @@ pub const LIST_VALIDATORS_RETRIES: Item<u8> = Item::new("list_validators_retry_s
 // info on each validator, including voting and slashing
 pub const VALIDATORS: Map<&str, Validator> = Map::new("validators");
 
+// epoch of the last validator set update we applied, older updates are ignored
+pub const VALSET_EPOCH: Item<u64> = Item::new("valset_epoch");
+
+// details of each validator as reported by the consumer
+pub const VALIDATOR_METADATA: Map<&str, ValidatorMetadata> = Map::new("validator_metadata");
+
 // map from (delgator, validator) to current stake - stored as shares, previously multiplied
 pub const STAKED: Map<(&Addr, &str), Stake> = Map::new("staked");
 
+// stake sent to the consumer per validator, that was not acked yet
+pub const PENDING_STAKE: Map<&str, Uint128> = Map::new("pending_stake");
+
+// map from (validator, delegator), used to iterate over all stakers of one validator
+pub const VALIDATOR_STAKERS: Map<(&str, &Addr), Empty> = Map::new("validator_stakers");
+
+// validators where our stake differs from the consumer, as of the last state sync
+pub const STATE_DRIFT: Map<&str, StakeDrift> = Map::new("state_drift");
+// stake the consumer should hold per validator: what it acked for staking minus what it acked
+// for unstaking. Our slashes don't change it, as the consumer never sees them.
+pub const CONSUMER_STAKE: Map<&str, Uint128> = Map::new("consumer_stake");
+// stake released by ExitStake that the consumer still holds, by (staker, validator).
+// It is unstaked on the consumer once a channel connects again.
+pub const EXITED_STAKE: Map<(&Addr, &str), Uint128> = Map::new("exited_stake");
+pub const LAST_STATE_SYNC: Item<Timestamp> = Item::new("last_state_sync");
+
+pub const RETRY_POLICY: Item<RetryPolicy> = Item::new("retry_policy");
+pub const RETRY_COUNTER: Item<u64> = Item::new("retry_counter");
+// timed out packets that can be resent, by retry key
+pub const RETRIES: Map<u64, Retry> = Map::new("retries");
+// map from (owner, retry key), used to list the retries of one staker
+pub const OWNER_RETRIES: Map<(&Addr, u64), Empty> = Map::new("owner_retries");
+
+// counter for slash event ids
+pub const SLASH_COUNTER: Item<u64> = Item::new("slash_counter");
+// map from (validator, slash id) to slash event
+pub const SLASHES: Map<(&str, u64), SlashEvent> = Map::new("slashes");
+// map from (staker, validator, slash id) to the tokens that slash took from the staker.
+// Only written once the slash is taken from the stake, which resets its locked amount.
+pub const ACCOUNT_SLASHES: Map<(&Addr, &str, u64), Uint128> = Map::new("account_slashes");
+
+// progress of force unbonding all stakers of a validator
+pub const FORCE_UNBOND: Map<&str, ForceUnbondProgress> = Map::new("force_unbond");
+// last staker handled by ProcessSlash, per validator. Removed once all stakers are processed.
+pub const PROCESS_SLASH: Map<&str, Addr> = Map::new("process_slash");
+
 pub const CLAIMS: Claims = Claims::new("claims");
 
+// voting power of each staker, the sum of their locked stake, snapshotted for governance.
+// Slashes only count once the staker unstakes or ProcessSlash reaches them,
+// as we don't touch every staker on a slash.
+pub const VOTING_POWER: SnapshotMap<&Addr, Uint128> = SnapshotMap::new(
+    "voting_power",
+    "voting_power__checkpoints",
+    "voting_power__changelog",
+    Strategy::EveryBlock,
+);
+pub const TOTAL_POWER: SnapshotItem<Uint128> = SnapshotItem::new(
+    "total_power",
+    "total_power__checkpoints",
+    "total_power__changelog",
+    Strategy::EveryBlock,
+);
+
+// map from delegator to the address their rewards are sent to, if not the delegator itself
+pub const WITHDRAW_ADDRESSES: Map<&Addr, Addr> = Map::new("withdraw_addresses");
+
+/// Returns the address that should receive the rewards of this staker
+pub fn withdraw_address(storage: &dyn Storage, staker: &Addr) -> StdResult<Addr> {
+    Ok(WITHDRAW_ADDRESSES
+        .may_load(storage, staker)?
+        .unwrap_or_else(|| staker.clone()))
+}
+
+/// Marks the metadata of a validator that dropped out of the bonded set
+pub fn set_jailed(storage: &mut dyn Storage, address: &str) -> StdResult<()> {
+    if let Some(mut metadata) = VALIDATOR_METADATA.may_load(storage, address)? {
+        metadata.jailed = true;
+        VALIDATOR_METADATA.save(storage, address, &metadata)?;
+    }
+    Ok(())
+}
+
+/// Updates the voting power of the staker after their locked stake changed
+pub fn update_voting_power(
+    storage: &mut dyn Storage,
+    staker: &Addr,
+    height: u64,
+    old_locked: Uint128,
+    new_locked: Uint128,
+) -> StdResult<()> {
+    if old_locked == new_locked {
+        return Ok(());
+    }
+    let power = VOTING_POWER.may_load(storage, staker)?.unwrap_or_default();
+    VOTING_POWER.save(storage, staker, &(power + new_locked - old_locked), height)?;
+    let total = TOTAL_POWER.may_load(storage)?.unwrap_or_default();
+    TOTAL_POWER.save(storage, &(total + new_locked - old_locked), height)?;
+    Ok(())
+}
+
+/// Walks back the slashes of the validator, from the current value of the stake up to its
+/// locked amount, to find how much each slash not yet taken from the stake took. Oldest first.
+pub fn untaken_slashes(
+    storage: &dyn Storage,
+    validator: &str,
+    stake: &Stake,
+    val: &Validator,
+) -> StdResult<Vec<(u64, Uint128)>> {
+    let mut value = stake.current_value(val);
+    let mut found = vec![];
+    for res in SLASHES
+        .prefix(validator)
+        .range(storage, None, None, Order::Descending)
+    {
+        if value >= stake.locked {
+            break;
+        }
+        let (id, event) = res?;
+        let kept = Decimal::one() - event.percentage;
+        let before = if kept.is_zero() {
+            stake.locked
+        } else {
+            value
+                .multiply_ratio(kept.denominator(), kept.numerator())
+                .min(stake.locked)
+        };
+        found.push((id, before - value));
+        value = before;
+    }
+    found.reverse();
+    Ok(found)
+}
+
+/// Takes the slashes from the stake like `Stake::take_slash`, recording how much each one
+/// took so the account keeps its slashing history
+pub fn take_account_slash(
+    storage: &mut dyn Storage,
+    staker: &Addr,
+    validator: &str,
+    stake: &mut Stake,
+    val: &Validator,
+) -> StdResult<Option<Uint128>> {
+    for (id, amount) in untaken_slashes(storage, validator, stake, val)? {
+        ACCOUNT_SLASHES.update(storage, (staker, validator, id), |old| -> StdResult<_> {
+            Ok(old.unwrap_or_default() + amount)
+        })?;
+    }
+    Ok(stake.take_slash(val))
+}
+
+/// Stake of the validator, including stake waiting for the consumer to ack
+pub fn validator_stake(
+    storage: &dyn Storage,
+    address: &str,
+    val: &Validator,
+) -> StdResult<Uint128> {
+    let pending = PENDING_STAKE
+        .may_load(storage, address)?
+        .unwrap_or_default();
+    Ok(val.stake_value() + pending)
+}
+
+/// Total stake over all validators, including stake waiting for the consumer to ack
+pub fn total_stake(storage: &dyn Storage) -> StdResult<Uint128> {
+    VALIDATORS
+        .range(storage, None, None, Order::Ascending)
+        .map(|res| {
+            let (address, val) = res?;
+            validator_stake(storage, &address, &val)
+        })
+        .sum()
+}
+
+/// Operations that can be paused separately by the guardian
+#[cw_serde]
+pub enum Operation {
+    /// Receiving new claims from lockup
+    Staking,
+    /// Unstaking and force unbonding validators
+    Unstaking,
+    /// Claiming rewards
+    Rewards,
+    /// Receiving packets from the consumer
+    IbcReceive,
+}
+
+/// Errors if the given operation is currently paused
+pub fn assert_not_paused(storage: &dyn Storage, operation: Operation) -> Result<(), ContractError> {
+    let paused = PAUSED.may_load(storage)?.unwrap_or_default();
+    if paused.contains(&operation) {
+        return Err(ContractError::Paused(operation));
+    }
+    Ok(())
+}
+
+#[cw_serde]
+#[derive(Default)]
+pub struct ForceUnbondProgress {
+    /// last staker we sent an unstake packet for
+    pub last_staker: Option<Addr>,
+    /// true once all stakers were processed
+    pub finished: bool,
+}
+
+#[cw_serde]
+pub struct SlashEvent {
+    pub height: u64,
+    pub time: Timestamp,
+    pub percentage: Decimal,
+    /// reference to the misbehaviour, as given by the slasher
+    pub evidence: Option<String>,
+    pub tombstoned: bool,
+}
+
+#[cw_serde]
+pub struct Retry {
+    /// staker that sent the packet
+    pub owner: Addr,
+    pub packet: ProviderMsg,
+    /// how often the packet was resent
+    pub attempts: u32,
+    /// block height from which the packet can be resent
+    pub retry_after: u64,
+    pub status: RetryStatus,
+}
+
+#[cw_serde]
+pub enum RetryStatus {
+    /// Timed out, waiting for RetryPending
+    Pending,
+    /// Resent, waiting for the consumer
+    Resent,
+    /// The consumer acked the resent packet
+    Succeeded,
+    /// Gave up, failed like a packet without retries
+    Failed,
+}
+
+#[cw_serde]
+pub struct StakeDrift {
+    /// stake we expect the consumer to hold for this validator, see `CONSUMER_STAKE`
+    pub provider_stake: Uint128,
+    /// stake the consumer reported for this validator
+    pub consumer_stake: Uint128,
+}
+
 #[cw_serde]
 #[derive(Default)]
 pub struct Stake {
@@ -1,34 +1,46 @@
 use std::str::FromStr;
 
 use cosmwasm_std::{
-    coins,
+    coins, from_binary,
     testing::{mock_env, mock_info},
-    to_binary, Addr, Decimal, IbcMsg, Uint128, WasmMsg,
+    to_binary, Addr, Decimal, DepsMut, IbcMsg, Uint128, WasmMsg,
 };
+use cw_controllers::AdminError;
 use mesh_apis::ClaimProviderMsg;
-use mesh_ibc::ProviderMsg;
+use mesh_ibc::{ProviderMsg, StdAck, ValidatorStake};
 use mesh_testing::{
     addr,
-    constants::{CHANNEL_ID, DELEGATOR_ADDR, LOCKUP_ADDR, REWARDS_IBC_DENOM, VALIDATOR},
+    constants::{
+        CHANNEL_ID, CREATOR_ADDR, DELEGATOR_ADDR, LOCKUP_ADDR, REWARDS_IBC_DENOM, VALIDATOR,
+    },
 };
 
 use crate::{
-    contract::execute,
+    contract::{
+        execute, query_account_slashes, query_config, query_paused, query_slashing_history,
+        query_utilization, STATE_DRIFT_EVIDENCE,
+    },
     ibc::build_timeout,
-    msg::ExecuteMsg,
-    state::{DelegatorRewards, Stake, ValStatus, CONFIG, STAKED},
+    msg::{ConfigUpdate, ExecuteMsg, StakeCaps, ValidatorUtilization},
+    state::{DelegatorRewards, Operation, Stake, ValStatus, CONFIG, STAKED, VALIDATORS},
     testing::utils::{
-        execute::execute_slash, helpers::add_validator, query::query_validators,
-        setup_unit::setup_unit_with_channel,
+        execute::execute_slash,
+        helpers::add_validator,
+        query::query_validators,
+        setup_unit::{setup_unit, setup_unit_with_channel},
     },
     ContractError,
 };
 
 use super::utils::{
-    execute::execute_claim_rewards,
+    execute::{execute_claim_rewards, execute_set_withdraw_address},
     helpers::{add_rewards, add_stake},
-    ibc_helpers::{add_stake_unit, remove_stake_unit, update_validator_unit},
-    query::query_provider_config,
+    ibc_helpers::{
+        add_stake_fail_unit, add_stake_unit, get_default_init_msg, ibc_close_channel,
+        list_validators_unit, query_account_unit, query_validators_unit, remove_stake_unit,
+        sync_state_unit, update_validator_unit,
+    },
+    query::{query_provider_config, query_withdraw_address},
     setup::setup_with_contract,
 };
 
@@ -103,6 +115,35 @@ fn test_claim_rewards() {
     assert_eq!(balance, coins(1000, REWARDS_IBC_DENOM))
 }
 
+#[test]
+fn test_claim_rewards_to_withdraw_address() {
+    let (mut app, mesh_provider_addr) = setup_with_contract();
+    let withdraw_addr = "custody_addr";
+
+    add_rewards(&mut app, mesh_provider_addr.clone());
+
+    // Defaults to the staker itself
+    let res = query_withdraw_address(&app, mesh_provider_addr.as_str(), DELEGATOR_ADDR).unwrap();
+    assert_eq!(res.withdraw_address, DELEGATOR_ADDR);
+
+    execute_set_withdraw_address(&mut app, mesh_provider_addr.as_str(), withdraw_addr).unwrap();
+    let res = query_withdraw_address(&app, mesh_provider_addr.as_str(), DELEGATOR_ADDR).unwrap();
+    assert_eq!(res.withdraw_address, withdraw_addr);
+
+    execute_claim_rewards(&mut app, mesh_provider_addr.as_str(), VALIDATOR).unwrap();
+
+    // Rewards went to the withdraw address, not the staker
+    let balance = app.wrap().query_all_balances(DELEGATOR_ADDR).unwrap();
+    assert_eq!(balance, vec![]);
+    let balance = app.wrap().query_all_balances(withdraw_addr).unwrap();
+    assert_eq!(balance, coins(1000, REWARDS_IBC_DENOM));
+
+    // Setting it back to ourselves resets it
+    execute_set_withdraw_address(&mut app, mesh_provider_addr.as_str(), DELEGATOR_ADDR).unwrap();
+    let res = query_withdraw_address(&app, mesh_provider_addr.as_str(), DELEGATOR_ADDR).unwrap();
+    assert_eq!(res.withdraw_address, DELEGATOR_ADDR);
+}
+
 #[test]
 fn test_claim_rewards_failing() {
     let (mut app, mesh_provider_addr) = setup_with_contract();
@@ -146,7 +187,7 @@ fn test_unbond() {
         res.messages[0].msg,
         WasmMsg::Execute {
             contract_addr: LOCKUP_ADDR.to_string(),
-            msg: to_binary(&ClaimProviderMsg::SlashClaim {
+            msg: to_binary(&ClaimProviderMsg::ReleaseClaim {
                 owner: DELEGATOR_ADDR.to_string(),
                 amount: Uint128::new(1000),
             })
@@ -309,13 +350,184 @@ fn test_unstake() {
     .unwrap_err();
     assert_eq!(err, ContractError::ZeroAmount);
 
-    // Try with removed validator
+    // Can still unstake from a removed validator
     update_validator_unit(deps.as_mut(), vec![], vec![VALIDATOR.to_string()]).unwrap();
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(DELEGATOR_ADDR, &[]),
+        ExecuteMsg::Unstake {
+            amount: Uint128::new(1000),
+            validator: VALIDATOR.to_string(),
+        },
+    )
+    .unwrap();
+    assert_eq!(res.messages.len(), 1);
+
+    // But not from an unknown one
     let err = execute(
         deps.as_mut(),
         mock_env(),
         mock_info(DELEGATOR_ADDR, &[]),
         ExecuteMsg::Unstake {
+            amount: Uint128::new(1000),
+            validator: "some_validator".to_string(),
+        },
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::UnknownValidator("some_validator".to_string())
+    );
+}
+
+#[test]
+fn test_process_slash() {
+    let (mut deps, _) = setup_unit_with_channel(None, CHANNEL_ID);
+    update_validator_unit(deps.as_mut(), vec![VALIDATOR.to_string()], vec![]).unwrap();
+    for staker in ["staker1", "staker2", "staker3"] {
+        add_stake_unit(deps.as_mut(), staker, VALIDATOR, Uint128::new(1000)).unwrap();
+    }
+    VALIDATORS
+        .update::<_, ContractError>(deps.as_mut().storage, VALIDATOR, |val| {
+            let mut val = val.unwrap();
+            val.slash(Decimal::percent(10));
+            Ok(val)
+        })
+        .unwrap();
+
+    let process_slash = |deps: DepsMut, start_after: Option<&str>| {
+        execute(
+            deps,
+            mock_env(),
+            mock_info("anyone", &[]),
+            ExecuteMsg::ProcessSlash {
+                validator: VALIDATOR.to_string(),
+                start_after: start_after.map(String::from),
+                limit: Some(2),
+            },
+        )
+        .unwrap()
+    };
+    let slash_claim = |owner: &str| {
+        WasmMsg::Execute {
+            contract_addr: LOCKUP_ADDR.to_string(),
+            msg: to_binary(&ClaimProviderMsg::SlashClaim {
+                owner: owner.to_string(),
+                amount: Uint128::new(100),
+            })
+            .unwrap(),
+            funds: vec![],
+        }
+        .into()
+    };
+
+    // An explicit start only processes what follows it, and leaves the cursor alone
+    let res = process_slash(deps.as_mut(), Some("staker1"));
+    let msgs: Vec<_> = res.messages.into_iter().map(|m| m.msg).collect();
+    assert_eq!(msgs, vec![slash_claim("staker2"), slash_claim("staker3")]);
+
+    // First batch starts from the beginning, staker2 was already taken
+    let res = process_slash(deps.as_mut(), None);
+    let msgs: Vec<_> = res.messages.into_iter().map(|m| m.msg).collect();
+    assert_eq!(msgs, vec![slash_claim("staker1")]);
+    let account = query_account_unit(deps.as_ref(), "staker1").unwrap();
+    assert_eq!(account.staked[0].slashed, Uint128::zero());
+
+    // Continues where the last call stopped
+    let res = process_slash(deps.as_mut(), None);
+    assert_eq!(res.messages.len(), 0);
+
+    // Every slash was taken, so there is nothing left to send
+    let res = process_slash(deps.as_mut(), None);
+    assert_eq!(res.messages.len(), 0);
+    let res = process_slash(deps.as_mut(), Some("staker1"));
+    assert_eq!(res.messages.len(), 0);
+
+    // Unstaking no longer slashes again
+    let res = remove_stake_unit(deps.as_mut(), "staker1", VALIDATOR, Uint128::new(900)).unwrap();
+    assert_eq!(res.messages.len(), 0);
+}
+
+#[test]
+fn test_force_unbond_validator() {
+    let (mut deps, _) = setup_unit_with_channel(None, CHANNEL_ID);
+    let other_delegator = "other_delegator";
+
+    update_validator_unit(deps.as_mut(), vec![VALIDATOR.to_string()], vec![]).unwrap();
+    add_stake_unit(deps.as_mut(), DELEGATOR_ADDR, VALIDATOR, Uint128::new(1000)).unwrap();
+    add_stake_unit(deps.as_mut(), other_delegator, VALIDATOR, Uint128::new(500)).unwrap();
+
+    let force_unbond = |deps: DepsMut| {
+        execute(
+            deps,
+            mock_env(),
+            mock_info("anyone", &[]),
+            ExecuteMsg::ForceUnbondValidator {
+                validator: VALIDATOR.to_string(),
+            },
+        )
+    };
+
+    // Cannot force unbond an active validator
+    let err = force_unbond(deps.as_mut()).unwrap_err();
+    assert_eq!(err, ContractError::ActiveValidator(VALIDATOR.to_string()));
+
+    // Once removed, anyone can start unbonding all stakers
+    update_validator_unit(deps.as_mut(), vec![], vec![VALIDATOR.to_string()]).unwrap();
+    let res = force_unbond(deps.as_mut()).unwrap();
+    assert_eq!(res.messages.len(), 2);
+    assert_eq!(
+        res.messages[0].msg,
+        IbcMsg::SendPacket {
+            channel_id: CHANNEL_ID.to_string(),
+            data: to_binary(&ProviderMsg::Unstake {
+                validator: VALIDATOR.to_string(),
+                amount: Uint128::new(1000),
+                key: DELEGATOR_ADDR.to_string()
+            })
+            .unwrap(),
+            timeout: build_timeout(deps.as_ref(), &mock_env()).unwrap(),
+        }
+        .into()
+    );
+    assert_eq!(
+        res.messages[1].msg,
+        IbcMsg::SendPacket {
+            channel_id: CHANNEL_ID.to_string(),
+            data: to_binary(&ProviderMsg::Unstake {
+                validator: VALIDATOR.to_string(),
+                amount: Uint128::new(500),
+                key: other_delegator.to_string()
+            })
+            .unwrap(),
+            timeout: build_timeout(deps.as_ref(), &mock_env()).unwrap(),
+        }
+        .into()
+    );
+
+    // All stakers were processed, nothing left to do
+    let err = force_unbond(deps.as_mut()).unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::ForceUnbondFinished(VALIDATOR.to_string())
+    );
+
+    // A validator that comes back and is removed again can be force unbonded again
+    list_validators_unit(deps.as_mut()).unwrap();
+    let err = force_unbond(deps.as_mut()).unwrap_err();
+    assert_eq!(err, ContractError::ActiveValidator(VALIDATOR.to_string()));
+    update_validator_unit(deps.as_mut(), vec![], vec![VALIDATOR.to_string()]).unwrap();
+    let res = force_unbond(deps.as_mut()).unwrap();
+    assert_eq!(res.messages.len(), 2);
+
+    // New stake is refused for a removed validator
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(LOCKUP_ADDR, &[]),
+        ExecuteMsg::ReceiveClaim {
+            owner: DELEGATOR_ADDR.to_string(),
             amount: Uint128::new(1000),
             validator: VALIDATOR.to_string(),
         },
@@ -323,3 +535,526 @@ fn test_unstake() {
     .unwrap_err();
     assert_eq!(err, ContractError::RemovedValidator(VALIDATOR.to_string()));
 }
+
+#[test]
+fn test_update_config() {
+    let (mut deps, _) = setup_unit(None);
+
+    let update_config = |deps: DepsMut, sender: &str, unbonding_period: Option<u64>| {
+        execute(
+            deps,
+            mock_env(),
+            mock_info(sender, &[]),
+            ExecuteMsg::UpdateConfig(ConfigUpdate {
+                unbonding_period,
+                rewards_ibc_denom: Some("new_denom".to_string()),
+                packet_lifetime: Some(100),
+                connection_id: Some("connection-2".to_string()),
+                ..ConfigUpdate::default()
+            }),
+        )
+    };
+
+    // Only admin can update
+    let err = update_config(deps.as_mut(), DELEGATOR_ADDR, Some(100)).unwrap_err();
+    assert_eq!(err, ContractError::Admin(AdminError::NotAdmin {}));
+
+    // Validate values
+    let err = update_config(deps.as_mut(), CREATOR_ADDR, Some(0)).unwrap_err();
+    assert!(matches!(err, ContractError::InvalidConfig(_)));
+
+    update_config(deps.as_mut(), CREATOR_ADDR, Some(100)).unwrap();
+    let cfg = query_config(deps.as_ref()).unwrap();
+    assert_eq!(cfg.unbonding_period, 100);
+    assert_eq!(cfg.rewards_ibc_denom, "new_denom");
+    assert_eq!(cfg.packet_lifetime, 100);
+    assert_eq!(cfg.consumer.connection_id, "connection-2");
+
+    // Cannot change the connection while a channel is open
+    let (mut deps, _) = setup_unit_with_channel(None, CHANNEL_ID);
+    let err = update_config(deps.as_mut(), CREATOR_ADDR, None).unwrap_err();
+    assert_eq!(err, ContractError::ChannelExists(CHANNEL_ID.to_string()));
+}
+
+#[test]
+fn test_update_unbonding_period_keeps_claims() {
+    let (mut deps, _) = setup_unit_with_channel(None, CHANNEL_ID);
+    let unbonding_period = CONFIG.load(deps.as_ref().storage).unwrap().unbonding_period;
+
+    update_validator_unit(deps.as_mut(), vec![VALIDATOR.to_string()], vec![]).unwrap();
+    add_stake_unit(deps.as_mut(), DELEGATOR_ADDR, VALIDATOR, Uint128::new(1000)).unwrap();
+    remove_stake_unit(deps.as_mut(), DELEGATOR_ADDR, VALIDATOR, Uint128::new(1000)).unwrap();
+
+    // Double the unbonding period after the claim was created
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(CREATOR_ADDR, &[]),
+        ExecuteMsg::UpdateConfig(ConfigUpdate {
+            unbonding_period: Some(unbonding_period * 2),
+            ..ConfigUpdate::default()
+        }),
+    )
+    .unwrap();
+
+    // The existing claim is still released after the old period
+    let mut env = mock_env();
+    env.block.time = env.block.time.plus_seconds(unbonding_period + 1);
+    execute(
+        deps.as_mut(),
+        env,
+        mock_info(DELEGATOR_ADDR, &[]),
+        ExecuteMsg::Unbond {},
+    )
+    .unwrap();
+}
+
+#[test]
+fn test_transfer_admin() {
+    let (mut deps, _) = setup_unit(None);
+    let new_admin = "new_admin";
+
+    let transfer = |deps: DepsMut, sender: &str| {
+        execute(
+            deps,
+            mock_env(),
+            mock_info(sender, &[]),
+            ExecuteMsg::TransferAdmin {
+                new_admin: new_admin.to_string(),
+            },
+        )
+    };
+    let accept = |deps: DepsMut, sender: &str| {
+        execute(
+            deps,
+            mock_env(),
+            mock_info(sender, &[]),
+            ExecuteMsg::AcceptAdmin {},
+        )
+    };
+
+    // Nothing to accept yet
+    let err = accept(deps.as_mut(), new_admin).unwrap_err();
+    assert_eq!(err, ContractError::NoPendingAdmin);
+
+    // Only admin can propose
+    let err = transfer(deps.as_mut(), new_admin).unwrap_err();
+    assert_eq!(err, ContractError::Admin(AdminError::NotAdmin {}));
+
+    transfer(deps.as_mut(), CREATOR_ADDR).unwrap();
+    let cfg = query_config(deps.as_ref()).unwrap();
+    assert_eq!(cfg.admin, Some(CREATOR_ADDR.to_string()));
+    assert_eq!(cfg.pending_admin, Some(new_admin.to_string()));
+
+    // Only the proposed admin can accept
+    let err = accept(deps.as_mut(), DELEGATOR_ADDR).unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized);
+
+    accept(deps.as_mut(), new_admin).unwrap();
+    let cfg = query_config(deps.as_ref()).unwrap();
+    assert_eq!(cfg.admin, Some(new_admin.to_string()));
+    assert_eq!(cfg.pending_admin, None);
+
+    // Old admin lost its rights
+    let err = transfer(deps.as_mut(), CREATOR_ADDR).unwrap_err();
+    assert_eq!(err, ContractError::Admin(AdminError::NotAdmin {}));
+}
+
+#[test]
+fn test_pause_operations() {
+    let guardian = "guardian";
+    let mut init_msg = get_default_init_msg(1);
+    init_msg.guardian = Some(guardian.to_string());
+    let (mut deps, _) = setup_unit_with_channel(Some(init_msg), CHANNEL_ID);
+
+    update_validator_unit(deps.as_mut(), vec![VALIDATOR.to_string()], vec![]).unwrap();
+    add_stake_unit(deps.as_mut(), DELEGATOR_ADDR, VALIDATOR, Uint128::new(1000)).unwrap();
+
+    let pause = |deps: DepsMut, sender: &str, operations: Vec<Operation>| {
+        execute(
+            deps,
+            mock_env(),
+            mock_info(sender, &[]),
+            ExecuteMsg::Pause { operations },
+        )
+    };
+    let receive_claim = |deps: DepsMut| {
+        execute(
+            deps,
+            mock_env(),
+            mock_info(LOCKUP_ADDR, &[]),
+            ExecuteMsg::ReceiveClaim {
+                owner: DELEGATOR_ADDR.to_string(),
+                amount: Uint128::new(1000),
+                validator: VALIDATOR.to_string(),
+            },
+        )
+    };
+    let unstake = |deps: DepsMut| {
+        execute(
+            deps,
+            mock_env(),
+            mock_info(DELEGATOR_ADDR, &[]),
+            ExecuteMsg::Unstake {
+                amount: Uint128::new(100),
+                validator: VALIDATOR.to_string(),
+            },
+        )
+    };
+
+    // Only guardian or admin can pause
+    let err = pause(deps.as_mut(), DELEGATOR_ADDR, vec![Operation::Staking]).unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized);
+
+    pause(deps.as_mut(), guardian, vec![Operation::Staking]).unwrap();
+    let paused = query_paused(deps.as_ref()).unwrap();
+    assert_eq!(paused.guardian, Some(guardian.to_string()));
+    assert_eq!(paused.paused, vec![Operation::Staking]);
+
+    // Staking is paused, but unstaking still works
+    let err = receive_claim(deps.as_mut()).unwrap_err();
+    assert_eq!(err, ContractError::Paused(Operation::Staking));
+    unstake(deps.as_mut()).unwrap();
+
+    // Admin can pause as well
+    pause(
+        deps.as_mut(),
+        CREATOR_ADDR,
+        vec![Operation::Unstaking, Operation::Rewards],
+    )
+    .unwrap();
+    let err = unstake(deps.as_mut()).unwrap_err();
+    assert_eq!(err, ContractError::Paused(Operation::Unstaking));
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(DELEGATOR_ADDR, &[]),
+        ExecuteMsg::ClaimRewards {
+            validator: VALIDATOR.to_string(),
+        },
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::Paused(Operation::Rewards));
+
+    // Unpause only what was asked
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(guardian, &[]),
+        ExecuteMsg::Unpause {
+            operations: vec![Operation::Staking, Operation::Unstaking],
+        },
+    )
+    .unwrap();
+    let paused = query_paused(deps.as_ref()).unwrap();
+    assert_eq!(paused.paused, vec![Operation::Rewards]);
+    receive_claim(deps.as_mut()).unwrap();
+    unstake(deps.as_mut()).unwrap();
+}
+
+#[test]
+fn test_pause_ibc_receive() {
+    let (mut deps, _) = setup_unit_with_channel(None, CHANNEL_ID);
+
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(CREATOR_ADDR, &[]),
+        ExecuteMsg::Pause {
+            operations: vec![Operation::IbcReceive],
+        },
+    )
+    .unwrap();
+
+    // We return an error ack, instead of failing the tx
+    let res = update_validator_unit(deps.as_mut(), vec![VALIDATOR.to_string()], vec![]).unwrap();
+    assert_eq!(
+        res.acknowledgement,
+        StdAck::fail_transient(ContractError::Paused(Operation::IbcReceive).to_string())
+    );
+    let ack: StdAck = from_binary(&res.acknowledgement).unwrap();
+    assert!(ack.is_transient());
+    query_validators_unit(deps.as_ref(), VALIDATOR).unwrap_err();
+}
+
+#[test]
+fn test_stake_caps() {
+    let other_validator = "other_validator";
+    let mut init_msg = get_default_init_msg(1);
+    init_msg.stake_caps = Some(StakeCaps {
+        max_total_stake: Some(Uint128::new(3000)),
+        max_validator_stake: Some(Uint128::new(2000)),
+        max_validator_share: Some(Decimal::percent(60)),
+        share_cap_min_total: Uint128::new(1000),
+    });
+    let (mut deps, _) = setup_unit_with_channel(Some(init_msg), CHANNEL_ID);
+    update_validator_unit(
+        deps.as_mut(),
+        vec![VALIDATOR.to_string(), other_validator.to_string()],
+        vec![],
+    )
+    .unwrap();
+
+    let receive_claim = |deps: DepsMut, validator: &str, amount: u128| {
+        execute(
+            deps,
+            mock_env(),
+            mock_info(LOCKUP_ADDR, &[]),
+            ExecuteMsg::ReceiveClaim {
+                owner: DELEGATOR_ADDR.to_string(),
+                amount: Uint128::new(amount),
+                validator: validator.to_string(),
+            },
+        )
+    };
+
+    // Below the share cap min total, we can stake all to one validator
+    receive_claim(deps.as_mut(), VALIDATOR, 1000).unwrap();
+
+    // Pending stake counts for the caps, 2000/2000 would be over 60% share
+    let err = receive_claim(deps.as_mut(), VALIDATOR, 1000).unwrap_err();
+    assert!(matches!(err, ContractError::StakeCapExceeded(_)));
+
+    // Ack the first stake, and add to the other validator
+    add_stake_unit(deps.as_mut(), DELEGATOR_ADDR, VALIDATOR, Uint128::new(1000)).unwrap();
+    receive_claim(deps.as_mut(), other_validator, 1000).unwrap();
+    add_stake_unit(
+        deps.as_mut(),
+        DELEGATOR_ADDR,
+        other_validator,
+        Uint128::new(1000),
+    )
+    .unwrap();
+
+    // 1500 of 2500 is exactly 60%
+    receive_claim(deps.as_mut(), VALIDATOR, 500).unwrap();
+
+    // Total cap of 3000, we have 2500
+    let err = receive_claim(deps.as_mut(), other_validator, 501).unwrap_err();
+    assert!(matches!(err, ContractError::StakeCapExceeded(_)));
+
+    let utilization = query_utilization(deps.as_ref(), None, None).unwrap();
+    assert_eq!(utilization.total_stake, Uint128::new(2500));
+    assert_eq!(
+        utilization.validators,
+        vec![
+            ValidatorUtilization {
+                address: other_validator.to_string(),
+                stake: Uint128::new(1000),
+                share: Decimal::percent(40),
+            },
+            ValidatorUtilization {
+                address: VALIDATOR.to_string(),
+                stake: Uint128::new(1500),
+                share: Decimal::percent(60),
+            }
+        ]
+    );
+
+    // A failed stake no longer counts
+    add_stake_fail_unit(deps.as_mut(), DELEGATOR_ADDR, VALIDATOR, Uint128::new(500)).unwrap();
+    let utilization = query_utilization(deps.as_ref(), None, None).unwrap();
+    assert_eq!(utilization.total_stake, Uint128::new(2000));
+}
+
+#[test]
+fn test_invalid_stake_caps() {
+    let (mut deps, _) = setup_unit(None);
+
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(CREATOR_ADDR, &[]),
+        ExecuteMsg::UpdateConfig(ConfigUpdate {
+            stake_caps: Some(StakeCaps {
+                max_validator_share: Some(Decimal::percent(101)),
+                ..StakeCaps::default()
+            }),
+            ..ConfigUpdate::default()
+        }),
+    )
+    .unwrap_err();
+    assert!(matches!(err, ContractError::InvalidConfig(_)));
+}
+
+#[test]
+fn test_exit_stake() {
+    let (mut deps, _) = setup_unit_with_channel(None, CHANNEL_ID);
+    update_validator_unit(deps.as_mut(), vec![VALIDATOR.to_string()], vec![]).unwrap();
+    add_stake_unit(deps.as_mut(), DELEGATOR_ADDR, VALIDATOR, Uint128::new(1000)).unwrap();
+
+    // Not possible while the channel is open
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(DELEGATOR_ADDR, &[]),
+        ExecuteMsg::ExitStake {},
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::ChannelOpen);
+
+    // Not possible during the recovery period
+    ibc_close_channel(deps.as_mut()).unwrap();
+    let recovery_period = query_config(deps.as_ref()).unwrap().channel_recovery_period;
+    let exit_at = mock_env().block.time.plus_seconds(recovery_period);
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(DELEGATOR_ADDR, &[]),
+        ExecuteMsg::ExitStake {},
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::ChannelRecovery(exit_at));
+
+    // After the recovery period, the stake is released on lockup
+    let mut env = mock_env();
+    env.block.time = exit_at;
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(DELEGATOR_ADDR, &[]),
+        ExecuteMsg::ExitStake {},
+    )
+    .unwrap();
+    assert_eq!(
+        res.messages[0].msg,
+        WasmMsg::Execute {
+            contract_addr: LOCKUP_ADDR.to_string(),
+            msg: to_binary(&ClaimProviderMsg::ReleaseClaim {
+                owner: DELEGATOR_ADDR.to_string(),
+                amount: Uint128::new(1000),
+            })
+            .unwrap(),
+            funds: vec![],
+        }
+        .into()
+    );
+    let validator = query_validators_unit(deps.as_ref(), VALIDATOR).unwrap();
+    assert_eq!(validator.tokens, Uint128::zero());
+
+    // Nothing left to exit
+    let err = execute(
+        deps.as_mut(),
+        env,
+        mock_info(DELEGATOR_ADDR, &[]),
+        ExecuteMsg::ExitStake {},
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::InsufficientStake);
+}
+
+#[test]
+fn test_correct_state_drift() {
+    let (mut deps, _) = setup_unit_with_channel(None, CHANNEL_ID);
+    update_validator_unit(deps.as_mut(), vec![VALIDATOR.to_string()], vec![]).unwrap();
+    add_stake_unit(deps.as_mut(), DELEGATOR_ADDR, VALIDATOR, Uint128::new(1000)).unwrap();
+
+    let correct = |deps: DepsMut, sender: &str| {
+        execute(
+            deps,
+            mock_env(),
+            mock_info(sender, &[]),
+            ExecuteMsg::CorrectStateDrift {
+                validator: VALIDATOR.to_string(),
+            },
+        )
+    };
+
+    // Nothing to correct before a sync
+    let err = correct(deps.as_mut(), CREATOR_ADDR).unwrap_err();
+    assert_eq!(err, ContractError::NoStateDrift(VALIDATOR.to_string()));
+
+    // The consumer only holds 800 of our 1000
+    sync_state_unit(
+        deps.as_mut(),
+        vec![ValidatorStake {
+            validator: VALIDATOR.to_string(),
+            stake: Uint128::new(800),
+        }],
+    )
+    .unwrap();
+
+    // Only the admin can correct
+    let err = correct(deps.as_mut(), DELEGATOR_ADDR).unwrap_err();
+    assert_eq!(err, ContractError::Admin(AdminError::NotAdmin {}));
+
+    correct(deps.as_mut(), CREATOR_ADDR).unwrap();
+    let validator = query_validators_unit(deps.as_ref(), VALIDATOR).unwrap();
+    assert_eq!(validator.tokens, Uint128::new(800));
+    assert_eq!(validator.multiplier, Decimal::percent(80));
+
+    // The difference shows up as slashed for the staker
+    let account = query_account_unit(deps.as_ref(), DELEGATOR_ADDR).unwrap();
+    assert_eq!(account.staked[0].tokens, Uint128::new(800));
+    assert_eq!(account.staked[0].slashed, Uint128::new(200));
+    let slashes = query_account_slashes(deps.as_ref(), DELEGATOR_ADDR.to_string()).unwrap();
+    assert_eq!(slashes.slashes.len(), 1);
+    assert_eq!(slashes.slashes[0].amount, Uint128::new(200));
+    let history = query_slashing_history(deps.as_ref(), VALIDATOR.to_string(), None, None).unwrap();
+    assert_eq!(history.slashes[0].percentage, Decimal::percent(20));
+    assert_eq!(
+        history.slashes[0].evidence,
+        Some(STATE_DRIFT_EVIDENCE.to_string())
+    );
+
+    // The drift is resolved
+    let err = correct(deps.as_mut(), CREATOR_ADDR).unwrap_err();
+    assert_eq!(err, ContractError::NoStateDrift(VALIDATOR.to_string()));
+
+    // We can't correct upwards
+    sync_state_unit(
+        deps.as_mut(),
+        vec![ValidatorStake {
+            validator: VALIDATOR.to_string(),
+            stake: Uint128::new(900),
+        }],
+    )
+    .unwrap();
+    let err = correct(deps.as_mut(), CREATOR_ADDR).unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::ConsumerStakeHigher(VALIDATOR.to_string())
+    );
+
+    // Losing all stake tombstones the validator, so nobody can stake on it anymore
+    sync_state_unit(
+        deps.as_mut(),
+        vec![ValidatorStake {
+            validator: VALIDATOR.to_string(),
+            stake: Uint128::zero(),
+        }],
+    )
+    .unwrap();
+    correct(deps.as_mut(), CREATOR_ADDR).unwrap();
+    let validator = query_validators_unit(deps.as_ref(), VALIDATOR).unwrap();
+    assert_eq!(validator.status, ValStatus::Tombstoned);
+    assert_eq!(validator.multiplier, Decimal::zero());
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(LOCKUP_ADDR, &[]),
+        ExecuteMsg::ReceiveClaim {
+            owner: DELEGATOR_ADDR.to_string(),
+            amount: Uint128::new(100),
+            validator: VALIDATOR.to_string(),
+        },
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::RemovedValidator(VALIDATOR.to_string()));
+
+    // Stake that was in flight is released instead
+    let res = add_stake_unit(deps.as_mut(), "other", VALIDATOR, Uint128::new(100)).unwrap();
+    assert_eq!(
+        res.messages[0].msg,
+        WasmMsg::Execute {
+            contract_addr: LOCKUP_ADDR.to_string(),
+            msg: to_binary(&ClaimProviderMsg::ReleaseClaim {
+                owner: "other".to_string(),
+                amount: Uint128::new(100),
+            })
+            .unwrap(),
+            funds: vec![],
+        }
+        .into()
+    );
+}
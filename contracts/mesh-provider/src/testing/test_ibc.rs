@@ -1,22 +1,36 @@
 use cosmwasm_std::{
-    coin, testing::mock_env, to_binary, Addr, IbcChannelCloseMsg, IbcPacketReceiveMsg,
-    IbcPacketTimeoutMsg, Uint128, WasmMsg,
+    coin,
+    testing::{mock_env, mock_info},
+    to_binary, Addr, Decimal, DepsMut, IbcAcknowledgement, IbcChannelCloseMsg, IbcMsg,
+    IbcPacketAckMsg, IbcPacketReceiveMsg, IbcPacketTimeoutMsg, Uint128, WasmMsg,
 };
 use mesh_apis::ClaimProviderMsg;
 use mesh_ibc::{
-    ConsumerMsg, ProviderMsg, RewardsResponse, UpdateValidatorsResponse, IBC_APP_VERSION,
+    ConsumerMsg, ProviderMsg, RewardsEntry, RewardsResponse, SlashResponse, StdAck,
+    UpdateValidatorsResponse, ValidatorMetadata, ValidatorStake, IBC_APP_VERSION,
 };
 use mesh_testing::{
     addr,
     constants::{
         CHANNEL_ID, DELEGATOR_ADDR, LOCKUP_ADDR, RELAYER_ADDR, REWARDS_IBC_DENOM, VALIDATOR,
     },
-    ibc_helpers::{ack_unwrap, mock_channel, mock_packet},
+    ibc_helpers::{ack_unwrap, mock_channel, mock_packet, to_ack_success},
 };
 
 use crate::{
-    ibc::{ibc_channel_close, ibc_packet_receive, ibc_packet_timeout},
-    state::{ValStatus, LIST_VALIDATORS_MAX_RETRIES, LIST_VALIDATORS_RETRIES},
+    contract::{
+        execute, query_channel, query_config, query_retries, query_slashing_history,
+        query_state_drift,
+    },
+    ibc::{
+        build_timeout, ibc_channel_close, ibc_packet_ack, ibc_packet_receive, ibc_packet_timeout,
+        retry_packet,
+    },
+    msg::{ExecuteMsg, RetryInfo, RetryPolicy, ValidatorDrift},
+    state::{
+        RetryStatus, ValStatus, EXITED_STAKE, LIST_VALIDATORS_MAX_RETRIES, LIST_VALIDATORS_RETRIES,
+        VALIDATORS,
+    },
     testing::utils::ibc_helpers::{
         add_stake_unit, get_default_init_msg, ibc_connect, ibc_open, ibc_open_channel,
         query_validators_unit, update_validator_unit,
@@ -26,8 +40,9 @@ use crate::{
 
 use super::utils::{
     ibc_helpers::{
-        add_stake_fail_unit, ibc_close_channel, list_validators_fail_unit, list_validators_unit,
-        remove_stake_fail_unit,
+        add_stake_fail_unit, ibc_close_channel, list_validators_epoch_unit,
+        list_validators_fail_unit, list_validators_unit, remove_stake_fail_unit, remove_stake_unit,
+        sync_state_unit, update_validator_epoch_unit,
     },
     setup_unit::{setup_unit, setup_unit_with_channel},
 };
@@ -235,13 +250,266 @@ fn test_recieve_rewards_failing() {
     assert_eq!(err, ContractError::NoStakedTokens(VALIDATOR.to_string()));
 }
 
+#[test]
+fn test_receive_batch_rewards() {
+    let setup = || {
+        let (mut deps, _) = setup_unit_with_channel(None, CHANNEL_ID);
+        let validators = vec![VALIDATOR.to_string(), "validator2".to_string()];
+        update_validator_unit(deps.as_mut(), validators, vec![]).unwrap();
+        add_stake_unit(deps.as_mut(), DELEGATOR_ADDR, VALIDATOR, Uint128::new(1000)).unwrap();
+        add_stake_unit(
+            deps.as_mut(),
+            DELEGATOR_ADDR,
+            "validator2",
+            Uint128::new(500),
+        )
+        .unwrap();
+        deps
+    };
+    let receive = |deps: DepsMut, msg: ConsumerMsg| {
+        ibc_packet_receive(
+            deps,
+            mock_env(),
+            IbcPacketReceiveMsg::new(mock_packet(to_binary(&msg).unwrap()), addr!(RELAYER_ADDR)),
+        )
+    };
+
+    // A batch has the same effect as one rewards packet per validator
+    let mut batched = setup();
+    let res = receive(
+        batched.as_mut(),
+        ConsumerMsg::BatchRewards {
+            entries: vec![
+                RewardsEntry {
+                    validator: VALIDATOR.to_string(),
+                    amount: coin(100, REWARDS_IBC_DENOM),
+                },
+                RewardsEntry {
+                    validator: "validator2".to_string(),
+                    amount: coin(30, REWARDS_IBC_DENOM),
+                },
+            ],
+        },
+    )
+    .unwrap();
+    ack_unwrap::<RewardsResponse>(res.acknowledgement);
+
+    let mut single = setup();
+    for (validator, amount) in [(VALIDATOR, 100), ("validator2", 30)] {
+        let msg = ConsumerMsg::Rewards {
+            validator: validator.to_string(),
+            total_funds: coin(amount, REWARDS_IBC_DENOM),
+        };
+        receive(single.as_mut(), msg).unwrap();
+    }
+    for validator in [VALIDATOR, "validator2"] {
+        assert_eq!(
+            VALIDATORS.load(&batched.storage, validator).unwrap(),
+            VALIDATORS.load(&single.storage, validator).unwrap()
+        );
+    }
+
+    // One unknown validator fails the whole batch with an error ack, nothing is added
+    let msg = ConsumerMsg::BatchRewards {
+        entries: vec![
+            RewardsEntry {
+                validator: VALIDATOR.to_string(),
+                amount: coin(100, REWARDS_IBC_DENOM),
+            },
+            RewardsEntry {
+                validator: "some_validator".to_string(),
+                amount: coin(100, REWARDS_IBC_DENOM),
+            },
+        ],
+    };
+    let res = receive(batched.as_mut(), msg).unwrap();
+    assert_eq!(
+        res.acknowledgement,
+        StdAck::fail(ContractError::UnknownValidator("some_validator".to_string()).to_string())
+    );
+    assert_eq!(
+        VALIDATORS.load(&batched.storage, VALIDATOR).unwrap(),
+        VALIDATORS.load(&single.storage, VALIDATOR).unwrap()
+    );
+}
+
+#[test]
+fn test_receive_slash() {
+    let (mut deps, _) = setup_unit_with_channel(None, CHANNEL_ID);
+    update_validator_unit(deps.as_mut(), vec![VALIDATOR.to_string()], vec![]).unwrap();
+    add_stake_unit(deps.as_mut(), DELEGATOR_ADDR, VALIDATOR, Uint128::new(1000)).unwrap();
+
+    let receive_slash = |deps: DepsMut, validator: &str, percentage: u64, tombstone: bool| {
+        let packet = mock_packet(
+            to_binary(&ConsumerMsg::Slash {
+                validator: validator.to_string(),
+                percentage: Decimal::percent(percentage),
+                infraction_height: 42,
+                tombstone,
+            })
+            .unwrap(),
+        );
+        ibc_packet_receive(
+            deps,
+            mock_env(),
+            IbcPacketReceiveMsg::new(packet, addr!(RELAYER_ADDR)),
+        )
+    };
+
+    let res = receive_slash(deps.as_mut(), VALIDATOR, 10, false).unwrap();
+    ack_unwrap::<SlashResponse>(res.acknowledgement);
+    let validator = query_validators_unit(deps.as_ref(), VALIDATOR).unwrap();
+    assert_eq!(validator.status, ValStatus::Active);
+    assert_eq!(validator.multiplier, Decimal::percent(90));
+
+    receive_slash(deps.as_mut(), VALIDATOR, 50, true).unwrap();
+    let validator = query_validators_unit(deps.as_ref(), VALIDATOR).unwrap();
+    assert_eq!(validator.status, ValStatus::Tombstoned);
+    assert_eq!(validator.multiplier, Decimal::percent(45));
+
+    let history = query_slashing_history(deps.as_ref(), VALIDATOR.to_string(), None, None)
+        .unwrap()
+        .slashes;
+    assert_eq!(history.len(), 2);
+    assert_eq!(
+        history[0].evidence,
+        Some("consumer infraction at height 42".to_string())
+    );
+    assert!(history[1].tombstoned);
+
+    // Bad slashes get an error ack
+    let res = receive_slash(deps.as_mut(), "some_validator", 10, false).unwrap();
+    assert_eq!(
+        res.acknowledgement,
+        StdAck::fail(ContractError::UnknownValidator("some_validator".to_string()).to_string())
+    );
+    let res = receive_slash(deps.as_mut(), VALIDATOR, 150, false).unwrap();
+    assert_eq!(
+        res.acknowledgement,
+        StdAck::fail(ContractError::InvalidSlashPercentage(Decimal::percent(150)).to_string())
+    );
+    let validator = query_validators_unit(deps.as_ref(), VALIDATOR).unwrap();
+    assert_eq!(validator.multiplier, Decimal::percent(45));
+}
+
 #[test]
 fn test_list_validators() {
     let (mut deps, _) = setup_unit_with_channel(None, CHANNEL_ID);
 
     list_validators_unit(deps.as_mut()).unwrap();
     let validator = query_validators_unit(deps.as_ref(), VALIDATOR).unwrap();
-    assert_eq!(validator.status, ValStatus::Active)
+    assert_eq!(validator.status, ValStatus::Active);
+    let metadata = validator.metadata.unwrap();
+    assert_eq!(metadata.commission, Decimal::percent(5));
+    assert!(!metadata.jailed);
+
+    // Removing the validator marks it as jailed
+    update_validator_unit(deps.as_mut(), vec![], vec![VALIDATOR.to_string()]).unwrap();
+    let validator = query_validators_unit(deps.as_ref(), VALIDATOR).unwrap();
+    assert_eq!(validator.status, ValStatus::Removed);
+    assert!(validator.metadata.unwrap().jailed);
+
+    // Adding it back makes it active with the metadata of the update
+    let packet = mock_packet(
+        to_binary(&ConsumerMsg::UpdateValidators {
+            added: vec![VALIDATOR.to_string()],
+            removed: vec![],
+            epoch: 1,
+            metadata: vec![ValidatorMetadata {
+                address: VALIDATOR.to_string(),
+                commission: Decimal::percent(10),
+                max_commission: Decimal::percent(20),
+                max_change_rate: Decimal::percent(1),
+                jailed: false,
+            }],
+        })
+        .unwrap(),
+    );
+    ibc_packet_receive(
+        deps.as_mut(),
+        mock_env(),
+        IbcPacketReceiveMsg::new(packet, addr!(RELAYER_ADDR)),
+    )
+    .unwrap();
+    let validator = query_validators_unit(deps.as_ref(), VALIDATOR).unwrap();
+    assert_eq!(validator.status, ValStatus::Active);
+    let metadata = validator.metadata.unwrap();
+    assert_eq!(metadata.commission, Decimal::percent(10));
+    assert!(!metadata.jailed);
+}
+
+#[test]
+fn test_valset_epochs() {
+    let (mut deps, _) = setup_unit_with_channel(None, CHANNEL_ID);
+    let other = "other_validator";
+
+    list_validators_epoch_unit(deps.as_mut(), vec![VALIDATOR.to_string()], 2).unwrap();
+    add_stake_unit(deps.as_mut(), DELEGATOR_ADDR, VALIDATOR, Uint128::new(1000)).unwrap();
+
+    // A late list from an older epoch is ignored
+    list_validators_epoch_unit(deps.as_mut(), vec![other.to_string()], 1).unwrap();
+    let validator = query_validators_unit(deps.as_ref(), VALIDATOR).unwrap();
+    assert_eq!(validator.status, ValStatus::Active);
+    query_validators_unit(deps.as_ref(), other).unwrap_err();
+
+    // So is an older update
+    update_validator_epoch_unit(
+        deps.as_mut(),
+        vec![other.to_string()],
+        vec![VALIDATOR.to_string()],
+        1,
+    )
+    .unwrap();
+    let validator = query_validators_unit(deps.as_ref(), VALIDATOR).unwrap();
+    assert_eq!(validator.status, ValStatus::Active);
+    query_validators_unit(deps.as_ref(), other).unwrap_err();
+
+    // A newer update is applied
+    update_validator_epoch_unit(deps.as_mut(), vec![other.to_string()], vec![], 3).unwrap();
+    query_validators_unit(deps.as_ref(), other).unwrap();
+
+    // Relisting the same epoch keeps the existing stake
+    list_validators_epoch_unit(
+        deps.as_mut(),
+        vec![VALIDATOR.to_string(), other.to_string()],
+        3,
+    )
+    .unwrap();
+    let validator = query_validators_unit(deps.as_ref(), VALIDATOR).unwrap();
+    assert_eq!(validator.tokens, Uint128::new(1000));
+}
+
+#[test]
+fn test_refresh_validators() {
+    let (mut deps, _) = setup_unit_with_channel(None, CHANNEL_ID);
+
+    list_validators_fail_unit(deps.as_mut()).unwrap();
+    assert_eq!(
+        LIST_VALIDATORS_RETRIES.load(deps.as_ref().storage).unwrap(),
+        LIST_VALIDATORS_MAX_RETRIES - 1
+    );
+
+    // Anyone can ask for a refresh
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("anyone", &[]),
+        ExecuteMsg::RefreshValidators {},
+    )
+    .unwrap();
+    assert_eq!(
+        res.messages[0].msg,
+        IbcMsg::SendPacket {
+            channel_id: CHANNEL_ID.to_string(),
+            data: to_binary(&ProviderMsg::ListValidators {}).unwrap(),
+            timeout: build_timeout(deps.as_ref(), &mock_env()).unwrap(),
+        }
+        .into()
+    );
+    assert_eq!(
+        LIST_VALIDATORS_RETRIES.load(deps.as_ref().storage).unwrap(),
+        LIST_VALIDATORS_MAX_RETRIES
+    );
 }
 
 #[test]
@@ -280,7 +548,7 @@ fn test_add_stake_fail() {
         res.messages[0].msg,
         WasmMsg::Execute {
             contract_addr: LOCKUP_ADDR.to_string(),
-            msg: to_binary(&ClaimProviderMsg::SlashClaim {
+            msg: to_binary(&ClaimProviderMsg::ReleaseClaim {
                 owner: DELEGATOR_ADDR.to_string(),
                 amount: Uint128::new(1000)
             })
@@ -300,3 +568,467 @@ fn test_remove_stake_fail() {
 
     assert_eq!(res.events[0].ty, "failed_unstake")
 }
+
+#[test]
+fn test_channel_recovery() {
+    let (mut deps, _) = setup_unit_with_channel(None, CHANNEL_ID);
+    let other_validator = "other_validator";
+
+    update_validator_unit(
+        deps.as_mut(),
+        vec![VALIDATOR.to_string(), other_validator.to_string()],
+        vec![],
+    )
+    .unwrap();
+    add_stake_unit(deps.as_mut(), DELEGATOR_ADDR, VALIDATOR, Uint128::new(1000)).unwrap();
+
+    ibc_close_channel(deps.as_mut()).unwrap();
+    let channel = query_channel(deps.as_ref()).unwrap();
+    assert_eq!(channel.channel, None);
+    assert_eq!(channel.closed_at, Some(mock_env().block.time));
+
+    // A timed out list validators has no channel to retry on
+    let res = list_validators_fail_unit(deps.as_mut()).unwrap();
+    assert_eq!(res.messages.len(), 0);
+
+    // A new channel on the same connection is accepted, lists validators and syncs state again
+    let new_channel = mock_channel("channel-2", IBC_APP_VERSION);
+    ibc_open(deps.as_mut(), new_channel.clone()).unwrap();
+    let res = ibc_connect(deps.as_mut(), new_channel).unwrap();
+    assert_eq!(res.events[0].ty, "channel_recovered");
+    assert_eq!(res.messages.len(), 2);
+
+    let channel = query_channel(deps.as_ref()).unwrap();
+    assert_eq!(channel.channel, Some("channel-2".to_string()));
+    assert_eq!(channel.closed_at, None);
+
+    // The consumer only lists our validator, the stake on it is kept
+    list_validators_unit(deps.as_mut()).unwrap();
+    let validator = query_validators_unit(deps.as_ref(), VALIDATOR).unwrap();
+    assert_eq!(validator.status, ValStatus::Active);
+    assert_eq!(validator.tokens, Uint128::new(1000));
+    let validator = query_validators_unit(deps.as_ref(), other_validator).unwrap();
+    assert_eq!(validator.status, ValStatus::Removed);
+}
+
+#[test]
+fn test_sync_state() {
+    let (mut deps, _) = setup_unit_with_channel(None, CHANNEL_ID);
+    let other_validator = "other_validator";
+    let unknown_validator = "unknown_validator";
+
+    update_validator_unit(
+        deps.as_mut(),
+        vec![VALIDATOR.to_string(), other_validator.to_string()],
+        vec![],
+    )
+    .unwrap();
+    add_stake_unit(deps.as_mut(), DELEGATOR_ADDR, VALIDATOR, Uint128::new(1000)).unwrap();
+    add_stake_unit(
+        deps.as_mut(),
+        DELEGATOR_ADDR,
+        other_validator,
+        Uint128::new(500),
+    )
+    .unwrap();
+
+    // The consumer holds less on our validator, and some stake on a validator we don't know
+    let res = sync_state_unit(
+        deps.as_mut(),
+        vec![
+            ValidatorStake {
+                validator: other_validator.to_string(),
+                stake: Uint128::new(500),
+            },
+            ValidatorStake {
+                validator: unknown_validator.to_string(),
+                stake: Uint128::new(10),
+            },
+            ValidatorStake {
+                validator: VALIDATOR.to_string(),
+                stake: Uint128::new(800),
+            },
+        ],
+    )
+    .unwrap();
+    assert_eq!(res.events.len(), 2);
+    assert_eq!(res.events[0].ty, "state_drift");
+
+    let drift = query_state_drift(deps.as_ref(), None, None).unwrap();
+    assert_eq!(drift.last_sync, Some(mock_env().block.time));
+    assert_eq!(
+        drift.validators,
+        vec![
+            ValidatorDrift {
+                address: unknown_validator.to_string(),
+                provider_stake: Uint128::zero(),
+                consumer_stake: Uint128::new(10),
+            },
+            ValidatorDrift {
+                address: VALIDATOR.to_string(),
+                provider_stake: Uint128::new(1000),
+                consumer_stake: Uint128::new(800),
+            },
+        ]
+    );
+
+    // A sync without drift clears the old records
+    sync_state_unit(
+        deps.as_mut(),
+        vec![
+            ValidatorStake {
+                validator: VALIDATOR.to_string(),
+                stake: Uint128::new(1000),
+            },
+            ValidatorStake {
+                validator: other_validator.to_string(),
+                stake: Uint128::new(500),
+            },
+        ],
+    )
+    .unwrap();
+    let drift = query_state_drift(deps.as_ref(), None, None).unwrap();
+    assert_eq!(drift.validators, vec![]);
+
+    // Our slashes never reach the consumer stake, so they are no drift
+    let packet = mock_packet(
+        to_binary(&ConsumerMsg::Slash {
+            validator: VALIDATOR.to_string(),
+            percentage: Decimal::percent(10),
+            infraction_height: 1,
+            tombstone: false,
+        })
+        .unwrap(),
+    );
+    ibc_packet_receive(
+        deps.as_mut(),
+        mock_env(),
+        IbcPacketReceiveMsg::new(packet, addr!(RELAYER_ADDR)),
+    )
+    .unwrap();
+    let res = sync_state_unit(
+        deps.as_mut(),
+        vec![
+            ValidatorStake {
+                validator: VALIDATOR.to_string(),
+                stake: Uint128::new(1000),
+            },
+            ValidatorStake {
+                validator: other_validator.to_string(),
+                stake: Uint128::new(500),
+            },
+        ],
+    )
+    .unwrap();
+    assert_eq!(res.events.len(), 0);
+
+    // Neither are unstakes the consumer acked
+    remove_stake_unit(deps.as_mut(), DELEGATOR_ADDR, VALIDATOR, Uint128::new(900)).unwrap();
+    let res = sync_state_unit(
+        deps.as_mut(),
+        vec![
+            ValidatorStake {
+                validator: VALIDATOR.to_string(),
+                stake: Uint128::new(100),
+            },
+            ValidatorStake {
+                validator: other_validator.to_string(),
+                stake: Uint128::new(500),
+            },
+        ],
+    )
+    .unwrap();
+    assert_eq!(res.events.len(), 0);
+}
+
+#[test]
+fn test_retry_pending() {
+    let mut init_msg = get_default_init_msg(1);
+    init_msg.retry_policy = Some(RetryPolicy {
+        max_attempts: 1,
+        backoff_blocks: 5,
+    });
+    let (mut deps, _) = setup_unit_with_channel(Some(init_msg), CHANNEL_ID);
+    update_validator_unit(deps.as_mut(), vec![VALIDATOR.to_string()], vec![]).unwrap();
+
+    let stake = ProviderMsg::Stake {
+        validator: VALIDATOR.to_string(),
+        amount: Uint128::new(1000),
+        key: DELEGATOR_ADDR.to_string(),
+    };
+    let timeout = |deps: DepsMut, packet: &ProviderMsg| {
+        let packet = mock_packet(to_binary(packet).unwrap());
+        ibc_packet_timeout(
+            deps,
+            mock_env(),
+            IbcPacketTimeoutMsg::new(packet, addr!(RELAYER_ADDR)),
+        )
+    };
+    let retry_pending = |deps: DepsMut, height: u64| {
+        let mut env = mock_env();
+        env.block.height = height;
+        execute(
+            deps,
+            env,
+            mock_info(RELAYER_ADDR, &[]),
+            ExecuteMsg::RetryPending { key: 1 },
+        )
+    };
+
+    // The timeout is recorded instead of releasing the stake
+    let res = timeout(deps.as_mut(), &stake).unwrap();
+    assert_eq!(res.messages.len(), 0);
+    assert_eq!(res.events[0].ty, "retry_scheduled");
+
+    let retry_after = mock_env().block.height + 5;
+    let retries = query_retries(deps.as_ref(), DELEGATOR_ADDR.to_string(), None, None).unwrap();
+    assert_eq!(
+        retries.retries,
+        vec![RetryInfo {
+            key: 1,
+            packet: stake.clone(),
+            attempts: 0,
+            retry_after,
+            status: RetryStatus::Pending,
+        }]
+    );
+
+    // Only after the backoff
+    let err = retry_pending(deps.as_mut(), retry_after - 1).unwrap_err();
+    assert_eq!(err, ContractError::RetryBackoff(retry_after));
+
+    let res = retry_pending(deps.as_mut(), retry_after).unwrap();
+    let resent = retry_packet(stake.clone(), 1);
+    assert_eq!(
+        res.messages[0].msg,
+        IbcMsg::SendPacket {
+            channel_id: CHANNEL_ID.to_string(),
+            data: to_binary(&resent).unwrap(),
+            timeout: build_timeout(deps.as_ref(), &mock_env()).unwrap(),
+        }
+        .into()
+    );
+    let err = retry_pending(deps.as_mut(), retry_after).unwrap_err();
+    assert_eq!(err, ContractError::RetryNotPending(1));
+
+    // No attempts left, so the stake is released this time
+    let res = timeout(deps.as_mut(), &resent).unwrap();
+    assert_eq!(
+        res.messages[0].msg,
+        WasmMsg::Execute {
+            contract_addr: LOCKUP_ADDR.to_string(),
+            msg: to_binary(&ClaimProviderMsg::ReleaseClaim {
+                owner: DELEGATOR_ADDR.to_string(),
+                amount: Uint128::new(1000)
+            })
+            .unwrap(),
+            funds: vec![]
+        }
+        .into()
+    );
+    let retries = query_retries(deps.as_ref(), DELEGATOR_ADDR.to_string(), None, None).unwrap();
+    assert_eq!(retries.retries[0].attempts, 1);
+    assert_eq!(retries.retries[0].status, RetryStatus::Failed);
+
+    // A resent packet that is acked succeeds
+    let unstake = ProviderMsg::Unstake {
+        validator: VALIDATOR.to_string(),
+        amount: Uint128::new(1000),
+        key: DELEGATOR_ADDR.to_string(),
+    };
+    timeout(deps.as_mut(), &unstake).unwrap();
+    add_stake_unit(deps.as_mut(), DELEGATOR_ADDR, VALIDATOR, Uint128::new(1000)).unwrap();
+    let packet = mock_packet(to_binary(&retry_packet(unstake, 2)).unwrap());
+    ibc_packet_ack(
+        deps.as_mut(),
+        mock_env(),
+        IbcPacketAckMsg::new(
+            IbcAcknowledgement::new(to_ack_success(())),
+            packet,
+            addr!(RELAYER_ADDR),
+        ),
+    )
+    .unwrap();
+    let retries = query_retries(deps.as_ref(), DELEGATOR_ADDR.to_string(), None, None).unwrap();
+    assert_eq!(retries.retries[1].status, RetryStatus::Succeeded);
+    let validator = query_validators_unit(deps.as_ref(), VALIDATOR).unwrap();
+    assert_eq!(validator.tokens, Uint128::zero());
+}
+
+#[test]
+fn test_retry_pending_closed_channel() {
+    let mut init_msg = get_default_init_msg(1);
+    init_msg.retry_policy = Some(RetryPolicy {
+        max_attempts: 3,
+        backoff_blocks: 0,
+    });
+    let (mut deps, _) = setup_unit_with_channel(Some(init_msg), CHANNEL_ID);
+
+    let packet = mock_packet(
+        to_binary(&ProviderMsg::Stake {
+            validator: VALIDATOR.to_string(),
+            amount: Uint128::new(1000),
+            key: DELEGATOR_ADDR.to_string(),
+        })
+        .unwrap(),
+    );
+    ibc_packet_timeout(
+        deps.as_mut(),
+        mock_env(),
+        IbcPacketTimeoutMsg::new(packet, addr!(RELAYER_ADDR)),
+    )
+    .unwrap();
+    ibc_close_channel(deps.as_mut()).unwrap();
+
+    // The packet can't be delivered anymore, so the stake is released
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(RELAYER_ADDR, &[]),
+        ExecuteMsg::RetryPending { key: 1 },
+    )
+    .unwrap();
+    assert_eq!(res.events[0].ty, "failed_stake");
+    assert_eq!(res.messages.len(), 1);
+    let retries = query_retries(deps.as_ref(), DELEGATOR_ADDR.to_string(), None, None).unwrap();
+    assert_eq!(retries.retries[0].status, RetryStatus::Failed);
+}
+
+#[test]
+fn test_exit_stake_recovery() {
+    let (mut deps, _) = setup_unit_with_channel(None, CHANNEL_ID);
+    update_validator_unit(deps.as_mut(), vec![VALIDATOR.to_string()], vec![]).unwrap();
+    add_stake_unit(deps.as_mut(), DELEGATOR_ADDR, VALIDATOR, Uint128::new(1000)).unwrap();
+
+    ibc_close_channel(deps.as_mut()).unwrap();
+    let recovery_period = query_config(deps.as_ref()).unwrap().channel_recovery_period;
+    let mut env = mock_env();
+    env.block.time = env.block.time.plus_seconds(recovery_period);
+    execute(
+        deps.as_mut(),
+        env,
+        mock_info(DELEGATOR_ADDR, &[]),
+        ExecuteMsg::ExitStake {},
+    )
+    .unwrap();
+
+    // An unstake relayed after the exit is already covered by it
+    let res =
+        remove_stake_unit(deps.as_mut(), DELEGATOR_ADDR, VALIDATOR, Uint128::new(400)).unwrap();
+    assert_eq!(res.events[0].ty, "ack_exit_unstake");
+    assert_eq!(res.messages.len(), 0);
+
+    // The consumer unstakes the rest once a channel connects again
+    let new_channel = mock_channel("channel-2", IBC_APP_VERSION);
+    ibc_open(deps.as_mut(), new_channel.clone()).unwrap();
+    let res = ibc_connect(deps.as_mut(), new_channel).unwrap();
+    let exit_packet = ProviderMsg::Unstake {
+        key: format!("{}/exit", DELEGATOR_ADDR),
+        validator: VALIDATOR.to_string(),
+        amount: Uint128::new(600),
+    };
+    assert_eq!(res.messages.len(), 3);
+    assert_eq!(
+        res.messages[2].msg,
+        IbcMsg::SendPacket {
+            channel_id: "channel-2".to_string(),
+            data: to_binary(&exit_packet).unwrap(),
+            timeout: build_timeout(deps.as_ref(), &mock_env()).unwrap(),
+        }
+        .into()
+    );
+
+    // A timed out exit is not retried, it is sent again on the next recovery
+    let timeout = IbcPacketTimeoutMsg::new(
+        mock_packet(to_binary(&exit_packet).unwrap()),
+        addr!(RELAYER_ADDR),
+    );
+    let res = ibc_packet_timeout(deps.as_mut(), mock_env(), timeout).unwrap();
+    assert_eq!(res.events[0].ty, "failed_exit_unstake");
+    let retries = query_retries(deps.as_ref(), DELEGATOR_ADDR.to_string(), None, None).unwrap();
+    assert_eq!(retries.retries, vec![]);
+
+    // Once acked, nothing is left to exit and the provider stake stays untouched
+    let ack = IbcAcknowledgement::new(to_ack_success(()));
+    let res = ibc_packet_ack(
+        deps.as_mut(),
+        mock_env(),
+        IbcPacketAckMsg::new(
+            ack,
+            mock_packet(to_binary(&exit_packet).unwrap()),
+            addr!(RELAYER_ADDR),
+        ),
+    )
+    .unwrap();
+    assert_eq!(res.events[0].ty, "ack_exit_unstake");
+    let exited = EXITED_STAKE
+        .may_load(&deps.storage, (&addr!(DELEGATOR_ADDR), VALIDATOR))
+        .unwrap();
+    assert_eq!(exited, None);
+    let validator = query_validators_unit(deps.as_ref(), VALIDATOR).unwrap();
+    assert_eq!(validator.tokens, Uint128::zero());
+}
+
+#[test]
+fn test_retry_policy_packet_kinds() {
+    let mut init_msg = get_default_init_msg(1);
+    init_msg.retry_policy = Some(RetryPolicy {
+        max_attempts: 1,
+        backoff_blocks: 5,
+    });
+    let (mut deps, _) = setup_unit_with_channel(Some(init_msg), CHANNEL_ID);
+    update_validator_unit(deps.as_mut(), vec![VALIDATOR.to_string()], vec![]).unwrap();
+
+    let timeout = |deps: DepsMut, packet: &ProviderMsg| {
+        let packet = mock_packet(to_binary(packet).unwrap());
+        ibc_packet_timeout(
+            deps,
+            mock_env(),
+            IbcPacketTimeoutMsg::new(packet, addr!(RELAYER_ADDR)),
+        )
+    };
+
+    // Stake and unstake are kept to be resent
+    let stake = ProviderMsg::Stake {
+        validator: VALIDATOR.to_string(),
+        amount: Uint128::new(1000),
+        key: DELEGATOR_ADDR.to_string(),
+    };
+    let res = timeout(deps.as_mut(), &stake).unwrap();
+    assert_eq!(res.events[0].ty, "retry_scheduled");
+    let unstake = ProviderMsg::Unstake {
+        validator: VALIDATOR.to_string(),
+        amount: Uint128::new(1000),
+        key: DELEGATOR_ADDR.to_string(),
+    };
+    let res = timeout(deps.as_mut(), &unstake).unwrap();
+    assert_eq!(res.events[0].ty, "retry_scheduled");
+    let retries = query_retries(deps.as_ref(), DELEGATOR_ADDR.to_string(), None, None).unwrap();
+    assert_eq!(retries.retries.len(), 2);
+
+    // List validators is resent right away with its own counter
+    let res = timeout(deps.as_mut(), &ProviderMsg::ListValidators {}).unwrap();
+    assert_eq!(res.events[0].ty, "list_validators_retry");
+    assert_eq!(res.messages.len(), 1);
+    assert_eq!(
+        LIST_VALIDATORS_RETRIES.load(&deps.storage).unwrap(),
+        LIST_VALIDATORS_MAX_RETRIES - 1
+    );
+
+    // Sync state fails, the crank sends it again
+    let res = timeout(deps.as_mut(), &ProviderMsg::SyncState {}).unwrap();
+    assert_eq!(res.events[0].ty, "failed_sync_state");
+    assert_eq!(res.messages.len(), 0);
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(RELAYER_ADDR, &[]),
+        ExecuteMsg::SyncState {},
+    )
+    .unwrap();
+    assert_eq!(res.messages.len(), 1);
+
+    // Neither of them is kept as a retry
+    let retries = query_retries(deps.as_ref(), DELEGATOR_ADDR.to_string(), None, None).unwrap();
+    assert_eq!(retries.retries.len(), 2);
+}
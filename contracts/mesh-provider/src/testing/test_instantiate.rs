@@ -25,6 +25,9 @@ fn test_instantiate() {
         }
     );
 
+    assert_eq!(provider_config.admin, Some(CREATOR_ADDR.to_string()));
+    assert_eq!(provider_config.pending_admin, None);
+
     let slasher_config = query_slasher_config(&app, mesh_slasher_addr.as_str()).unwrap();
 
     assert_eq!(
@@ -1,8 +1,33 @@
-use cosmwasm_std::Uint128;
-use mesh_testing::constants::{CHANNEL_ID, DELEGATOR_ADDR, VALIDATOR};
+use cosmwasm_std::{
+    coin, coins,
+    testing::{mock_env, mock_info},
+    to_binary, Addr, BankMsg, ContractResult, Decimal, DepsMut, IbcAcknowledgement,
+    IbcPacketAckMsg, IbcPacketReceiveMsg, StdError, SystemResult, Uint128, WasmMsg, WasmQuery,
+};
+use cw_controllers::Claim;
+use cw_utils::Expiration;
+use mesh_apis::{ClaimProviderMsg, Lein, LockupBalanceResponse};
+use mesh_ibc::{ConsumerMsg, ProviderMsg};
+use mesh_testing::{
+    addr,
+    constants::{
+        CHANNEL_ID, DELEGATOR_ADDR, LOCKUP_ADDR, RELAYER_ADDR, REWARDS_IBC_DENOM, VALIDATOR,
+    },
+    ibc_helpers::{mock_packet, to_ack_success},
+};
+
+use crate::{
+    contract::{
+        execute, query_account_slashes, query_portfolio, query_slashing_history,
+        query_total_power_at_height, query_voting_power_at_height,
+    },
+    ibc::{ibc_packet_ack, ibc_packet_receive},
+    msg::{AccountSlash, ExecuteMsg, Position, SlashInfo},
+    state::{ValStatus, CONFIG},
+};
 
 use super::utils::{
-    ibc_helpers::{add_stake_unit, query_account_unit, update_validator_unit},
+    ibc_helpers::{add_stake_unit, query_account_unit, remove_stake_unit, update_validator_unit},
     setup_unit::setup_unit_with_channel,
 };
 
@@ -18,3 +43,370 @@ fn test_query_account() {
 
     assert_eq!(account.staked[0].tokens, Uint128::new(1000))
 }
+
+#[test]
+fn test_query_voting_power() {
+    let (mut deps, _) = setup_unit_with_channel(None, CHANNEL_ID);
+    let height = mock_env().block.height;
+
+    update_validator_unit(
+        deps.as_mut(),
+        vec![VALIDATOR.to_string(), "other_validator".to_string()],
+        vec![],
+    )
+    .unwrap();
+    add_stake_unit(deps.as_mut(), DELEGATOR_ADDR, VALIDATOR, Uint128::new(1000)).unwrap();
+    add_stake_unit(
+        deps.as_mut(),
+        DELEGATOR_ADDR,
+        "other_validator",
+        Uint128::new(500),
+    )
+    .unwrap();
+    add_stake_unit(
+        deps.as_mut(),
+        "other_delegator",
+        VALIDATOR,
+        Uint128::new(200),
+    )
+    .unwrap();
+
+    // Unstake some a few blocks later
+    let mut env = mock_env();
+    env.block.height = height + 5;
+    let packet = mock_packet(
+        to_binary(&ProviderMsg::Unstake {
+            key: DELEGATOR_ADDR.to_string(),
+            amount: Uint128::new(300),
+            validator: VALIDATOR.to_string(),
+        })
+        .unwrap(),
+    );
+    ibc_packet_ack(
+        deps.as_mut(),
+        env.clone(),
+        IbcPacketAckMsg::new(
+            IbcAcknowledgement::new(to_ack_success(())),
+            packet,
+            addr!(RELAYER_ADDR),
+        ),
+    )
+    .unwrap();
+
+    let voting_power = |height: Option<u64>| {
+        query_voting_power_at_height(
+            deps.as_ref(),
+            env.clone(),
+            DELEGATOR_ADDR.to_string(),
+            height,
+        )
+        .unwrap()
+        .power
+    };
+    let total_power = |height: Option<u64>| {
+        query_total_power_at_height(deps.as_ref(), env.clone(), height)
+            .unwrap()
+            .power
+    };
+
+    // Changes count from the next block on
+    assert_eq!(voting_power(Some(height)), Uint128::zero());
+    assert_eq!(voting_power(Some(height + 1)), Uint128::new(1500));
+    assert_eq!(total_power(Some(height + 1)), Uint128::new(1700));
+    assert_eq!(voting_power(Some(height + 6)), Uint128::new(1200));
+    assert_eq!(total_power(Some(height + 6)), Uint128::new(1400));
+
+    // Defaults to the current height
+    let res =
+        query_voting_power_at_height(deps.as_ref(), env.clone(), DELEGATOR_ADDR.to_string(), None)
+            .unwrap();
+    assert_eq!(res.height, height + 5);
+    assert_eq!(res.power, Uint128::new(1500));
+}
+
+#[test]
+fn test_query_slashes() {
+    let (mut deps, _) = setup_unit_with_channel(None, CHANNEL_ID);
+    CONFIG
+        .update::<_, StdError>(deps.as_mut().storage, |mut cfg| {
+            cfg.slasher = Some(addr!("slasher"));
+            Ok(cfg)
+        })
+        .unwrap();
+
+    update_validator_unit(deps.as_mut(), vec![VALIDATOR.to_string()], vec![]).unwrap();
+    add_stake_unit(deps.as_mut(), DELEGATOR_ADDR, VALIDATOR, Uint128::new(1000)).unwrap();
+
+    let slash = |deps: DepsMut, percentage: u64, force_unbond: bool, evidence: Option<&str>| {
+        execute(
+            deps,
+            mock_env(),
+            mock_info("slasher", &[]),
+            ExecuteMsg::Slash {
+                validator: VALIDATOR.to_string(),
+                percentage: Decimal::percent(percentage),
+                force_unbond,
+                evidence: evidence.map(String::from),
+            },
+        )
+        .unwrap();
+    };
+    slash(deps.as_mut(), 10, false, Some("downtime"));
+    slash(deps.as_mut(), 50, true, None);
+
+    let history = query_slashing_history(deps.as_ref(), VALIDATOR.to_string(), None, None).unwrap();
+    assert_eq!(
+        history.slashes,
+        vec![
+            SlashInfo {
+                id: 1,
+                height: mock_env().block.height,
+                time: mock_env().block.time,
+                percentage: Decimal::percent(10),
+                evidence: Some("downtime".to_string()),
+                tombstoned: false,
+            },
+            SlashInfo {
+                id: 2,
+                height: mock_env().block.height,
+                time: mock_env().block.time,
+                percentage: Decimal::percent(50),
+                evidence: None,
+                tombstoned: true,
+            }
+        ]
+    );
+
+    // Pagination
+    let history =
+        query_slashing_history(deps.as_ref(), VALIDATOR.to_string(), Some(1), None).unwrap();
+    assert_eq!(history.slashes.len(), 1);
+    assert_eq!(history.slashes[0].id, 2);
+
+    // 1000 -> 900 -> 450
+    let account = query_account_slashes(deps.as_ref(), DELEGATOR_ADDR.to_string()).unwrap();
+    assert_eq!(
+        account.slashes,
+        vec![
+            AccountSlash {
+                validator: VALIDATOR.to_string(),
+                id: 1,
+                height: mock_env().block.height,
+                percentage: Decimal::percent(10),
+                amount: Uint128::new(100),
+            },
+            AccountSlash {
+                validator: VALIDATOR.to_string(),
+                id: 2,
+                height: mock_env().block.height,
+                percentage: Decimal::percent(50),
+                amount: Uint128::new(450),
+            }
+        ]
+    );
+
+    // Taking the slashes from the stake keeps the history
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("anyone", &[]),
+        ExecuteMsg::ProcessSlash {
+            validator: VALIDATOR.to_string(),
+            start_after: None,
+            limit: None,
+        },
+    )
+    .unwrap();
+    let processed = query_account_slashes(deps.as_ref(), DELEGATOR_ADDR.to_string()).unwrap();
+    assert_eq!(processed, account);
+
+    // Stake added after the slashes was not slashed
+    add_stake_unit(deps.as_mut(), "staker2", VALIDATOR, Uint128::new(450)).unwrap();
+    let account = query_account_slashes(deps.as_ref(), "staker2".to_string()).unwrap();
+    assert_eq!(account.slashes, vec![]);
+}
+
+#[test]
+fn test_query_slashes_after_restake() {
+    let (mut deps, _) = setup_unit_with_channel(None, CHANNEL_ID);
+    CONFIG
+        .update::<_, StdError>(deps.as_mut().storage, |mut cfg| {
+            cfg.slasher = Some(addr!("slasher"));
+            Ok(cfg)
+        })
+        .unwrap();
+    update_validator_unit(deps.as_mut(), vec![VALIDATOR.to_string()], vec![]).unwrap();
+    add_stake_unit(deps.as_mut(), DELEGATOR_ADDR, VALIDATOR, Uint128::new(1000)).unwrap();
+    for percentage in [10, 50] {
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("slasher", &[]),
+            ExecuteMsg::Slash {
+                validator: VALIDATOR.to_string(),
+                percentage: Decimal::percent(percentage),
+                force_unbond: false,
+                evidence: None,
+            },
+        )
+        .unwrap();
+    }
+
+    // Staking again takes the slashes of the old stake first
+    let res = add_stake_unit(deps.as_mut(), DELEGATOR_ADDR, VALIDATOR, Uint128::new(900)).unwrap();
+    assert_eq!(
+        res.messages[0].msg,
+        WasmMsg::Execute {
+            contract_addr: LOCKUP_ADDR.to_string(),
+            msg: to_binary(&ClaimProviderMsg::SlashClaim {
+                owner: DELEGATOR_ADDR.to_string(),
+                amount: Uint128::new(550),
+            })
+            .unwrap(),
+            funds: vec![],
+        }
+        .into()
+    );
+
+    // 1000 -> 900 -> 450, the new stake doesn't change that
+    let account = query_account_slashes(deps.as_ref(), DELEGATOR_ADDR.to_string()).unwrap();
+    let amounts: Vec<_> = account.slashes.iter().map(|s| s.amount.u128()).collect();
+    assert_eq!(amounts, vec![100, 450]);
+}
+
+#[test]
+fn test_query_portfolio() {
+    let (mut deps, _) = setup_unit_with_channel(None, CHANNEL_ID);
+    let env = mock_env();
+
+    // Nothing staked, so we don't ask lockup
+    let portfolio =
+        query_portfolio(deps.as_ref(), env.clone(), DELEGATOR_ADDR.to_string()).unwrap();
+    assert_eq!(portfolio.positions, vec![]);
+    assert_eq!(portfolio.lien, Uint128::zero());
+
+    update_validator_unit(deps.as_mut(), vec![VALIDATOR.to_string()], vec![]).unwrap();
+    add_stake_unit(deps.as_mut(), DELEGATOR_ADDR, VALIDATOR, Uint128::new(1000)).unwrap();
+    let packet = mock_packet(
+        to_binary(&ConsumerMsg::Rewards {
+            validator: VALIDATOR.to_string(),
+            total_funds: coin(100, REWARDS_IBC_DENOM),
+        })
+        .unwrap(),
+    );
+    ibc_packet_receive(
+        deps.as_mut(),
+        mock_env(),
+        IbcPacketReceiveMsg::new(packet, addr!(RELAYER_ADDR)),
+    )
+    .unwrap();
+    remove_stake_unit(deps.as_mut(), DELEGATOR_ADDR, VALIDATOR, Uint128::new(400)).unwrap();
+
+    let contract = env.contract.address.to_string();
+    deps.querier.update_wasm(move |query| match query {
+        WasmQuery::Smart { contract_addr, .. } if contract_addr == LOCKUP_ADDR => {
+            let balance = LockupBalanceResponse {
+                bonded: Uint128::new(2000),
+                free: Uint128::new(1000),
+                claims: vec![
+                    Lein {
+                        leinholder: "other".to_string(),
+                        amount: Uint128::new(300),
+                    },
+                    Lein {
+                        leinholder: contract.clone(),
+                        amount: Uint128::new(1000),
+                    },
+                ],
+            };
+            SystemResult::Ok(ContractResult::Ok(to_binary(&balance).unwrap()))
+        }
+        _ => panic!("unexpected query"),
+    });
+
+    let portfolio =
+        query_portfolio(deps.as_ref(), env.clone(), DELEGATOR_ADDR.to_string()).unwrap();
+    assert_eq!(
+        portfolio.positions,
+        vec![Position {
+            validator: VALIDATOR.to_string(),
+            status: ValStatus::Active,
+            tokens: Uint128::new(600),
+            shares: Uint128::new(600),
+            slashed: Uint128::zero(),
+            pending_rewards: Uint128::new(100),
+        }]
+    );
+    let unbonding_period = CONFIG.load(&deps.storage).unwrap().unbonding_period;
+    assert_eq!(
+        portfolio.unbonding,
+        vec![Claim {
+            amount: Uint128::new(400),
+            release_at: Expiration::AtTime(env.block.time.plus_seconds(unbonding_period)),
+        }]
+    );
+    assert_eq!(portfolio.lien, Uint128::new(1000));
+}
+
+#[test]
+fn test_pending_rewards_match_claim() {
+    let (mut deps, _) = setup_unit_with_channel(None, CHANNEL_ID);
+    let env = mock_env();
+    let stakers = [("staker1", 1000u128, 100u128), ("staker2", 3000, 300)];
+
+    update_validator_unit(deps.as_mut(), vec![VALIDATOR.to_string()], vec![]).unwrap();
+    for (staker, amount, _) in stakers {
+        add_stake_unit(deps.as_mut(), staker, VALIDATOR, Uint128::new(amount)).unwrap();
+    }
+    let packet = mock_packet(
+        to_binary(&ConsumerMsg::Rewards {
+            validator: VALIDATOR.to_string(),
+            total_funds: coin(400, REWARDS_IBC_DENOM),
+        })
+        .unwrap(),
+    );
+    ibc_packet_receive(
+        deps.as_mut(),
+        mock_env(),
+        IbcPacketReceiveMsg::new(packet, addr!(RELAYER_ADDR)),
+    )
+    .unwrap();
+
+    deps.querier.update_wasm(|_| {
+        let balance = LockupBalanceResponse {
+            bonded: Uint128::zero(),
+            free: Uint128::zero(),
+            claims: vec![],
+        };
+        SystemResult::Ok(ContractResult::Ok(to_binary(&balance).unwrap()))
+    });
+    deps.querier
+        .update_balance(env.contract.address.clone(), coins(400, REWARDS_IBC_DENOM));
+
+    // Each staker sees and claims their share of the rewards
+    for (staker, _, rewards) in stakers {
+        let portfolio = query_portfolio(deps.as_ref(), env.clone(), staker.to_string()).unwrap();
+        assert_eq!(
+            portfolio.positions[0].pending_rewards,
+            Uint128::new(rewards)
+        );
+
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(staker, &[]),
+            ExecuteMsg::ClaimRewards {
+                validator: VALIDATOR.to_string(),
+            },
+        )
+        .unwrap();
+        assert_eq!(
+            res.messages[0].msg,
+            BankMsg::Send {
+                to_address: staker.to_string(),
+                amount: coins(rewards, REWARDS_IBC_DENOM),
+            }
+            .into()
+        );
+    }
+}
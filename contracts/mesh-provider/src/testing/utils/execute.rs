@@ -23,6 +23,7 @@ pub fn execute_slash(
             validator: validator.to_string(),
             percentage: Decimal::from_str(slash_amount).unwrap(),
             force_unbond,
+            evidence: None,
         },
         &[],
     )
@@ -42,3 +43,18 @@ pub fn execute_claim_rewards(
         &[],
     )
 }
+
+pub fn execute_set_withdraw_address(
+    app: &mut App,
+    contract_addr: &str,
+    address: &str,
+) -> AnyResult<AppResponse> {
+    app.execute_contract(
+        addr!(DELEGATOR_ADDR),
+        addr!(contract_addr),
+        &ExecuteMsg::SetWithdrawAddress {
+            address: address.to_string(),
+        },
+        &[],
+    )
+}
@@ -5,11 +5,14 @@ use std::vec;
 use cosmwasm_std::{
     from_binary,
     testing::{mock_env, mock_info},
-    to_binary, Addr, Deps, DepsMut, Ibc3ChannelOpenResponse, IbcAcknowledgement, IbcBasicResponse,
-    IbcChannel, IbcChannelCloseMsg, IbcChannelConnectMsg, IbcChannelOpenMsg, IbcPacketAckMsg,
-    IbcPacketReceiveMsg, IbcReceiveResponse, StdError, Uint128,
+    to_binary, Addr, Decimal, Deps, DepsMut, Ibc3ChannelOpenResponse, IbcAcknowledgement,
+    IbcBasicResponse, IbcChannel, IbcChannelCloseMsg, IbcChannelConnectMsg, IbcChannelOpenMsg,
+    IbcPacketAckMsg, IbcPacketReceiveMsg, IbcReceiveResponse, StdError, Uint128,
+};
+use mesh_ibc::{
+    ConsumerMsg, ListValidatorsResponse, ProviderMsg, SyncStateResponse, ValidatorMetadata,
+    ValidatorStake, IBC_APP_VERSION,
 };
-use mesh_ibc::{ConsumerMsg, ListValidatorsResponse, ProviderMsg, IBC_APP_VERSION};
 use mesh_testing::{
     addr,
     constants::{
@@ -45,6 +48,11 @@ pub fn get_default_init_msg(slasher_code_id: u64) -> InstantiateMsg {
         unbonding_period: 86400 * 14,
         rewards_ibc_denom: REWARDS_IBC_DENOM.to_string(),
         packet_lifetime: None,
+        admin: None,
+        guardian: None,
+        stake_caps: None,
+        channel_recovery_period: None,
+        retry_policy: None,
     }
 }
 
@@ -95,7 +103,24 @@ pub fn update_validator_unit(
     added: Vec<String>,
     removed: Vec<String>,
 ) -> Result<IbcReceiveResponse, ContractError> {
-    let packet = mock_packet(to_binary(&ConsumerMsg::UpdateValidators { added, removed }).unwrap());
+    update_validator_epoch_unit(deps, added, removed, 0)
+}
+
+pub fn update_validator_epoch_unit(
+    deps: DepsMut,
+    added: Vec<String>,
+    removed: Vec<String>,
+    epoch: u64,
+) -> Result<IbcReceiveResponse, ContractError> {
+    let packet = mock_packet(
+        to_binary(&ConsumerMsg::UpdateValidators {
+            added,
+            removed,
+            epoch,
+            metadata: vec![],
+        })
+        .unwrap(),
+    );
 
     ibc_packet_receive(
         deps,
@@ -200,6 +225,33 @@ pub fn list_validators_unit(deps: DepsMut) -> Result<IbcBasicResponse, ContractE
     let original_packet = mock_packet(to_binary(&ProviderMsg::ListValidators {}).unwrap());
     let ack = IbcAcknowledgement::new(to_ack_success(ListValidatorsResponse {
         validators: vec![VALIDATOR.to_string()],
+        epoch: 0,
+        metadata: vec![ValidatorMetadata {
+            address: VALIDATOR.to_string(),
+            commission: Decimal::percent(5),
+            max_commission: Decimal::percent(20),
+            max_change_rate: Decimal::percent(1),
+            jailed: false,
+        }],
+    }));
+
+    ibc_packet_ack(
+        deps,
+        mock_env(),
+        IbcPacketAckMsg::new(ack, original_packet, addr!(RELAYER_ADDR)),
+    )
+}
+
+pub fn list_validators_epoch_unit(
+    deps: DepsMut,
+    validators: Vec<String>,
+    epoch: u64,
+) -> Result<IbcBasicResponse, ContractError> {
+    let original_packet = mock_packet(to_binary(&ProviderMsg::ListValidators {}).unwrap());
+    let ack = IbcAcknowledgement::new(to_ack_success(ListValidatorsResponse {
+        validators,
+        epoch,
+        metadata: vec![],
     }));
 
     ibc_packet_ack(
@@ -220,6 +272,20 @@ pub fn list_validators_fail_unit(deps: DepsMut) -> Result<IbcBasicResponse, Cont
     )
 }
 
+pub fn sync_state_unit(
+    deps: DepsMut,
+    validators: Vec<ValidatorStake>,
+) -> Result<IbcBasicResponse, ContractError> {
+    let original_packet = mock_packet(to_binary(&ProviderMsg::SyncState {}).unwrap());
+    let ack = IbcAcknowledgement::new(to_ack_success(SyncStateResponse { validators }));
+
+    ibc_packet_ack(
+        deps,
+        mock_env(),
+        IbcPacketAckMsg::new(ack, original_packet, addr!(RELAYER_ADDR)),
+    )
+}
+
 // Queries
 pub fn query_validators_unit(deps: Deps, validator: &str) -> Result<ValidatorResponse, StdError> {
     let res = query(
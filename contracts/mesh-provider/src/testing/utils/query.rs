@@ -2,7 +2,7 @@ use cosmwasm_std::StdResult;
 use cw_multi_test::App;
 use mesh_testing::msgs::{SlasherConfigResponse, SlasherQueryMsg};
 
-use crate::msg::{ConfigResponse, ListValidatorsResponse, QueryMsg};
+use crate::msg::{ConfigResponse, ListValidatorsResponse, QueryMsg, WithdrawAddressResponse};
 
 pub fn query_provider_config(app: &App, contract_addr: &str) -> StdResult<ConfigResponse> {
     app.wrap()
@@ -28,3 +28,16 @@ pub fn query_validators(
         },
     )
 }
+
+pub fn query_withdraw_address(
+    app: &App,
+    contract_addr: &str,
+    address: &str,
+) -> StdResult<WithdrawAddressResponse> {
+    app.wrap().query_wasm_smart(
+        contract_addr,
+        &QueryMsg::WithdrawAddress {
+            address: address.to_string(),
+        },
+    )
+}
@@ -42,8 +42,4 @@ pub enum QueryMsg {
     },
 }
 
-#[cw_serde]
-pub struct Delegation {
-    pub validator: String,
-    pub delegation: Uint128,
-}
+pub use mesh_apis::Delegation;
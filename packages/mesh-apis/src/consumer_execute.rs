@@ -1,6 +1,57 @@
 use cosmwasm_schema::cw_serde;
+use cosmwasm_std::Decimal;
 
 #[cw_serde]
 pub enum ConsumerExecuteMsg {
-    MeshConsumerRecieveRewardsMsg { validator: String },
+    MeshConsumerRecieveRewardsMsg {
+        validator: String,
+    },
+    /// Revalues the virtual stake with the current price, at most once per rebalance epoch.
+    /// Permissionless.
+    Rebalance {},
+    /// Sends the changes to the active validator set since the last sync to the providers,
+    /// with the changes whose update failed before. Permissionless.
+    SyncValidators {},
+    /// Resends rewards whose packet or ICS20 transfer to the provider failed.
+    /// Permissionless.
+    RetryRewards {},
+    /// Resends slashes the provider could not take yet, e.g. while it was paused.
+    /// Permissionless.
+    RetrySlashes {},
+    /// Sends the rewards collected since the last batch in one packet, at most once per
+    /// batch interval. Permissionless.
+    SendBatchRewards {},
+}
+
+/// Called by the chain (e.g. a custom staking hook), never by users
+#[cw_serde]
+pub enum ConsumerSudoMsg {
+    /// A validator was slashed on this chain, forwarded to the provider
+    Slash {
+        validator: String,
+        percentage: Decimal,
+        infraction_height: u64,
+        tombstone: bool,
+    },
+    /// An ICS20 transfer sent by this contract completed. If it failed or timed out, the tokens
+    /// were refunded to us. This is the `ibc_lifecycle_complete` callback of the ibc-hooks
+    /// middleware, which only calls back for transfers with an `{"ibc_callback": <contract>}` memo.
+    IbcLifecycleComplete(IbcLifecycleComplete),
+}
+
+#[cw_serde]
+pub enum IbcLifecycleComplete {
+    IbcAck {
+        /// the source channel of the transfer
+        channel: String,
+        sequence: u64,
+        /// the raw acknowledgement, as JSON
+        ack: String,
+        success: bool,
+    },
+    IbcTimeout {
+        /// the source channel of the transfer
+        channel: String,
+        sequence: u64,
+    },
 }
@@ -1,9 +1,15 @@
 mod claims;
 mod consumer_execute;
+mod lockup_query;
+mod price_feed;
 mod slash;
 mod staking_execute;
+mod staking_query;
 
 pub use claims::{ClaimProviderMsg, ClaimReceiverMsg};
-pub use consumer_execute::ConsumerExecuteMsg;
+pub use consumer_execute::{ConsumerExecuteMsg, ConsumerSudoMsg, IbcLifecycleComplete};
+pub use lockup_query::{Lein, LockupBalanceResponse, LockupQueryMsg};
+pub use price_feed::{PriceFeedQueryMsg, PriceResponse};
 pub use slash::SlashMsg;
 pub use staking_execute::{StakingExecuteMsg, StakingSudoMsg};
+pub use staking_query::{Delegation, StakingQueryMsg};
@@ -0,0 +1,21 @@
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::Uint128;
+
+/// The lockup queries used by leinholders
+#[cw_serde]
+pub enum LockupQueryMsg {
+    Balance { account: String },
+}
+
+#[cw_serde]
+pub struct LockupBalanceResponse {
+    pub bonded: Uint128,
+    pub free: Uint128,
+    pub claims: Vec<Lein>,
+}
+
+#[cw_serde]
+pub struct Lein {
+    pub leinholder: String,
+    pub amount: Uint128,
+}
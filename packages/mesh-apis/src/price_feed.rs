@@ -0,0 +1,16 @@
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{Decimal, Timestamp};
+
+/// The query a price feed contract answers, used by the consumer to convert provider tokens
+#[cw_serde]
+pub enum PriceFeedQueryMsg {
+    /// Local tokens per provider token, ideally a TWAP over a long window
+    Price {},
+}
+
+#[cw_serde]
+pub struct PriceResponse {
+    pub price: Decimal,
+    /// When the price was last updated
+    pub updated_at: Timestamp,
+}
@@ -11,5 +11,7 @@ pub enum SlashMsg {
         /// do we forcibly unbond this validator on the provider side,
         /// regardless of the behavior of the consumer?
         force_unbond: bool,
+        /// reference to the misbehaviour, kept in the slashing history
+        evidence: Option<String>,
     },
 }
@@ -0,0 +1,15 @@
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::Uint128;
+
+/// The meta-staking queries used by other contracts
+#[cw_serde]
+pub enum StakingQueryMsg {
+    /// AllDelegations will return all delegations by the consumer
+    AllDelegations { consumer: String },
+}
+
+#[cw_serde]
+pub struct Delegation {
+    pub validator: String,
+    pub delegation: Uint128,
+}
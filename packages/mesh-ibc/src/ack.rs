@@ -3,6 +3,9 @@ use serde::{de::DeserializeOwned, Serialize};
 
 use cosmwasm_std::{from_slice, to_binary, Binary};
 
+// prefix of errors that go away on their own, e.g. while the receiver is paused
+const TRANSIENT_ERROR: &str = "transient: ";
+
 /// This is a generic ICS acknowledgement format.
 /// Proto defined here: https://github.com/cosmos/cosmos-sdk/blob/v0.42.0/proto/ibc/core/channel/v1/channel.proto#L141-L147
 /// If ibc_receive_packet returns Err(), then x/wasm runtime will rollback the state and return an error message in this format
@@ -24,6 +27,11 @@ impl StdAck {
         StdAck::Error(err).ack()
     }
 
+    // create a serialized error message the sender may retry later
+    pub fn fail_transient(err: String) -> Binary {
+        StdAck::Error(format!("{}{}", TRANSIENT_ERROR, err)).ack()
+    }
+
     pub fn ack(&self) -> Binary {
         to_binary(self).unwrap()
     }
@@ -53,4 +61,8 @@ impl StdAck {
     pub fn is_ok(&self) -> bool {
         matches!(self, StdAck::Result(_))
     }
+
+    pub fn is_transient(&self) -> bool {
+        matches!(self, StdAck::Error(err) if err.starts_with(TRANSIENT_ERROR))
+    }
 }
@@ -1,6 +1,6 @@
 use cosmwasm_schema::cw_serde;
 
-use cosmwasm_std::{Coin, Uint128};
+use cosmwasm_std::{Coin, Decimal, Uint128};
 
 /// These are messages sent from the provider to the consumer
 #[cw_serde]
@@ -26,6 +26,9 @@ pub enum ProviderMsg {
         /// properly handle ack and timeout messages (not used by consumer)
         key: String,
     },
+    /// Returns the stake the consumer holds on every validator, so the provider
+    /// can detect if its view drifted from the consumer
+    SyncState {},
 }
 
 /// These are messages sent from the consumer to the provider
@@ -37,17 +40,64 @@ pub enum ConsumerMsg {
     UpdateValidators {
         added: Vec<String>,
         removed: Vec<String>,
+        /// Valset epoch after this change, updates older than the last seen epoch are ignored
+        #[serde(default)]
+        epoch: u64,
+        /// Details of the added validators, so stakers can choose between them
+        #[serde(default)]
+        metadata: Vec<ValidatorMetadata>,
     },
     Rewards {
         validator: String,
         total_funds: Coin,
     },
+    /// Rewards of many validators, the funds follow in one ICS20 transfer per denom
+    BatchRewards { entries: Vec<RewardsEntry> },
+    /// A validator was slashed on the consumer chain, the provider slashes the cross-stake too
+    Slash {
+        validator: String,
+        percentage: Decimal,
+        /// Consumer chain height of the misbehaviour
+        infraction_height: u64,
+        /// Unbond all stake from the validator, it can't be staked to again
+        tombstone: bool,
+    },
 }
 
 /// List the current validator set.
 #[cw_serde]
 pub struct ListValidatorsResponse {
     pub validators: Vec<String>,
+    /// Valset epoch of this list, bumped by the consumer whenever the set changes
+    #[serde(default)]
+    pub epoch: u64,
+    /// Details of the listed validators, so stakers can choose between them
+    #[serde(default)]
+    pub metadata: Vec<ValidatorMetadata>,
+}
+
+/// Validator details from the consumer chain staking module
+#[cw_serde]
+pub struct ValidatorMetadata {
+    pub address: String,
+    pub commission: Decimal,
+    pub max_commission: Decimal,
+    pub max_change_rate: Decimal,
+    /// The staking queries only return bonded validators, so this is true for validators
+    /// that dropped out of the bonded set, jailed or not
+    pub jailed: bool,
+}
+
+/// Stake per validator as seen by the consumer, in provider tokens
+#[cw_serde]
+pub struct SyncStateResponse {
+    pub validators: Vec<ValidatorStake>,
+}
+
+#[cw_serde]
+pub struct ValidatorStake {
+    pub validator: String,
+    pub stake: Uint128,
 }
 
 /// TODO: any data we want when incrementing stake
@@ -62,6 +112,15 @@ pub struct UnstakeResponse {}
 #[cw_serde]
 pub struct UpdateValidatorsResponse {}
 
+#[cw_serde]
+pub struct RewardsEntry {
+    pub validator: String,
+    pub amount: Coin,
+}
+
+#[cw_serde]
+pub struct SlashResponse {}
+
 /// TODO: any data we want after delivering rewards
 #[cw_serde]
 pub struct RewardsResponse {}
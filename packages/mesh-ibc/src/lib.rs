@@ -7,8 +7,9 @@ use cosmwasm_std::IbcOrder;
 pub use crate::ack::StdAck;
 pub use crate::checks::{check_order, check_version, MeshSecurityError};
 pub use crate::ibc_msg::{
-    ConsumerMsg, ListValidatorsResponse, ProviderMsg, RewardsResponse, StakeResponse,
-    UnstakeResponse, UpdateValidatorsResponse,
+    ConsumerMsg, ListValidatorsResponse, ProviderMsg, RewardsEntry, RewardsResponse, SlashResponse,
+    StakeResponse, SyncStateResponse, UnstakeResponse, UpdateValidatorsResponse, ValidatorMetadata,
+    ValidatorStake,
 };
 
 pub const IBC_APP_VERSION: &str = "mesh-security-v0.1";
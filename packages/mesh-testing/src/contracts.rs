@@ -16,7 +16,9 @@ pub fn mesh_consumer_contract() -> Box<dyn Contract<Empty>> {
         mesh_consumer::contract::execute,
         mesh_consumer::contract::instantiate,
         mesh_consumer::contract::query,
-    );
+    )
+    .with_sudo(mesh_consumer::contract::sudo)
+    .with_reply(mesh_consumer::contract::reply);
     Box::new(contract)
 }
 
@@ -39,6 +39,11 @@ pub fn get_mesh_provider_init_msg(slasher_code_id: u64, lockup_addr: Option<&str
         unbonding_period: 86400 * 14,
         rewards_ibc_denom: REWARDS_IBC_DENOM.to_string(),
         packet_lifetime: None,
+        admin: None,
+        guardian: None,
+        stake_caps: None,
+        channel_recovery_period: None,
+        retry_policy: None,
     }
 }
 
@@ -86,14 +91,20 @@ pub fn instantiate_mesh_consumer(
 ) -> Addr {
     let mesh_consumer_id = app.store_code(mesh_consumer_contract());
     let init_msg = init_msg.unwrap_or(mesh_consumer::msg::InstantiateMsg {
-        provider: mesh_consumer::msg::ProviderInfo {
+        providers: vec![mesh_consumer::msg::ProviderInfo {
             port_id: "some_port".to_string(),
             connection_id: "come_connection".to_string(),
-        },
+            address: None,
+            address_from_port: true,
+            ics20_channel: "some_channel".to_string(),
+            price_feed: None,
+            max_cap: None,
+        }],
         remote_to_local_exchange_rate: Decimal::from_str("0.1").unwrap(),
         meta_staking_contract_address: meta_staking_addr.unwrap().to_string(),
-        ics20_channel: "some_channel".to_string(),
         packet_lifetime: None,
+        rebalance: None,
+        rewards_batch_interval: None,
     });
 
     app.instantiate_contract(
@@ -144,13 +144,17 @@ async function demoSetup(): Promise<SetupInfo> {
 
   // instantiate mesh_consumer on wasmd
   const initMeshConsumer = {
-    provider: {
-      port_id: meshProviderPort,
-      connection_id: link.endA.connectionID,
-    },
+    providers: [
+      {
+        port_id: meshProviderPort,
+        connection_id: link.endA.connectionID,
+        // rewards go to the provider contract bound to the port
+        address_from_port: true,
+        ics20_channel: ics20.osmo,
+      },
+    ],
     remote_to_local_exchange_rate: "0.1",
     meta_staking_contract_address: wasmMetaStaking,
-    ics20_channel: ics20.osmo,
   };
   const { contractAddress: wasmMeshConsumer } = await wasmClient.sign.instantiate(
     wasmClient.senderAddress,
@@ -237,14 +241,17 @@ test.serial("fail if connect from different connect or port", async (t) => {
 
   // instantiate mesh_consumer on wasmd
   const initMeshConsumer = {
-    provider: {
-      // this is not the meshProviderPort, so authentication will reject it
-      port_id: "connection-123456",
-      connection_id: link.endA.connectionID,
-    },
+    providers: [
+      {
+        // this is not the meshProviderPort, so authentication will reject it
+        port_id: "connection-123456",
+        connection_id: link.endA.connectionID,
+        address_from_port: true,
+        ics20_channel: "channel-10",
+      },
+    ],
     remote_to_local_exchange_rate: "0.1",
     meta_staking_contract_address: wasmMetaStaking,
-    ics20_channel: "channel-10",
   };
   const { contractAddress: wasmMeshConsumer } = await wasmClient.sign.instantiate(
     wasmClient.senderAddress,
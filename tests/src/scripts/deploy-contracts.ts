@@ -116,13 +116,16 @@ async function installConsumer(
 
   console.log("instantiate mesh_consumer on wasmd");
   const initMeshConsumer: ConsumerInitMsg = {
-    provider: {
-      port_id: providerPortId,
-      connection_id: connectionId,
-    },
+    providers: [
+      {
+        port_id: providerPortId,
+        connection_id: connectionId,
+        address_from_port: true,
+        ics20_channel: "",
+      },
+    ],
     remote_to_local_exchange_rate: "0.3",
     meta_staking_contract_address: metaStakingAddr,
-    ics20_channel: "",
   };
   const { contractAddress: meshConsumerAddr } = await client.instantiate(
     signer,
@@ -5,11 +5,43 @@
 */
 
 import { CosmWasmClient, SigningCosmWasmClient, ExecuteResult } from "@cosmjs/cosmwasm-stargate";
-import { Coin, StdFee } from "@cosmjs/amino";
-import { Decimal, InstantiateMsg, ProviderInfo, ExecuteMsg, QueryMsg, Addr, Config } from "./MeshConsumer.types";
+import { StdFee } from "@cosmjs/amino";
+import { Uint128, Decimal, InstantiateMsg, ProviderInfo, PriceFeedInfo, RebalanceInfo, ExecuteMsg, QueryMsg, SudoMsg, IbcLifecycleComplete, ChannelResponse, Addr, Config, ListVirtualStakeResponse, VirtualStakeResponse, PendingRewardsResponse, Coin, ProvidersResponse, ProviderResponse, ProviderChannel, Provider, PriceFeed, UnsentRewardsResponse, UnsentReward } from "./MeshConsumer.types";
 export interface MeshConsumerReadOnlyInterface {
   contractAddress: string;
   config: () => Promise<Config>;
+  providers: () => Promise<ProvidersResponse>;
+  channel: ({
+    provider
+  }: {
+    provider: string;
+  }) => Promise<ChannelResponse>;
+  virtualStake: ({
+    provider,
+    validator
+  }: {
+    provider: string;
+    validator: string;
+  }) => Promise<VirtualStakeResponse>;
+  listVirtualStake: ({
+    limit,
+    provider,
+    startAfter
+  }: {
+    limit?: number;
+    provider: string;
+    startAfter?: string;
+  }) => Promise<ListVirtualStakeResponse>;
+  pendingRewards: ({
+    provider
+  }: {
+    provider: string;
+  }) => Promise<PendingRewardsResponse>;
+  unsentRewards: ({
+    provider
+  }: {
+    provider: string;
+  }) => Promise<UnsentRewardsResponse>;
 }
 export class MeshConsumerQueryClient implements MeshConsumerReadOnlyInterface {
   client: CosmWasmClient;
@@ -19,6 +51,12 @@ export class MeshConsumerQueryClient implements MeshConsumerReadOnlyInterface {
     this.client = client;
     this.contractAddress = contractAddress;
     this.config = this.config.bind(this);
+    this.providers = this.providers.bind(this);
+    this.channel = this.channel.bind(this);
+    this.virtualStake = this.virtualStake.bind(this);
+    this.listVirtualStake = this.listVirtualStake.bind(this);
+    this.pendingRewards = this.pendingRewards.bind(this);
+    this.unsentRewards = this.unsentRewards.bind(this);
   }
 
   config = async (): Promise<Config> => {
@@ -26,6 +64,75 @@ export class MeshConsumerQueryClient implements MeshConsumerReadOnlyInterface {
       config: {}
     });
   };
+  providers = async (): Promise<ProvidersResponse> => {
+    return this.client.queryContractSmart(this.contractAddress, {
+      providers: {}
+    });
+  };
+  channel = async ({
+    provider
+  }: {
+    provider: string;
+  }): Promise<ChannelResponse> => {
+    return this.client.queryContractSmart(this.contractAddress, {
+      channel: {
+        provider
+      }
+    });
+  };
+  virtualStake = async ({
+    provider,
+    validator
+  }: {
+    provider: string;
+    validator: string;
+  }): Promise<VirtualStakeResponse> => {
+    return this.client.queryContractSmart(this.contractAddress, {
+      virtual_stake: {
+        provider,
+        validator
+      }
+    });
+  };
+  listVirtualStake = async ({
+    limit,
+    provider,
+    startAfter
+  }: {
+    limit?: number;
+    provider: string;
+    startAfter?: string;
+  }): Promise<ListVirtualStakeResponse> => {
+    return this.client.queryContractSmart(this.contractAddress, {
+      list_virtual_stake: {
+        limit,
+        provider,
+        start_after: startAfter
+      }
+    });
+  };
+  pendingRewards = async ({
+    provider
+  }: {
+    provider: string;
+  }): Promise<PendingRewardsResponse> => {
+    return this.client.queryContractSmart(this.contractAddress, {
+      pending_rewards: {
+        provider
+      }
+    });
+  };
+  unsentRewards = async ({
+    provider
+  }: {
+    provider: string;
+  }): Promise<UnsentRewardsResponse> => {
+    return this.client.queryContractSmart(this.contractAddress, {
+      unsent_rewards: {
+        provider
+      }
+    });
+  };
 }
 export interface MeshConsumerInterface extends MeshConsumerReadOnlyInterface {
   contractAddress: string;
@@ -35,6 +142,11 @@ export interface MeshConsumerInterface extends MeshConsumerReadOnlyInterface {
   }: {
     validator: string;
   }, fee?: number | StdFee | "auto", memo?: string, funds?: Coin[]) => Promise<ExecuteResult>;
+  rebalance: (fee?: number | StdFee | "auto", memo?: string, funds?: Coin[]) => Promise<ExecuteResult>;
+  syncValidators: (fee?: number | StdFee | "auto", memo?: string, funds?: Coin[]) => Promise<ExecuteResult>;
+  retryRewards: (fee?: number | StdFee | "auto", memo?: string, funds?: Coin[]) => Promise<ExecuteResult>;
+  retrySlashes: (fee?: number | StdFee | "auto", memo?: string, funds?: Coin[]) => Promise<ExecuteResult>;
+  sendBatchRewards: (fee?: number | StdFee | "auto", memo?: string, funds?: Coin[]) => Promise<ExecuteResult>;
 }
 export class MeshConsumerClient extends MeshConsumerQueryClient implements MeshConsumerInterface {
   client: SigningCosmWasmClient;
@@ -47,6 +159,11 @@ export class MeshConsumerClient extends MeshConsumerQueryClient implements MeshC
     this.sender = sender;
     this.contractAddress = contractAddress;
     this.meshConsumerRecieveRewardsMsg = this.meshConsumerRecieveRewardsMsg.bind(this);
+    this.rebalance = this.rebalance.bind(this);
+    this.syncValidators = this.syncValidators.bind(this);
+    this.retryRewards = this.retryRewards.bind(this);
+    this.retrySlashes = this.retrySlashes.bind(this);
+    this.sendBatchRewards = this.sendBatchRewards.bind(this);
   }
 
   meshConsumerRecieveRewardsMsg = async ({
@@ -60,4 +177,29 @@ export class MeshConsumerClient extends MeshConsumerQueryClient implements MeshC
       }
     }, fee, memo, funds);
   };
+  rebalance = async (fee: number | StdFee | "auto" = "auto", memo?: string, funds?: Coin[]): Promise<ExecuteResult> => {
+    return await this.client.execute(this.sender, this.contractAddress, {
+      rebalance: {}
+    }, fee, memo, funds);
+  };
+  syncValidators = async (fee: number | StdFee | "auto" = "auto", memo?: string, funds?: Coin[]): Promise<ExecuteResult> => {
+    return await this.client.execute(this.sender, this.contractAddress, {
+      sync_validators: {}
+    }, fee, memo, funds);
+  };
+  retryRewards = async (fee: number | StdFee | "auto" = "auto", memo?: string, funds?: Coin[]): Promise<ExecuteResult> => {
+    return await this.client.execute(this.sender, this.contractAddress, {
+      retry_rewards: {}
+    }, fee, memo, funds);
+  };
+  retrySlashes = async (fee: number | StdFee | "auto" = "auto", memo?: string, funds?: Coin[]): Promise<ExecuteResult> => {
+    return await this.client.execute(this.sender, this.contractAddress, {
+      retry_slashes: {}
+    }, fee, memo, funds);
+  };
+  sendBatchRewards = async (fee: number | StdFee | "auto" = "auto", memo?: string, funds?: Coin[]): Promise<ExecuteResult> => {
+    return await this.client.execute(this.sender, this.contractAddress, {
+      send_batch_rewards: {}
+    }, fee, memo, funds);
+  };
 }
\ No newline at end of file
@@ -4,30 +4,159 @@
 * and run the @cosmwasm/ts-codegen generate command to regenerate this file.
 */
 
+export type Uint128 = string;
 export type Decimal = string;
 export interface InstantiateMsg {
-  ics20_channel: string;
   meta_staking_contract_address: string;
   packet_lifetime?: number | null;
-  provider: ProviderInfo;
+  providers: ProviderInfo[];
+  rebalance?: RebalanceInfo | null;
   remote_to_local_exchange_rate: Decimal;
+  rewards_batch_interval?: number | null;
 }
 export interface ProviderInfo {
+  address?: string | null;
+  address_from_port?: boolean;
   connection_id: string;
+  ics20_channel: string;
+  max_cap?: Uint128 | null;
   port_id: string;
+  price_feed?: PriceFeedInfo | null;
+}
+export interface PriceFeedInfo {
+  discount: Decimal;
+  max_staleness: number;
+  oracle: string;
+}
+export interface RebalanceInfo {
+  epoch: number;
+  max_adjustment: Decimal;
 }
 export type ExecuteMsg = {
   mesh_consumer_recieve_rewards_msg: {
     validator: string;
   };
+} | {
+  rebalance: {};
+} | {
+  sync_validators: {};
+} | {
+  retry_rewards: {};
+} | {
+  retry_slashes: {};
+} | {
+  send_batch_rewards: {};
 };
 export type QueryMsg = {
   config: {};
+} | {
+  providers: {};
+} | {
+  channel: {
+    provider: string;
+  };
+} | {
+  virtual_stake: {
+    provider: string;
+    validator: string;
+  };
+} | {
+  list_virtual_stake: {
+    limit?: number | null;
+    provider: string;
+    start_after?: string | null;
+  };
+} | {
+  pending_rewards: {
+    provider: string;
+  };
+} | {
+  unsent_rewards: {
+    provider: string;
+  };
+};
+export type SudoMsg = {
+  slash: {
+    infraction_height: number;
+    percentage: Decimal;
+    tombstone: boolean;
+    validator: string;
+  };
+} | {
+  ibc_lifecycle_complete: IbcLifecycleComplete;
 };
+export type IbcLifecycleComplete = {
+  ibc_ack: {
+    ack: string;
+    channel: string;
+    sequence: number;
+    success: boolean;
+  };
+} | {
+  ibc_timeout: {
+    channel: string;
+    sequence: number;
+  };
+};
+export interface ChannelResponse {
+  channel?: string | null;
+  ics20_channel: string;
+  packet_lifetime: number;
+}
 export type Addr = string;
 export interface Config {
-  ics20_channel: string;
   meta_staking_contract_address: Addr;
-  provider: ProviderInfo;
+  rebalance?: RebalanceInfo | null;
   remote_to_local_exchange_rate: Decimal;
+  rewards_batch_interval?: number | null;
+}
+export interface ListVirtualStakeResponse {
+  stakes: VirtualStakeResponse[];
+}
+export interface VirtualStakeResponse {
+  local: Uint128;
+  rate?: Decimal | null;
+  remote: Uint128;
+  validator: string;
+}
+export interface PendingRewardsResponse {
+  rewards: Coin[];
+}
+export interface Coin {
+  amount: Uint128;
+  denom: string;
+  [k: string]: unknown;
+}
+export interface ProvidersResponse {
+  providers: ProviderResponse[];
+}
+export interface ProviderResponse {
+  channel?: ProviderChannel | null;
+  connection_id: string;
+  provider: Provider;
+}
+export interface ProviderChannel {
+  channel_id: string;
+  recipient: string;
+}
+export interface Provider {
+  address?: string | null;
+  address_from_port: boolean;
+  ics20_channel: string;
+  max_cap?: Uint128 | null;
+  port_id: string;
+  price_feed?: PriceFeed | null;
+}
+export interface PriceFeed {
+  discount: Decimal;
+  max_staleness: number;
+  oracle: Addr;
+}
+export interface UnsentRewardsResponse {
+  rewards: UnsentReward[];
+  transfers: Coin[];
+}
+export interface UnsentReward {
+  amount: Coin;
+  validator: string;
 }
\ No newline at end of file
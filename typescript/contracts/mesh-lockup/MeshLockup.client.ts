@@ -6,7 +6,7 @@
 
 import { CosmWasmClient, SigningCosmWasmClient, ExecuteResult } from "@cosmjs/cosmwasm-stargate";
 import { Coin, StdFee } from "@cosmjs/amino";
-import { InstantiateMsg, ExecuteMsg, Uint128, QueryMsg, BalanceResponse, Lein } from "./MeshLockup.types";
+import { SlashDestination, InstantiateMsg, ExecuteMsg, Uint128, QueryMsg, BalanceResponse, Lein, SlashedResponse } from "./MeshLockup.types";
 export interface MeshLockupReadOnlyInterface {
   contractAddress: string;
   balance: ({
@@ -14,6 +14,7 @@ export interface MeshLockupReadOnlyInterface {
   }: {
     account: string;
   }) => Promise<BalanceResponse>;
+  slashed: () => Promise<SlashedResponse>;
 }
 export class MeshLockupQueryClient implements MeshLockupReadOnlyInterface {
   client: CosmWasmClient;
@@ -23,6 +24,7 @@ export class MeshLockupQueryClient implements MeshLockupReadOnlyInterface {
     this.client = client;
     this.contractAddress = contractAddress;
     this.balance = this.balance.bind(this);
+    this.slashed = this.slashed.bind(this);
   }
 
   balance = async ({
@@ -36,6 +38,11 @@ export class MeshLockupQueryClient implements MeshLockupReadOnlyInterface {
       }
     });
   };
+  slashed = async (): Promise<SlashedResponse> => {
+    return this.client.queryContractSmart(this.contractAddress, {
+      slashed: {}
+    });
+  };
 }
 export interface MeshLockupInterface extends MeshLockupReadOnlyInterface {
   contractAddress: string;
@@ -4,8 +4,18 @@
 * and run the @cosmwasm/ts-codegen generate command to regenerate this file.
 */
 
+export type SlashDestination = "burn" | {
+  community_pool: {
+    address: string;
+  };
+} | {
+  insurance_fund: {
+    address: string;
+  };
+};
 export interface InstantiateMsg {
   denom: string;
+  slash_destination?: SlashDestination;
 }
 export type ExecuteMsg = {
   bond: {};
@@ -35,6 +45,8 @@ export type QueryMsg = {
   balance: {
     account: string;
   };
+} | {
+  slashed: {};
 };
 export interface BalanceResponse {
   bonded: Uint128;
@@ -44,4 +56,8 @@ export interface BalanceResponse {
 export interface Lein {
   amount: Uint128;
   leinholder: string;
+}
+export interface SlashedResponse {
+  destination: SlashDestination;
+  total: Uint128;
 }
\ No newline at end of file
@@ -6,7 +6,7 @@
 
 import { CosmWasmClient, SigningCosmWasmClient, ExecuteResult } from "@cosmjs/cosmwasm-stargate";
 import { Coin, StdFee } from "@cosmjs/amino";
-import { Binary, InstantiateMsg, ConsumerInfo, SlasherInfo, ExecuteMsg, Decimal, Uint128, QueryMsg, AccountResponse, StakeInfo, ConfigResponse, ValStatus, ListValidatorsResponse, ValidatorResponse } from "./MeshProvider.types";
+import { Binary, Uint128, Decimal, InstantiateMsg, ConsumerInfo, RetryPolicy, SlasherInfo, StakeCaps, ExecuteMsg, Operation, ConfigUpdate, QueryMsg, AccountResponse, StakeInfo, AccountSlashesResponse, AccountSlash, Timestamp, Uint64, ChannelResponse, ConfigResponse, ValStatus, ListValidatorsResponse, ValidatorResponse, ValidatorMetadata, PausedResponse, Expiration, PortfolioResponse, Position, Claim, ProviderMsg, RetryStatus, RetriesResponse, RetryInfo, SlashingHistoryResponse, SlashInfo, StateDriftResponse, ValidatorDrift, TotalPowerAtHeightResponse, UtilizationResponse, ValidatorUtilization, VotingPowerAtHeightResponse, WithdrawAddressResponse } from "./MeshProvider.types";
 export interface MeshProviderReadOnlyInterface {
   contractAddress: string;
   config: () => Promise<ConfigResponse>;
@@ -15,6 +15,11 @@ export interface MeshProviderReadOnlyInterface {
   }: {
     address: string;
   }) => Promise<AccountResponse>;
+  portfolio: ({
+    address
+  }: {
+    address: string;
+  }) => Promise<PortfolioResponse>;
   validator: ({
     address
   }: {
@@ -27,6 +32,62 @@ export interface MeshProviderReadOnlyInterface {
     limit?: number;
     startAfter?: string;
   }) => Promise<ListValidatorsResponse>;
+  utilization: ({
+    limit,
+    startAfter
+  }: {
+    limit?: number;
+    startAfter?: string;
+  }) => Promise<UtilizationResponse>;
+  paused: () => Promise<PausedResponse>;
+  withdrawAddress: ({
+    address
+  }: {
+    address: string;
+  }) => Promise<WithdrawAddressResponse>;
+  channel: () => Promise<ChannelResponse>;
+  stateDrift: ({
+    limit,
+    startAfter
+  }: {
+    limit?: number;
+    startAfter?: string;
+  }) => Promise<StateDriftResponse>;
+  retries: ({
+    limit,
+    owner,
+    startAfter
+  }: {
+    limit?: number;
+    owner: string;
+    startAfter?: number;
+  }) => Promise<RetriesResponse>;
+  slashingHistory: ({
+    limit,
+    startAfter,
+    validator
+  }: {
+    limit?: number;
+    startAfter?: number;
+    validator: string;
+  }) => Promise<SlashingHistoryResponse>;
+  accountSlashes: ({
+    address
+  }: {
+    address: string;
+  }) => Promise<AccountSlashesResponse>;
+  votingPowerAtHeight: ({
+    address,
+    height
+  }: {
+    address: string;
+    height?: number;
+  }) => Promise<VotingPowerAtHeightResponse>;
+  totalPowerAtHeight: ({
+    height
+  }: {
+    height?: number;
+  }) => Promise<TotalPowerAtHeightResponse>;
 }
 export class MeshProviderQueryClient implements MeshProviderReadOnlyInterface {
   client: CosmWasmClient;
@@ -37,8 +98,19 @@ export class MeshProviderQueryClient implements MeshProviderReadOnlyInterface {
     this.contractAddress = contractAddress;
     this.config = this.config.bind(this);
     this.account = this.account.bind(this);
+    this.portfolio = this.portfolio.bind(this);
     this.validator = this.validator.bind(this);
     this.listValidators = this.listValidators.bind(this);
+    this.utilization = this.utilization.bind(this);
+    this.paused = this.paused.bind(this);
+    this.withdrawAddress = this.withdrawAddress.bind(this);
+    this.channel = this.channel.bind(this);
+    this.stateDrift = this.stateDrift.bind(this);
+    this.retries = this.retries.bind(this);
+    this.slashingHistory = this.slashingHistory.bind(this);
+    this.accountSlashes = this.accountSlashes.bind(this);
+    this.votingPowerAtHeight = this.votingPowerAtHeight.bind(this);
+    this.totalPowerAtHeight = this.totalPowerAtHeight.bind(this);
   }
 
   config = async (): Promise<ConfigResponse> => {
@@ -57,6 +129,17 @@ export class MeshProviderQueryClient implements MeshProviderReadOnlyInterface {
       }
     });
   };
+  portfolio = async ({
+    address
+  }: {
+    address: string;
+  }): Promise<PortfolioResponse> => {
+    return this.client.queryContractSmart(this.contractAddress, {
+      portfolio: {
+        address
+      }
+    });
+  };
   validator = async ({
     address
   }: {
@@ -82,15 +165,136 @@ export class MeshProviderQueryClient implements MeshProviderReadOnlyInterface {
       }
     });
   };
+  utilization = async ({
+    limit,
+    startAfter
+  }: {
+    limit?: number;
+    startAfter?: string;
+  }): Promise<UtilizationResponse> => {
+    return this.client.queryContractSmart(this.contractAddress, {
+      utilization: {
+        limit,
+        start_after: startAfter
+      }
+    });
+  };
+  paused = async (): Promise<PausedResponse> => {
+    return this.client.queryContractSmart(this.contractAddress, {
+      paused: {}
+    });
+  };
+  withdrawAddress = async ({
+    address
+  }: {
+    address: string;
+  }): Promise<WithdrawAddressResponse> => {
+    return this.client.queryContractSmart(this.contractAddress, {
+      withdraw_address: {
+        address
+      }
+    });
+  };
+  channel = async (): Promise<ChannelResponse> => {
+    return this.client.queryContractSmart(this.contractAddress, {
+      channel: {}
+    });
+  };
+  stateDrift = async ({
+    limit,
+    startAfter
+  }: {
+    limit?: number;
+    startAfter?: string;
+  }): Promise<StateDriftResponse> => {
+    return this.client.queryContractSmart(this.contractAddress, {
+      state_drift: {
+        limit,
+        start_after: startAfter
+      }
+    });
+  };
+  retries = async ({
+    limit,
+    owner,
+    startAfter
+  }: {
+    limit?: number;
+    owner: string;
+    startAfter?: number;
+  }): Promise<RetriesResponse> => {
+    return this.client.queryContractSmart(this.contractAddress, {
+      retries: {
+        limit,
+        owner,
+        start_after: startAfter
+      }
+    });
+  };
+  slashingHistory = async ({
+    limit,
+    startAfter,
+    validator
+  }: {
+    limit?: number;
+    startAfter?: number;
+    validator: string;
+  }): Promise<SlashingHistoryResponse> => {
+    return this.client.queryContractSmart(this.contractAddress, {
+      slashing_history: {
+        limit,
+        start_after: startAfter,
+        validator
+      }
+    });
+  };
+  accountSlashes = async ({
+    address
+  }: {
+    address: string;
+  }): Promise<AccountSlashesResponse> => {
+    return this.client.queryContractSmart(this.contractAddress, {
+      account_slashes: {
+        address
+      }
+    });
+  };
+  votingPowerAtHeight = async ({
+    address,
+    height
+  }: {
+    address: string;
+    height?: number;
+  }): Promise<VotingPowerAtHeightResponse> => {
+    return this.client.queryContractSmart(this.contractAddress, {
+      voting_power_at_height: {
+        address,
+        height
+      }
+    });
+  };
+  totalPowerAtHeight = async ({
+    height
+  }: {
+    height?: number;
+  }): Promise<TotalPowerAtHeightResponse> => {
+    return this.client.queryContractSmart(this.contractAddress, {
+      total_power_at_height: {
+        height
+      }
+    });
+  };
 }
 export interface MeshProviderInterface extends MeshProviderReadOnlyInterface {
   contractAddress: string;
   sender: string;
   slash: ({
+    evidence,
     forceUnbond,
     percentage,
     validator
   }: {
+    evidence?: string;
     forceUnbond: boolean;
     percentage: Decimal;
     validator: string;
@@ -117,6 +321,60 @@ export interface MeshProviderInterface extends MeshProviderReadOnlyInterface {
   }: {
     validator: string;
   }, fee?: number | StdFee | "auto", memo?: string, funds?: Coin[]) => Promise<ExecuteResult>;
+  forceUnbondValidator: ({
+    validator
+  }: {
+    validator: string;
+  }, fee?: number | StdFee | "auto", memo?: string, funds?: Coin[]) => Promise<ExecuteResult>;
+  processSlash: ({
+    limit,
+    startAfter,
+    validator
+  }: {
+    limit?: number;
+    startAfter?: string;
+    validator: string;
+  }, fee?: number | StdFee | "auto", memo?: string, funds?: Coin[]) => Promise<ExecuteResult>;
+  updateConfig: (fee?: number | StdFee | "auto", memo?: string, funds?: Coin[]) => Promise<ExecuteResult>;
+  transferAdmin: ({
+    newAdmin
+  }: {
+    newAdmin: string;
+  }, fee?: number | StdFee | "auto", memo?: string, funds?: Coin[]) => Promise<ExecuteResult>;
+  acceptAdmin: (fee?: number | StdFee | "auto", memo?: string, funds?: Coin[]) => Promise<ExecuteResult>;
+  updateGuardian: ({
+    guardian
+  }: {
+    guardian?: string;
+  }, fee?: number | StdFee | "auto", memo?: string, funds?: Coin[]) => Promise<ExecuteResult>;
+  pause: ({
+    operations
+  }: {
+    operations: Operation[];
+  }, fee?: number | StdFee | "auto", memo?: string, funds?: Coin[]) => Promise<ExecuteResult>;
+  unpause: ({
+    operations
+  }: {
+    operations: Operation[];
+  }, fee?: number | StdFee | "auto", memo?: string, funds?: Coin[]) => Promise<ExecuteResult>;
+  setWithdrawAddress: ({
+    address
+  }: {
+    address: string;
+  }, fee?: number | StdFee | "auto", memo?: string, funds?: Coin[]) => Promise<ExecuteResult>;
+  exitStake: (fee?: number | StdFee | "auto", memo?: string, funds?: Coin[]) => Promise<ExecuteResult>;
+  refreshValidators: (fee?: number | StdFee | "auto", memo?: string, funds?: Coin[]) => Promise<ExecuteResult>;
+  syncState: (fee?: number | StdFee | "auto", memo?: string, funds?: Coin[]) => Promise<ExecuteResult>;
+  correctStateDrift: ({
+    validator
+  }: {
+    validator: string;
+  }, fee?: number | StdFee | "auto", memo?: string, funds?: Coin[]) => Promise<ExecuteResult>;
+  retryPending: ({
+    key
+  }: {
+    key: number;
+  }, fee?: number | StdFee | "auto", memo?: string, funds?: Coin[]) => Promise<ExecuteResult>;
 }
 export class MeshProviderClient extends MeshProviderQueryClient implements MeshProviderInterface {
   client: SigningCosmWasmClient;
@@ -133,19 +391,36 @@ export class MeshProviderClient extends MeshProviderQueryClient implements MeshP
     this.unstake = this.unstake.bind(this);
     this.unbond = this.unbond.bind(this);
     this.claimRewards = this.claimRewards.bind(this);
+    this.forceUnbondValidator = this.forceUnbondValidator.bind(this);
+    this.processSlash = this.processSlash.bind(this);
+    this.updateConfig = this.updateConfig.bind(this);
+    this.transferAdmin = this.transferAdmin.bind(this);
+    this.acceptAdmin = this.acceptAdmin.bind(this);
+    this.updateGuardian = this.updateGuardian.bind(this);
+    this.pause = this.pause.bind(this);
+    this.unpause = this.unpause.bind(this);
+    this.setWithdrawAddress = this.setWithdrawAddress.bind(this);
+    this.exitStake = this.exitStake.bind(this);
+    this.refreshValidators = this.refreshValidators.bind(this);
+    this.syncState = this.syncState.bind(this);
+    this.correctStateDrift = this.correctStateDrift.bind(this);
+    this.retryPending = this.retryPending.bind(this);
   }
 
   slash = async ({
+    evidence,
     forceUnbond,
     percentage,
     validator
   }: {
+    evidence?: string;
     forceUnbond: boolean;
     percentage: Decimal;
     validator: string;
   }, fee: number | StdFee | "auto" = "auto", memo?: string, funds?: Coin[]): Promise<ExecuteResult> => {
     return await this.client.execute(this.sender, this.contractAddress, {
       slash: {
+        evidence,
         force_unbond: forceUnbond,
         percentage,
         validator
@@ -199,4 +474,134 @@ export class MeshProviderClient extends MeshProviderQueryClient implements MeshP
       }
     }, fee, memo, funds);
   };
+  forceUnbondValidator = async ({
+    validator
+  }: {
+    validator: string;
+  }, fee: number | StdFee | "auto" = "auto", memo?: string, funds?: Coin[]): Promise<ExecuteResult> => {
+    return await this.client.execute(this.sender, this.contractAddress, {
+      force_unbond_validator: {
+        validator
+      }
+    }, fee, memo, funds);
+  };
+  processSlash = async ({
+    limit,
+    startAfter,
+    validator
+  }: {
+    limit?: number;
+    startAfter?: string;
+    validator: string;
+  }, fee: number | StdFee | "auto" = "auto", memo?: string, funds?: Coin[]): Promise<ExecuteResult> => {
+    return await this.client.execute(this.sender, this.contractAddress, {
+      process_slash: {
+        limit,
+        start_after: startAfter,
+        validator
+      }
+    }, fee, memo, funds);
+  };
+  updateConfig = async (fee: number | StdFee | "auto" = "auto", memo?: string, funds?: Coin[]): Promise<ExecuteResult> => {
+    return await this.client.execute(this.sender, this.contractAddress, {
+      update_config: {}
+    }, fee, memo, funds);
+  };
+  transferAdmin = async ({
+    newAdmin
+  }: {
+    newAdmin: string;
+  }, fee: number | StdFee | "auto" = "auto", memo?: string, funds?: Coin[]): Promise<ExecuteResult> => {
+    return await this.client.execute(this.sender, this.contractAddress, {
+      transfer_admin: {
+        new_admin: newAdmin
+      }
+    }, fee, memo, funds);
+  };
+  acceptAdmin = async (fee: number | StdFee | "auto" = "auto", memo?: string, funds?: Coin[]): Promise<ExecuteResult> => {
+    return await this.client.execute(this.sender, this.contractAddress, {
+      accept_admin: {}
+    }, fee, memo, funds);
+  };
+  updateGuardian = async ({
+    guardian
+  }: {
+    guardian?: string;
+  }, fee: number | StdFee | "auto" = "auto", memo?: string, funds?: Coin[]): Promise<ExecuteResult> => {
+    return await this.client.execute(this.sender, this.contractAddress, {
+      update_guardian: {
+        guardian
+      }
+    }, fee, memo, funds);
+  };
+  pause = async ({
+    operations
+  }: {
+    operations: Operation[];
+  }, fee: number | StdFee | "auto" = "auto", memo?: string, funds?: Coin[]): Promise<ExecuteResult> => {
+    return await this.client.execute(this.sender, this.contractAddress, {
+      pause: {
+        operations
+      }
+    }, fee, memo, funds);
+  };
+  unpause = async ({
+    operations
+  }: {
+    operations: Operation[];
+  }, fee: number | StdFee | "auto" = "auto", memo?: string, funds?: Coin[]): Promise<ExecuteResult> => {
+    return await this.client.execute(this.sender, this.contractAddress, {
+      unpause: {
+        operations
+      }
+    }, fee, memo, funds);
+  };
+  setWithdrawAddress = async ({
+    address
+  }: {
+    address: string;
+  }, fee: number | StdFee | "auto" = "auto", memo?: string, funds?: Coin[]): Promise<ExecuteResult> => {
+    return await this.client.execute(this.sender, this.contractAddress, {
+      set_withdraw_address: {
+        address
+      }
+    }, fee, memo, funds);
+  };
+  exitStake = async (fee: number | StdFee | "auto" = "auto", memo?: string, funds?: Coin[]): Promise<ExecuteResult> => {
+    return await this.client.execute(this.sender, this.contractAddress, {
+      exit_stake: {}
+    }, fee, memo, funds);
+  };
+  refreshValidators = async (fee: number | StdFee | "auto" = "auto", memo?: string, funds?: Coin[]): Promise<ExecuteResult> => {
+    return await this.client.execute(this.sender, this.contractAddress, {
+      refresh_validators: {}
+    }, fee, memo, funds);
+  };
+  syncState = async (fee: number | StdFee | "auto" = "auto", memo?: string, funds?: Coin[]): Promise<ExecuteResult> => {
+    return await this.client.execute(this.sender, this.contractAddress, {
+      sync_state: {}
+    }, fee, memo, funds);
+  };
+  correctStateDrift = async ({
+    validator
+  }: {
+    validator: string;
+  }, fee: number | StdFee | "auto" = "auto", memo?: string, funds?: Coin[]): Promise<ExecuteResult> => {
+    return await this.client.execute(this.sender, this.contractAddress, {
+      correct_state_drift: {
+        validator
+      }
+    }, fee, memo, funds);
+  };
+  retryPending = async ({
+    key
+  }: {
+    key: number;
+  }, fee: number | StdFee | "auto" = "auto", memo?: string, funds?: Coin[]): Promise<ExecuteResult> => {
+    return await this.client.execute(this.sender, this.contractAddress, {
+      retry_pending: {
+        key
+      }
+    }, fee, memo, funds);
+  };
 }
\ No newline at end of file
@@ -5,23 +5,41 @@
 */
 
 export type Binary = string;
+export type Uint128 = string;
+export type Decimal = string;
 export interface InstantiateMsg {
+  admin?: string | null;
+  channel_recovery_period?: number | null;
   consumer: ConsumerInfo;
+  guardian?: string | null;
   lockup: string;
   packet_lifetime?: number | null;
+  retry_policy?: RetryPolicy | null;
   rewards_ibc_denom: string;
   slasher: SlasherInfo;
+  stake_caps?: StakeCaps | null;
   unbonding_period: number;
 }
 export interface ConsumerInfo {
   connection_id: string;
 }
+export interface RetryPolicy {
+  backoff_blocks: number;
+  max_attempts: number;
+}
 export interface SlasherInfo {
   code_id: number;
   msg: Binary;
 }
+export interface StakeCaps {
+  max_total_stake?: Uint128 | null;
+  max_validator_share?: Decimal | null;
+  max_validator_stake?: Uint128 | null;
+  share_cap_min_total: Uint128;
+}
 export type ExecuteMsg = {
   slash: {
+    evidence?: string | null;
     force_unbond: boolean;
     percentage: Decimal;
     validator: string;
@@ -43,15 +61,75 @@ export type ExecuteMsg = {
   claim_rewards: {
     validator: string;
   };
+} | {
+  force_unbond_validator: {
+    validator: string;
+  };
+} | {
+  process_slash: {
+    limit?: number | null;
+    start_after?: string | null;
+    validator: string;
+  };
+} | {
+  update_config: ConfigUpdate;
+} | {
+  transfer_admin: {
+    new_admin: string;
+  };
+} | {
+  accept_admin: {};
+} | {
+  update_guardian: {
+    guardian?: string | null;
+  };
+} | {
+  pause: {
+    operations: Operation[];
+  };
+} | {
+  unpause: {
+    operations: Operation[];
+  };
+} | {
+  set_withdraw_address: {
+    address: string;
+  };
+} | {
+  exit_stake: {};
+} | {
+  refresh_validators: {};
+} | {
+  sync_state: {};
+} | {
+  correct_state_drift: {
+    validator: string;
+  };
+} | {
+  retry_pending: {
+    key: number;
+  };
 };
-export type Decimal = string;
-export type Uint128 = string;
+export type Operation = "staking" | "unstaking" | "rewards" | "ibc_receive";
+export interface ConfigUpdate {
+  channel_recovery_period?: number | null;
+  connection_id?: string | null;
+  packet_lifetime?: number | null;
+  retry_policy?: RetryPolicy | null;
+  rewards_ibc_denom?: string | null;
+  stake_caps?: StakeCaps | null;
+  unbonding_period?: number | null;
+}
 export type QueryMsg = {
   config: {};
 } | {
   account: {
     address: string;
   };
+} | {
+  portfolio: {
+    address: string;
+  };
 } | {
   validator: {
     address: string;
@@ -61,6 +139,49 @@ export type QueryMsg = {
     limit?: number | null;
     start_after?: string | null;
   };
+} | {
+  utilization: {
+    limit?: number | null;
+    start_after?: string | null;
+  };
+} | {
+  paused: {};
+} | {
+  withdraw_address: {
+    address: string;
+  };
+} | {
+  channel: {};
+} | {
+  state_drift: {
+    limit?: number | null;
+    start_after?: string | null;
+  };
+} | {
+  retries: {
+    limit?: number | null;
+    owner: string;
+    start_after?: number | null;
+  };
+} | {
+  slashing_history: {
+    limit?: number | null;
+    start_after?: number | null;
+    validator: string;
+  };
+} | {
+  account_slashes: {
+    address: string;
+  };
+} | {
+  voting_power_at_height: {
+    address: string;
+    height?: number | null;
+  };
+} | {
+  total_power_at_height: {
+    height?: number | null;
+  };
 };
 export interface AccountResponse {
   staked: StakeInfo[];
@@ -70,9 +191,34 @@ export interface StakeInfo {
   tokens: Uint128;
   validator: string;
 }
+export interface AccountSlashesResponse {
+  slashes: AccountSlash[];
+}
+export interface AccountSlash {
+  amount: Uint128;
+  height: number;
+  id: number;
+  percentage: Decimal;
+  validator: string;
+}
+export type Timestamp = Uint64;
+export type Uint64 = string;
+export interface ChannelResponse {
+  channel?: string | null;
+  closed_at?: Timestamp | null;
+  exit_at?: Timestamp | null;
+}
 export interface ConfigResponse {
+  admin?: string | null;
+  channel_recovery_period: number;
   consumer: ConsumerInfo;
+  lockup: string;
+  packet_lifetime: number;
+  pending_admin?: string | null;
+  retry_policy: RetryPolicy;
+  rewards_ibc_denom: string;
   slasher?: string | null;
+  unbonding_period: number;
 }
 export type ValStatus = "active" | "removed" | "tombstoned";
 export interface ListValidatorsResponse {
@@ -80,7 +226,112 @@ export interface ListValidatorsResponse {
 }
 export interface ValidatorResponse {
   address: string;
+  metadata?: ValidatorMetadata | null;
   multiplier: Decimal;
   status: ValStatus;
   tokens: Uint128;
+}
+export interface ValidatorMetadata {
+  address: string;
+  commission: Decimal;
+  jailed: boolean;
+  max_change_rate: Decimal;
+  max_commission: Decimal;
+}
+export interface PausedResponse {
+  guardian?: string | null;
+  paused: Operation[];
+}
+export type Expiration = {
+  at_height: number;
+} | {
+  at_time: Timestamp;
+} | {
+  never: {};
+};
+export interface PortfolioResponse {
+  lien: Uint128;
+  positions: Position[];
+  unbonding: Claim[];
+}
+export interface Position {
+  pending_rewards: Uint128;
+  shares: Uint128;
+  slashed: Uint128;
+  status: ValStatus;
+  tokens: Uint128;
+  validator: string;
+}
+export interface Claim {
+  amount: Uint128;
+  release_at: Expiration;
+}
+export type ProviderMsg = {
+  list_validators: {};
+} | {
+  stake: {
+    amount: Uint128;
+    key: string;
+    validator: string;
+  };
+} | {
+  unstake: {
+    amount: Uint128;
+    key: string;
+    validator: string;
+  };
+} | {
+  sync_state: {};
+};
+export type RetryStatus = "pending" | "resent" | "succeeded" | "failed";
+export interface RetriesResponse {
+  retries: RetryInfo[];
+}
+export interface RetryInfo {
+  attempts: number;
+  key: number;
+  packet: ProviderMsg;
+  retry_after: number;
+  status: RetryStatus;
+}
+export interface SlashingHistoryResponse {
+  slashes: SlashInfo[];
+}
+export interface SlashInfo {
+  evidence?: string | null;
+  height: number;
+  id: number;
+  percentage: Decimal;
+  time: Timestamp;
+  tombstoned: boolean;
+}
+export interface StateDriftResponse {
+  last_sync?: Timestamp | null;
+  validators: ValidatorDrift[];
+}
+export interface ValidatorDrift {
+  address: string;
+  consumer_stake: Uint128;
+  provider_stake: Uint128;
+}
+export interface TotalPowerAtHeightResponse {
+  height: number;
+  power: Uint128;
+}
+export interface UtilizationResponse {
+  caps: StakeCaps;
+  total_stake: Uint128;
+  validators: ValidatorUtilization[];
+}
+export interface ValidatorUtilization {
+  address: string;
+  share: Decimal;
+  stake: Uint128;
+}
+export interface VotingPowerAtHeightResponse {
+  height: number;
+  power: Uint128;
+}
+export interface WithdrawAddressResponse {
+  withdraw_address: string;
 }
\ No newline at end of file